@@ -0,0 +1,61 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use nostr::{Event as NostrEvent, Keys};
+use prediction_market_event::{
+    information::Information,
+    nostr_event_types::{EventPayoutAttestation, NostrEventUtils},
+    Event, EventPayout,
+};
+
+const NOSTR_EVENT_COUNT: usize = 8192;
+
+/// Attestations to different events, each signed by oracle_keys or a new oracle if none.
+fn attestations(oracle_keys: Option<&Keys>) -> Vec<NostrEvent> {
+    (0..NOSTR_EVENT_COUNT)
+        .map(|_| {
+            let event = Event::new_with_random_nonce(2, 10, Information::None);
+            let event_payout = EventPayout::new(&event, vec![10, 0]).unwrap();
+            let builder =
+                EventPayoutAttestation::create_nostr_event_builder(&event_payout).unwrap();
+            match oracle_keys {
+                Some(keys) => builder.to_event(keys).unwrap(),
+                None => builder.to_event(&Keys::generate()).unwrap(),
+            }
+        })
+        .collect()
+}
+
+fn bulk_interpret(c: &mut Criterion) {
+    let oracle_keys = Keys::generate();
+    // the bulk benchmark depends on the features, run it with and without `rayon`
+    let bulk = if cfg!(feature = "rayon") {
+        "bulk_rayon"
+    } else {
+        "bulk_sequential"
+    };
+
+    for (name, nostr_events) in [
+        ("interpret_attestations_many_oracles", attestations(None)),
+        (
+            "interpret_attestations_one_oracle",
+            attestations(Some(&oracle_keys)),
+        ),
+    ] {
+        let mut group = c.benchmark_group(name);
+        group.sample_size(10);
+        group.bench_function("per_event", |b| {
+            b.iter(|| {
+                nostr_events
+                    .iter()
+                    .map(EventPayoutAttestation::interpret_nostr_event_enveloped)
+                    .collect::<Vec<_>>()
+            })
+        });
+        group.bench_function(bulk, |b| {
+            b.iter(|| EventPayoutAttestation::interpret_nostr_events_bulk(&nostr_events))
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bulk_interpret);
+criterion_main!(benches);
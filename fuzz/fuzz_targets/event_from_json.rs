@@ -0,0 +1,26 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use prediction_market_event::{information::Information, Event, EventHashHex};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(json) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok(event) = Event::try_from_json_str(json) else {
+        return;
+    };
+
+    // validation must never panic, whatever the parsed values are
+    let _ = event.validate(Information::ALL_VARIANT_IDS);
+
+    // parsed events must survive a round trip unchanged
+    let event_json = event.try_to_json_string().unwrap();
+    let event_from_json = Event::try_from_json_str(&event_json).unwrap();
+    assert_eq!(event, event_from_json);
+
+    // hash is stable across the round trip and always well formed
+    let hash_hex = event.hash_hex().unwrap();
+    assert!(EventHashHex::is_valid_format(&hash_hex.0));
+    assert_eq!(hash_hex, event_from_json.hash_hex().unwrap());
+});
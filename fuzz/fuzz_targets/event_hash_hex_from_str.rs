@@ -0,0 +1,27 @@
+#![no_main]
+
+use std::str::FromStr;
+
+use libfuzzer_sys::fuzz_target;
+use prediction_market_event::{nostr_event_types::EventPointer, EventHashHex};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    let res = EventHashHex::from_str(s);
+    let event_pointer = EventPointer::from_bech32(s);
+    assert_eq!(
+        res.is_ok(),
+        EventHashHex::is_valid_format(s) || event_pointer.is_ok()
+    );
+
+    if let Ok(event_hash_hex) = res {
+        assert!(EventHashHex::is_valid_format(&event_hash_hex.0));
+        match event_pointer {
+            Ok(event_pointer) => assert_eq!(event_hash_hex, event_pointer.event_hash_hex),
+            Err(_) => assert_eq!(event_hash_hex.to_string(), s),
+        }
+    }
+});
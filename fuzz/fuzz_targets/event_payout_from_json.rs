@@ -0,0 +1,38 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use prediction_market_event::{information::Information, Event, EventPayout, PayoutUnit};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(json) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok(event_payout) = EventPayout::try_from_json_str(json) else {
+        return;
+    };
+
+    // parsed payouts must survive a round trip unchanged
+    let event_payout_json = event_payout.try_to_json_string().unwrap();
+    let event_payout_from_json = EventPayout::try_from_json_str(&event_payout_json).unwrap();
+    assert_eq!(event_payout, event_payout_from_json);
+
+    let Ok(outcome_count) = u16::try_from(event_payout.units_per_outcome.len()) else {
+        return;
+    };
+    let units_sum = event_payout
+        .units_per_outcome
+        .iter()
+        .try_fold(0 as PayoutUnit, |sum, u| sum.checked_add(*u));
+    let event = Event::new_with_random_nonce(
+        outcome_count,
+        units_sum.unwrap_or(PayoutUnit::MAX),
+        Information::None,
+    );
+
+    // the parsed payout can not reference a freshly created event
+    assert!(event_payout.validate(&event).is_err());
+
+    // the same units bound to that event validate exactly when their sum does not overflow
+    let rebound = EventPayout::new(&event, event_payout.units_per_outcome.clone()).unwrap();
+    assert_eq!(rebound.validate(&event).is_ok(), units_sum.is_some());
+});
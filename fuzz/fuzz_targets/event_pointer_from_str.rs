@@ -0,0 +1,18 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use prediction_market_event::{nostr_event_types::EventPointer, EventHashHex};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok(pointer) = s.parse::<EventPointer>() else {
+        return;
+    };
+
+    // decode succeeded => the pointer re-encodes and decodes to the same value
+    assert!(EventHashHex::is_valid_format(&pointer.event_hash_hex.0));
+    let encoded = pointer.to_bech32().unwrap();
+    assert_eq!(encoded.parse::<EventPointer>().unwrap(), pointer);
+});
@@ -0,0 +1,24 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use prediction_market_event::fuzzing::{byte_array_to_hex_string, hex_string_to_byte_array};
+
+fuzz_target!(|data: &[u8]| {
+    // decoding arbitrary bytes as hex must only ever fail, never panic
+    let Ok(hex_string) = std::str::from_utf8(data) else {
+        let bytes = byte_array_to_hex_string(data);
+        assert_eq!(hex_string_to_byte_array(&bytes).unwrap(), data);
+        return;
+    };
+
+    let Ok(byte_array) = hex_string_to_byte_array(hex_string) else {
+        return;
+    };
+
+    // successful decoding means the input was lower or upper case hex of the decoded bytes
+    assert_eq!(byte_array.len() * 2, hex_string.len());
+    assert_eq!(
+        byte_array_to_hex_string(&byte_array),
+        hex_string.to_ascii_lowercase()
+    );
+});
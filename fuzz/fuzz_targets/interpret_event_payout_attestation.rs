@@ -0,0 +1,35 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use prediction_market_event::{
+    nostr::{Event as NostrEvent, JsonUtil},
+    nostr_event_types::{
+        read_event_hash_tag, EventHashTagMode, EventPayoutAttestation, NostrEventUtils,
+    },
+    EventHashHex, PayoutUnit,
+};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(json) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok((nostr_public_key_hex, event_payout)) =
+        EventPayoutAttestation::interpret_nostr_event_json(json)
+    else {
+        return;
+    };
+
+    // interpret succeeded => the signer, hash tag and payout are what the note actually carries
+    let nostr_event = NostrEvent::from_json(json).unwrap();
+    assert!(nostr_event.verify().is_ok());
+    assert_eq!(nostr_public_key_hex.0, nostr_event.pubkey.to_hex());
+    assert!(EventHashHex::is_valid_format(
+        &event_payout.event_hash_hex.0
+    ));
+    assert_eq!(
+        read_event_hash_tag(&nostr_event, EventHashTagMode::Transition).unwrap(),
+        event_payout.event_hash_hex
+    );
+    let units_per_outcome: Vec<PayoutUnit> = serde_json::from_str(&nostr_event.content).unwrap();
+    assert_eq!(units_per_outcome, event_payout.units_per_outcome);
+});
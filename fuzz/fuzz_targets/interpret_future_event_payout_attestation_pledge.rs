@@ -0,0 +1,32 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use prediction_market_event::{
+    nostr::{Event as NostrEvent, JsonUtil},
+    nostr_event_types::{
+        read_event_hash_tag, EventHashTagMode, FutureEventPayoutAttestationPledge, NostrEventUtils,
+    },
+    EventHashHex,
+};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(json) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok((nostr_public_key_hex, pledge)) =
+        FutureEventPayoutAttestationPledge::interpret_nostr_event_json(json)
+    else {
+        return;
+    };
+
+    // interpret succeeded => the signer and hash tag are what the note actually carries
+    let nostr_event = NostrEvent::from_json(json).unwrap();
+    assert!(nostr_event.verify().is_ok());
+    assert_eq!(nostr_public_key_hex.0, nostr_event.pubkey.to_hex());
+    assert!(EventHashHex::is_valid_format(&pledge.event_hash_hex.0));
+    assert_eq!(
+        read_event_hash_tag(&nostr_event, EventHashTagMode::Transition).unwrap(),
+        pledge.event_hash_hex
+    );
+    assert_eq!(pledge.terms.is_none(), nostr_event.content.is_empty());
+});
@@ -0,0 +1,26 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use prediction_market_event::{
+    information::Information,
+    nostr::{Event as NostrEvent, JsonUtil},
+    nostr_event_types::{read_event_hash_tag, EventHashTagMode, NewEvent, NostrEventUtils},
+};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(json) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok(event) = NewEvent::interpret_nostr_event_json(json) else {
+        return;
+    };
+
+    // interpret succeeded => the note is signed and its hash tag equals the recomputed hash
+    let nostr_event = NostrEvent::from_json(json).unwrap();
+    assert!(nostr_event.verify().is_ok());
+    assert_eq!(nostr_event.kind, NewEvent::KIND);
+    let hash_tag = read_event_hash_tag(&nostr_event, EventHashTagMode::Transition).unwrap();
+    assert_eq!(hash_tag, event.hash_hex().unwrap());
+
+    let _ = event.validate(Information::ALL_VARIANT_IDS);
+});
@@ -0,0 +1,129 @@
+#![no_main]
+
+//! Signs arbitrary notes with a fixed key so that the interpreters get past signature
+//! verification and the content and tag handling is exercised.
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use prediction_market_event::{
+    nostr::{EventBuilder as NostrEventBuilder, JsonUtil, Keys, Tag, TagKind},
+    nostr_event_types::{
+        EventHashTagMode, EventPayoutAttestation, FutureEventPayoutAttestationPledge,
+        InterpretOptions, NewEvent, NostrEventUtils, EVENT_HASH_LABEL_NAMESPACE,
+    },
+    Event, EventHashHex,
+};
+
+const SECRET_KEY: &str = "0000000000000000000000000000000000000000000000000000000000000001";
+
+#[derive(Debug, Arbitrary)]
+enum TargetKind {
+    NewEvent,
+    FutureEventPayoutAttestationPledge,
+    EventPayoutAttestation,
+}
+
+#[derive(Debug, Arbitrary)]
+enum InputTag {
+    Hashtag(String),
+    Label(String),
+    /// Hashtag containing the hash of the content, if it parses as an [Event].
+    ContentHashHashtag,
+    /// Label containing the hash of the content, if it parses as an [Event].
+    ContentHashLabel,
+    Raw(Vec<String>),
+}
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    kind: TargetKind,
+    dedicated: bool,
+    content: String,
+    tags: Vec<InputTag>,
+}
+
+fuzz_target!(|input: Input| {
+    let keys = Keys::parse(SECRET_KEY).unwrap();
+
+    let content_hash = Event::try_from_json_str(&input.content)
+        .map(|event| event.hash_hex().unwrap().0)
+        .unwrap_or_default();
+    let label = |value: String| {
+        Tag::custom(
+            TagKind::custom("l"),
+            [value, EVENT_HASH_LABEL_NAMESPACE.to_owned()],
+        )
+    };
+    let tags: Vec<Tag> = input
+        .tags
+        .into_iter()
+        .filter_map(|t| match t {
+            InputTag::Hashtag(h) => Some(Tag::hashtag(h)),
+            InputTag::Label(l) => Some(label(l)),
+            InputTag::ContentHashHashtag => Some(Tag::hashtag(content_hash.clone())),
+            InputTag::ContentHashLabel => Some(label(content_hash.clone())),
+            InputTag::Raw(r) => Tag::parse(&r).ok(),
+        })
+        .collect();
+
+    let kind = match input.kind {
+        TargetKind::NewEvent => NewEvent::KIND,
+        TargetKind::FutureEventPayoutAttestationPledge => FutureEventPayoutAttestationPledge::KIND,
+        TargetKind::EventPayoutAttestation => EventPayoutAttestation::KIND,
+    };
+    let Ok(nostr_event) = NostrEventBuilder::new(kind, input.content, tags).to_event(&keys) else {
+        return;
+    };
+    let json = nostr_event.as_json();
+    let mut options = InterpretOptions::default();
+    if input.dedicated {
+        options.event_hash_tag_mode = EventHashTagMode::Dedicated;
+    }
+
+    let event_hash_hex = match input.kind {
+        TargetKind::NewEvent => {
+            let Ok(event) = NewEvent::interpret_nostr_event_json_with_options(&json, &options)
+            else {
+                return;
+            };
+            event.hash_hex().unwrap()
+        }
+        TargetKind::FutureEventPayoutAttestationPledge => {
+            let Ok((pk, pledge)) =
+                FutureEventPayoutAttestationPledge::interpret_nostr_event_json_with_options(
+                    &json, &options,
+                )
+            else {
+                return;
+            };
+            assert_eq!(pk.0, keys.public_key().to_hex());
+            pledge.event_hash_hex
+        }
+        TargetKind::EventPayoutAttestation => {
+            let Ok((pk, event_payout)) =
+                EventPayoutAttestation::interpret_nostr_event_json_with_options(&json, &options)
+            else {
+                return;
+            };
+            assert_eq!(pk.0, keys.public_key().to_hex());
+            event_payout.event_hash_hex
+        }
+    };
+
+    // interpret succeeded => every hash reference on the note agrees with the interpreted hash
+    let mut references = 0;
+    for tag in nostr_event.tags.iter() {
+        match tag.as_slice() {
+            [k, v, n, ..] if k == "l" && n == EVENT_HASH_LABEL_NAMESPACE => {
+                assert_eq!(v.to_ascii_lowercase(), event_hash_hex.0);
+                references += 1;
+            }
+            [k, v, ..] if k == "t" && EventHashHex::is_valid_format(v) => {
+                assert_eq!(v.to_ascii_lowercase(), event_hash_hex.0);
+                references += 1;
+            }
+            _ => {}
+        }
+    }
+    assert!(references > 0);
+});
@@ -0,0 +1,222 @@
+//! Aggregation of [crate::nostr_event_types::EventPayoutAttestation]s from multiple oracles into a single [EventPayout].
+
+use std::collections::{HashMap, HashSet};
+
+use nostr::event::Event as NostrEvent;
+
+use crate::{
+    nostr_event_types::{resolve_signed_attestation_history, NostrPublicKeyHex},
+    Error, Event, EventPayout, PayoutUnit,
+};
+
+/// How the attestations of the accepted oracles are combined into one [EventPayout].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AggregationRule {
+    /// At least m oracles attested identical payouts, and no other payout was attested by m oracles.
+    MOfNIdentical { m: usize },
+
+    /// Median of the units attested for every outcome, scaled to [Event::units_to_payout].
+    /// Requires at least min_attestations valid attestations.
+    ///
+    /// For an even number of attestations the lower median is used. Units lost by scaling are given to the outcomes with the largest remainders, ties go to the lower outcome.
+    PerOutcomeMedian { min_attestations: usize },
+
+    /// The payout with the highest total weight wins, if that weight is at least min_weight and no other payout has the same weight.
+    /// Every accepted oracle needs an entry in weights.
+    WeightedVote {
+        weights: HashMap<NostrPublicKeyHex, u64>,
+        min_weight: u64,
+    },
+}
+
+/// Result of [aggregate_event_payout].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AggregationReport {
+    /// [None] if the attestations do not satisfy the [AggregationRule].
+    pub event_payout: Option<EventPayout>,
+
+    /// Oracles that attested [AggregationReport::event_payout].
+    /// Empty if there is no [AggregationReport::event_payout].
+    pub agreeing: Vec<NostrPublicKeyHex>,
+
+    /// Oracles that attested a different valid payout.
+    /// If there is no [AggregationReport::event_payout], every oracle with a valid attestation.
+    pub dissenting: Vec<NostrPublicKeyHex>,
+
+    /// Oracles without an effective attestation, including oracles that retracted theirs.
+    pub missing: Vec<NostrPublicKeyHex>,
+
+    /// Oracles whose effective attestation failed [EventPayout::validate].
+    pub invalid: Vec<InvalidAttestation>,
+}
+
+/// Entry of [AggregationReport::invalid].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InvalidAttestation {
+    pub oracle: NostrPublicKeyHex,
+    pub event_payout: EventPayout,
+
+    /// Why [EventPayout::validate] failed.
+    pub reason: String,
+}
+
+/// Aggregates the attestations of oracles for [Event] into one [EventPayout] using [AggregationRule].
+///
+/// Accepts any number of [NostrEvent]s. The effective payout of every oracle is resolved with [crate::nostr_event_types::resolve_attestation_history], so [crate::nostr_event_types::EventPayoutAttestationCorrection]s are respected.
+/// Nostr events signed by other keys, about other events or failing interpretation are ignored.
+/// Every effective payout is validated against [Event] and reported in [AggregationReport::invalid] if it fails.
+///
+/// Fails if [Event] can not be hashed or [AggregationRule] is misconfigured.
+pub fn aggregate_event_payout(
+    event: &Event,
+    oracles: &[NostrPublicKeyHex],
+    nostr_events: &[NostrEvent],
+    rule: &AggregationRule,
+) -> Result<AggregationReport, Error> {
+    let event_hash_hex = event.hash_hex()?;
+
+    let mut seen = HashSet::new();
+    let oracles: Vec<&NostrPublicKeyHex> = oracles.iter().filter(|o| seen.insert(*o)).collect();
+
+    // every nostr event is interpreted once, in the history of its signer
+    let mut nostr_events_by_signer: HashMap<String, Vec<&NostrEvent>> = HashMap::new();
+    for nostr_event in nostr_events {
+        nostr_events_by_signer
+            .entry(nostr_event.pubkey.to_hex())
+            .or_default()
+            .push(nostr_event);
+    }
+
+    let mut attested: Vec<(&NostrPublicKeyHex, EventPayout)> = Vec::new();
+    let mut missing = Vec::new();
+    let mut invalid = Vec::new();
+    for oracle in oracles.iter() {
+        let signed = nostr_events_by_signer.remove(&oracle.0).unwrap_or_default();
+        let history = resolve_signed_attestation_history(signed, &event_hash_hex);
+        match history.effective_event_payout {
+            None => missing.push((*oracle).clone()),
+            Some(event_payout) => match event_payout.validate(event) {
+                Ok(()) => attested.push((oracle, event_payout)),
+                Err(e) => invalid.push(InvalidAttestation {
+                    oracle: (*oracle).clone(),
+                    event_payout,
+                    reason: e.to_string(),
+                }),
+            },
+        }
+    }
+
+    let event_payout = match rule {
+        AggregationRule::MOfNIdentical { m } => {
+            if *m == 0 {
+                return Err(Error::Validation("m must be greater than 0".to_string()));
+            }
+            let mut counts: Vec<(&EventPayout, usize)> = Vec::new();
+            for (_, event_payout) in attested.iter() {
+                match counts.iter_mut().find(|(p, _)| *p == event_payout) {
+                    Some((_, count)) => *count += 1,
+                    None => counts.push((event_payout, 1)),
+                }
+            }
+            let mut reached = counts.into_iter().filter(|(_, count)| count >= m);
+            match (reached.next(), reached.next()) {
+                (Some((event_payout, _)), None) => Some(event_payout.clone()),
+                _ => None,
+            }
+        }
+        AggregationRule::PerOutcomeMedian { min_attestations } => {
+            if *min_attestations == 0 {
+                return Err(Error::Validation(
+                    "min attestations must be greater than 0".to_string(),
+                ));
+            }
+            if attested.len() < *min_attestations {
+                None
+            } else {
+                let medians: Vec<PayoutUnit> = (0..usize::from(event.outcome_count))
+                    .map(|outcome| {
+                        let mut units: Vec<PayoutUnit> = attested
+                            .iter()
+                            .map(|(_, p)| p.units_per_outcome[outcome])
+                            .collect();
+                        units.sort_unstable();
+                        units[(units.len() - 1) / 2]
+                    })
+                    .collect();
+                scale_units(&medians, event.units_to_payout).map(|units_per_outcome| EventPayout {
+                    event_hash_hex: event_hash_hex.clone(),
+                    units_per_outcome,
+                })
+            }
+        }
+        AggregationRule::WeightedVote {
+            weights,
+            min_weight,
+        } => {
+            if let Some(oracle) = oracles.iter().find(|o| !weights.contains_key(**o)) {
+                return Err(Error::Validation(format!("oracle {oracle} has no weight")));
+            }
+            let mut totals: Vec<(&EventPayout, u64)> = Vec::new();
+            for (oracle, event_payout) in attested.iter() {
+                let weight = weights[*oracle];
+                match totals.iter_mut().find(|(p, _)| *p == event_payout) {
+                    Some((_, total)) => *total = total.saturating_add(weight),
+                    None => totals.push((event_payout, weight)),
+                }
+            }
+            totals.sort_by_key(|(_, total)| std::cmp::Reverse(*total));
+            match totals.as_slice() {
+                [(event_payout, total), rest @ ..]
+                    if total >= min_weight && rest.first().is_none_or(|(_, t)| t < total) =>
+                {
+                    Some((*event_payout).clone())
+                }
+                _ => None,
+            }
+        }
+    };
+
+    let mut agreeing = Vec::new();
+    let mut dissenting = Vec::new();
+    for (oracle, p) in attested {
+        if event_payout.as_ref() == Some(&p) {
+            agreeing.push(oracle.clone());
+        } else {
+            dissenting.push(oracle.clone());
+        }
+    }
+
+    Ok(AggregationReport {
+        event_payout,
+        agreeing,
+        dissenting,
+        missing,
+        invalid,
+    })
+}
+
+/// Scales units so that they sum up to total, using the largest remainder method.
+/// Returns [None] if units sum up to 0.
+fn scale_units(units: &[PayoutUnit], total: PayoutUnit) -> Option<Vec<PayoutUnit>> {
+    let sum: u128 = units.iter().map(|u| u128::from(*u)).sum();
+    if sum == 0 {
+        return None;
+    }
+    let total = u128::from(total);
+
+    let mut scaled: Vec<u128> = Vec::with_capacity(units.len());
+    let mut remainders: Vec<(u128, usize)> = Vec::with_capacity(units.len());
+    for (i, u) in units.iter().enumerate() {
+        let product = u128::from(*u) * total;
+        scaled.push(product / sum);
+        remainders.push((product % sum, i));
+    }
+    let lost = total - scaled.iter().sum::<u128>();
+    remainders.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    for (_, i) in remainders.into_iter().take(lost as usize) {
+        scaled[i] += 1;
+    }
+
+    // every scaled value is at most total, which fits into PayoutUnit
+    Some(scaled.into_iter().map(|u| u as PayoutUnit).collect())
+}
@@ -0,0 +1,50 @@
+//! Minimal prediction market nostr relay, see [prediction_market_event::relay].
+//!
+//! Usage: `prediction-market-relay [--bind <address>] [--db <path>]`
+//! Nostr events are kept in memory unless `--db` names a file of json lines to load and append to.
+
+use std::process::ExitCode;
+
+use prediction_market_event::relay::Relay;
+use tokio::net::TcpListener;
+
+const USAGE: &str = "usage: prediction-market-relay [--bind <address>] [--db <path>]";
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+    let mut bind = "127.0.0.1:7000".to_string();
+    let mut db = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.next()) {
+            ("--bind", Some(value)) => bind = value,
+            ("--db", Some(value)) => db = Some(value),
+            _ => {
+                eprintln!("{USAGE}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    if let Err(e) = run(&bind, db.as_deref()).await {
+        eprintln!("{e}");
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
+
+async fn run(bind: &str, db: Option<&str>) -> Result<(), prediction_market_event::Error> {
+    let relay = match db {
+        Some(path) => Relay::open(path)?,
+        None => Relay::in_memory(),
+    };
+    let listener = TcpListener::bind(bind).await?;
+    eprintln!("listening on ws://{}", listener.local_addr()?);
+
+    relay
+        .serve(listener, |e| eprintln!("failed to accept connection: {e}"))
+        .await;
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+//! Write policy plugin for relays that consult an external program over json lines, see [prediction_market_event::write_policy].
+//!
+//! Usage: `prediction-market-write-policy [--config <path>]`
+//! The config file contains a [prediction_market_event::write_policy::WritePolicyConfig] as json, without it every
+//! valid nostr event is accepted.
+
+use std::{
+    io::{BufRead, Write},
+    process::ExitCode,
+};
+
+use prediction_market_event::write_policy::{WritePolicy, WritePolicyConfig};
+
+const USAGE: &str = "usage: prediction-market-write-policy [--config <path>]";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let config = match args.as_slice() {
+        [] => WritePolicyConfig::default(),
+        [flag, path] if flag == "--config" => match read_config(path) {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("failed to read config {path}: {e}");
+                return ExitCode::FAILURE;
+            }
+        },
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut policy = WritePolicy::new(config);
+    let mut stdout = std::io::stdout().lock();
+    for line in std::io::stdin().lock().lines() {
+        let Ok(line) = line else {
+            return ExitCode::FAILURE;
+        };
+        let output = policy.handle_line(&line);
+        if writeln!(stdout, "{output}")
+            .and_then(|_| stdout.flush())
+            .is_err()
+        {
+            return ExitCode::FAILURE;
+        }
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn read_config(path: &str) -> Result<WritePolicyConfig, Box<dyn std::error::Error>> {
+    let json = std::fs::read_to_string(path)?;
+
+    Ok(serde_json::from_str(&json)?)
+}
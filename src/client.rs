@@ -0,0 +1,569 @@
+//! Async nostr relay client for publishing and subscribing to [NostrEventUtils] kinds.
+//!
+//! Requires the `client` feature. Only `ws://` relays can be reached unless a tls feature of
+//! `tokio-tungstenite` (for example `rustls-tls-webpki-roots`) is enabled by the depending crate.
+
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures_util::{future::join_all, SinkExt, Stream, StreamExt};
+use nostr::{
+    event::{Event as NostrEvent, EventId},
+    message::{ClientMessage, RelayMessage, SubscriptionId},
+    types::Filter,
+    util::JsonUtil,
+};
+use tokio::{
+    net::TcpStream,
+    sync::{mpsc, oneshot},
+};
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+use crate::{
+    nostr_event_types::{InterpretOptions, Interpreted, NostrEventUtils},
+    signer::AsyncSigner,
+    Error,
+};
+
+/// Timeout used by [Client::new] for connecting to a relay and waiting for its `OK` message.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Connection to any number of nostr relays.
+///
+/// Every relay is served by its own task, which is stopped when the [Client] and all its [Subscription]s are dropped.
+/// Requires a tokio runtime.
+pub struct Client {
+    relays: Vec<Relay>,
+    timeout: Duration,
+}
+
+struct Relay {
+    url: String,
+    commands: mpsc::UnboundedSender<Command>,
+}
+
+enum Command {
+    Publish {
+        nostr_event: Box<NostrEvent>,
+        ok: oneshot::Sender<Result<(), String>>,
+    },
+    Subscribe {
+        subscription_id: SubscriptionId,
+        filters: Vec<Filter>,
+        notifications: mpsc::UnboundedSender<Notification>,
+    },
+    Close {
+        subscription_id: SubscriptionId,
+    },
+}
+
+enum Notification {
+    Event {
+        relay_url: String,
+        nostr_event: Box<NostrEvent>,
+    },
+    EndOfStoredEvents {
+        relay_url: String,
+    },
+    Closed {
+        relay_url: String,
+        message: String,
+    },
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Client {
+    /// Create [Client] without relays using [DEFAULT_TIMEOUT].
+    pub fn new() -> Self {
+        Self::with_timeout(DEFAULT_TIMEOUT)
+    }
+
+    /// Create [Client] without relays using timeout for connecting and publishing.
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self {
+            relays: Vec::new(),
+            timeout,
+        }
+    }
+
+    /// Create [Client] using [DEFAULT_TIMEOUT] and connect to every relay of relay_urls.
+    ///
+    /// Returns the [Client] with every relay that could be connected to and an [Error::Relay] for every relay that could not.
+    pub async fn connect(relay_urls: &[String]) -> (Self, Vec<Error>) {
+        let mut client = Self::new();
+        let mut errors = Vec::new();
+        for relay_url in relay_urls {
+            if let Err(e) = client.add_relay(relay_url).await {
+                errors.push(e);
+            }
+        }
+
+        (client, errors)
+    }
+
+    /// Connect to relay. Does nothing if the relay was already added.
+    ///
+    /// Fails with [Error::Relay] if the websocket connection can not be opened within the timeout.
+    pub async fn add_relay(&mut self, relay_url: &str) -> Result<(), Error> {
+        if self.relays.iter().any(|r| r.url == relay_url) {
+            return Ok(());
+        }
+        let ws = match tokio::time::timeout(self.timeout, connect_async(relay_url)).await {
+            Ok(Ok((ws, _))) => ws,
+            Ok(Err(e)) => return Err(relay_error(relay_url, e)),
+            Err(_) => return Err(relay_error(relay_url, "timed out connecting")),
+        };
+        let (commands, commands_receiver) = mpsc::unbounded_channel();
+        tokio::spawn(run_relay(relay_url.to_owned(), ws, commands_receiver));
+        self.relays.push(Relay {
+            url: relay_url.to_owned(),
+            commands,
+        });
+
+        Ok(())
+    }
+
+    /// Urls of the connected relays.
+    pub fn relay_urls(&self) -> Vec<&str> {
+        self.relays.iter().map(|r| r.url.as_str()).collect()
+    }
+
+    /// Publish [NostrEvent] to every relay and wait for their `OK` messages.
+    ///
+    /// Returns the url and result of every relay. The result is an [Error::Relay] if the relay rejected the [NostrEvent],
+    /// disconnected or did not answer within the timeout.
+    pub async fn publish_nostr_event(
+        &self,
+        nostr_event: &NostrEvent,
+    ) -> Vec<(String, Result<(), Error>)> {
+        let results = join_all(
+            self.relays
+                .iter()
+                .map(|relay| publish_to_relay(relay, nostr_event, self.timeout)),
+        )
+        .await;
+
+        self.relays
+            .iter()
+            .map(|r| r.url.clone())
+            .zip(results)
+            .collect()
+    }
+
+    /// Create a [NostrEvent] of kind T from param, sign it with signer and publish it with [Client::publish_nostr_event].
+    ///
+    /// Returns the published [NostrEvent] and the url and result of every relay.
+    pub async fn publish_with_signer<T: NostrEventUtils, S: AsyncSigner + ?Sized>(
+        &self,
+        param: &T::CreateParameter,
+        signer: &S,
+    ) -> Result<(NostrEvent, Vec<(String, Result<(), Error>)>), Error> {
+        let nostr_event = T::create_nostr_signed_event_async(param, signer).await?;
+        let results = self.publish_nostr_event(&nostr_event).await;
+
+        Ok((nostr_event, results))
+    }
+
+    /// Subscribe to nostr events of kind T on every relay using default [InterpretOptions].
+    ///
+    /// See [Client::subscribe_with_options].
+    pub fn subscribe<T: NostrEventUtils>(&self, filters: Vec<Filter>) -> Subscription<T> {
+        self.subscribe_with_options(filters, InterpretOptions::default())
+    }
+
+    /// Subscribe to nostr events of kind T on every relay.
+    ///
+    /// The kinds of every [Filter] are replaced with [NostrEventUtils::KIND] of T, no filters is the same as [NostrEventUtils::filter].
+    /// Received nostr events are interpreted with options, see [Subscription].
+    pub fn subscribe_with_options<T: NostrEventUtils>(
+        &self,
+        filters: Vec<Filter>,
+        options: InterpretOptions,
+    ) -> Subscription<T> {
+        let mut filters = filters;
+        if filters.is_empty() {
+            filters.push(T::filter());
+        }
+        for filter in filters.iter_mut() {
+            filter.kinds = Some(BTreeSet::from([T::KIND]));
+        }
+
+        Subscription {
+            subscribed: self.open_subscription(filters),
+            options,
+            kind: PhantomData,
+        }
+    }
+
+    /// Subscribe to nostr events of any kind on every relay, without interpreting them.
+    ///
+    /// See [NostrEventSubscription].
+    pub fn subscribe_nostr_events(&self, filters: Vec<Filter>) -> NostrEventSubscription {
+        NostrEventSubscription {
+            subscribed: self.open_subscription(filters),
+        }
+    }
+
+    fn open_subscription(&self, filters: Vec<Filter>) -> Subscribed {
+        let subscription_id = SubscriptionId::generate();
+        let (notifications, notifications_receiver) = mpsc::unbounded_channel();
+        for relay in self.relays.iter() {
+            let command = Command::Subscribe {
+                subscription_id: subscription_id.clone(),
+                filters: filters.clone(),
+                notifications: notifications.clone(),
+            };
+            if relay.commands.send(command).is_err() {
+                let _ = notifications.send(Notification::Closed {
+                    relay_url: relay.url.clone(),
+                    message: "disconnected".to_string(),
+                });
+            }
+        }
+
+        Subscribed {
+            subscription_id,
+            relays: self.relays.iter().map(|r| r.commands.clone()).collect(),
+            notifications: notifications_receiver,
+            seen: HashSet::new(),
+        }
+    }
+}
+
+/// Item of a [Subscription], V is the [NostrEventUtils::InterpretResult] of the subscribed kind.
+#[derive(Debug)]
+pub enum SubscriptionItem<V> {
+    /// Nostr event that was interpreted successfully, [Interpreted::metadata] contains the relay it was received from.
+    Interpreted {
+        nostr_event: Box<NostrEvent>,
+        interpreted: Interpreted<V>,
+    },
+
+    /// Nostr event that failed to be interpreted.
+    Invalid {
+        relay_url: String,
+        nostr_event: Box<NostrEvent>,
+        error: Error,
+    },
+
+    /// The relay sent all of its stored nostr events, following nostr events are new.
+    EndOfStoredEvents { relay_url: String },
+
+    /// The relay closed the subscription or disconnected, always an [Error::Relay].
+    /// No more items are received from this relay.
+    RelayError { error: Error },
+}
+
+/// [Stream] of [SubscriptionItem]s created by [Client::subscribe].
+///
+/// Nostr events are deduplicated by nostr event id across all relays, only the first copy with a valid signature is returned.
+/// Copies with an invalid signature are returned as [SubscriptionItem::Invalid] and do not hide later valid copies.
+/// The [Stream] ends once every relay closed the subscription. Dropping [Subscription] sends `CLOSE` to every relay.
+pub struct Subscription<T: NostrEventUtils> {
+    subscribed: Subscribed,
+    options: InterpretOptions,
+    kind: PhantomData<fn() -> T>,
+}
+
+impl<T: NostrEventUtils> Subscription<T> {
+    /// Id of the subscription sent to the relays.
+    pub fn subscription_id(&self) -> &SubscriptionId {
+        &self.subscribed.subscription_id
+    }
+}
+
+impl<T: NostrEventUtils> Stream for Subscription<T> {
+    type Item = SubscriptionItem<T::InterpretResult>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            let Some(notification) = std::task::ready!(this.subscribed.poll_notification(cx))
+            else {
+                return Poll::Ready(Option::None);
+            };
+            let item = match notification {
+                Notification::Event {
+                    relay_url,
+                    nostr_event,
+                } => {
+                    if nostr_event.kind != T::KIND {
+                        continue;
+                    }
+                    match T::interpret_nostr_event_enveloped_with_options(
+                        &nostr_event,
+                        &this.options,
+                    ) {
+                        Ok(interpreted) => {
+                            this.subscribed.mark_seen(&nostr_event);
+                            SubscriptionItem::Interpreted {
+                                nostr_event,
+                                interpreted: interpreted.with_relay_url(relay_url),
+                            }
+                        }
+                        Err(error) => {
+                            // other copies of an authentic nostr event fail the same way
+                            if nostr_event.verify().is_ok() {
+                                this.subscribed.mark_seen(&nostr_event);
+                            }
+                            SubscriptionItem::Invalid {
+                                relay_url,
+                                nostr_event,
+                                error,
+                            }
+                        }
+                    }
+                }
+                notification => notification.into_item(),
+            };
+
+            return Poll::Ready(Some(item));
+        }
+    }
+}
+
+/// [Stream] of [SubscriptionItem]s created by [Client::subscribe_nostr_events].
+///
+/// Same as [Subscription], but nostr events of every kind are received and only their signature is verified,
+/// the value of [SubscriptionItem::Interpreted] is `()`.
+pub struct NostrEventSubscription {
+    subscribed: Subscribed,
+}
+
+impl NostrEventSubscription {
+    /// Id of the subscription sent to the relays.
+    pub fn subscription_id(&self) -> &SubscriptionId {
+        &self.subscribed.subscription_id
+    }
+}
+
+impl Stream for NostrEventSubscription {
+    type Item = SubscriptionItem<()>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let Some(notification) = std::task::ready!(this.subscribed.poll_notification(cx)) else {
+            return Poll::Ready(Option::None);
+        };
+        let item = match notification {
+            Notification::Event {
+                relay_url,
+                nostr_event,
+            } => match nostr_event.verify() {
+                Ok(()) => {
+                    this.subscribed.mark_seen(&nostr_event);
+                    SubscriptionItem::Interpreted {
+                        interpreted: Interpreted::new(&nostr_event, ()).with_relay_url(relay_url),
+                        nostr_event,
+                    }
+                }
+                Err(e) => SubscriptionItem::Invalid {
+                    relay_url,
+                    nostr_event,
+                    error: e.into(),
+                },
+            },
+            notification => notification.into_item(),
+        };
+
+        Poll::Ready(Some(item))
+    }
+}
+
+/// Subscription on every relay of a [Client], shared by [Subscription] and [NostrEventSubscription].
+struct Subscribed {
+    subscription_id: SubscriptionId,
+    relays: Vec<mpsc::UnboundedSender<Command>>,
+    notifications: mpsc::UnboundedReceiver<Notification>,
+    seen: HashSet<EventId>,
+}
+
+impl Subscribed {
+    /// Next notification, skipping nostr events already received from any relay and marked with [Subscribed::mark_seen].
+    fn poll_notification(&mut self, cx: &mut Context<'_>) -> Poll<Option<Notification>> {
+        loop {
+            let notification = std::task::ready!(self.notifications.poll_recv(cx));
+            if let Some(Notification::Event { nostr_event, .. }) = &notification {
+                if self.seen.contains(&nostr_event.id) {
+                    continue;
+                }
+            }
+
+            return Poll::Ready(notification);
+        }
+    }
+
+    /// Skip later copies of nostr_event. Only call once its signature is verified, a relay can send a forged
+    /// nostr event reusing the id of an authentic one.
+    fn mark_seen(&mut self, nostr_event: &NostrEvent) {
+        self.seen.insert(nostr_event.id);
+    }
+}
+
+impl Drop for Subscribed {
+    fn drop(&mut self) {
+        for commands in self.relays.iter() {
+            let _ = commands.send(Command::Close {
+                subscription_id: self.subscription_id.clone(),
+            });
+        }
+    }
+}
+
+impl Notification {
+    /// [SubscriptionItem] of a notification that is not [Notification::Event].
+    fn into_item<V>(self) -> SubscriptionItem<V> {
+        match self {
+            Notification::Event { .. } => {
+                unreachable!("nostr events are interpreted by the subscription")
+            }
+            Notification::EndOfStoredEvents { relay_url } => {
+                SubscriptionItem::EndOfStoredEvents { relay_url }
+            }
+            Notification::Closed { relay_url, message } => SubscriptionItem::RelayError {
+                error: relay_error(&relay_url, message),
+            },
+        }
+    }
+}
+
+fn relay_error(relay_url: &str, message: impl ToString) -> Error {
+    Error::Relay {
+        relay_url: relay_url.to_owned(),
+        message: message.to_string(),
+    }
+}
+
+async fn publish_to_relay(
+    relay: &Relay,
+    nostr_event: &NostrEvent,
+    timeout: Duration,
+) -> Result<(), Error> {
+    let (ok, ok_receiver) = oneshot::channel();
+    let command = Command::Publish {
+        nostr_event: Box::new(nostr_event.clone()),
+        ok,
+    };
+    if relay.commands.send(command).is_err() {
+        return Err(relay_error(&relay.url, "disconnected"));
+    }
+
+    match tokio::time::timeout(timeout, ok_receiver).await {
+        Ok(Ok(Ok(()))) => Ok(()),
+        Ok(Ok(Err(message))) => Err(relay_error(&relay.url, message)),
+        Ok(Err(_)) => Err(relay_error(&relay.url, "disconnected")),
+        Err(_) => Err(relay_error(&relay.url, "timed out waiting for OK")),
+    }
+}
+
+/// Task serving one relay connection until all command senders are dropped or the connection is lost.
+async fn run_relay(
+    relay_url: String,
+    ws: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    mut commands: mpsc::UnboundedReceiver<Command>,
+) {
+    let (mut sink, mut stream) = ws.split();
+    // publishes of the same nostr event while waiting for its OK share it
+    let mut pending: HashMap<EventId, Vec<oneshot::Sender<Result<(), String>>>> = HashMap::new();
+    let mut subscriptions: HashMap<SubscriptionId, mpsc::UnboundedSender<Notification>> =
+        HashMap::new();
+
+    let reason = loop {
+        tokio::select! {
+            command = commands.recv() => {
+                let Some(command) = command else {
+                    let _ = sink.send(Message::Close(Option::None)).await;
+                    return;
+                };
+                let client_message = match command {
+                    Command::Publish { nostr_event, ok } => {
+                        let waiting = pending.entry(nostr_event.id).or_default();
+                        waiting.push(ok);
+                        if waiting.len() > 1 {
+                            continue;
+                        }
+                        ClientMessage::Event(nostr_event)
+                    }
+                    Command::Subscribe { subscription_id, filters, notifications } => {
+                        subscriptions.insert(subscription_id.clone(), notifications);
+                        ClientMessage::req(subscription_id, filters)
+                    }
+                    Command::Close { subscription_id } => {
+                        if subscriptions.remove(&subscription_id).is_none() {
+                            continue;
+                        }
+                        ClientMessage::close(subscription_id)
+                    }
+                };
+                if let Err(e) = sink.send(Message::text(client_message.as_json())).await {
+                    break e.to_string();
+                }
+            }
+            message = stream.next() => {
+                let text = match message {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | Option::None => break "disconnected".to_string(),
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => break e.to_string(),
+                };
+                let Ok(relay_message) = RelayMessage::from_json(text.as_str()) else {
+                    continue;
+                };
+                match relay_message {
+                    RelayMessage::Event { subscription_id, event } => {
+                        if let Some(notifications) = subscriptions.get(&subscription_id) {
+                            let _ = notifications.send(Notification::Event {
+                                relay_url: relay_url.clone(),
+                                nostr_event: event,
+                            });
+                        }
+                    }
+                    RelayMessage::EndOfStoredEvents(subscription_id) => {
+                        if let Some(notifications) = subscriptions.get(&subscription_id) {
+                            let _ = notifications.send(Notification::EndOfStoredEvents {
+                                relay_url: relay_url.clone(),
+                            });
+                        }
+                    }
+                    RelayMessage::Closed { subscription_id, message } => {
+                        if let Some(notifications) = subscriptions.remove(&subscription_id) {
+                            let _ = notifications.send(Notification::Closed {
+                                relay_url: relay_url.clone(),
+                                message: format!("subscription closed: {message}"),
+                            });
+                        }
+                    }
+                    RelayMessage::Ok { event_id, status, message } => {
+                        for ok in pending.remove(&event_id).unwrap_or_default() {
+                            let _ = ok.send(match status {
+                                true => Ok(()),
+                                false => Err(format!("rejected: {message}")),
+                            });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    };
+
+    for ok in pending.into_values().flatten() {
+        let _ = ok.send(Err(reason.clone()));
+    }
+    for (_, notifications) in subscriptions {
+        let _ = notifications.send(Notification::Closed {
+            relay_url: relay_url.clone(),
+            message: reason.clone(),
+        });
+    }
+}
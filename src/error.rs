@@ -19,4 +19,28 @@ pub enum Error {
 
     #[error("nostr keys: {0}")]
     NostrKey(#[from] nostr::key::Error),
+
+    #[error("nostr event id: {0}")]
+    NostrEventId(#[from] nostr::event::id::Error),
+
+    #[error("bech32 decode: {0}")]
+    Bech32Decode(#[from] bech32::primitives::decode::CheckedHrpstringError),
+
+    #[error("bech32 encode: {0}")]
+    Bech32Encode(#[from] bech32::EncodeError),
+
+    #[error("nip44: {0}")]
+    Nip44(#[from] nostr::nips::nip44::Error),
+
+    #[error("signer: {0}")]
+    Signer(String),
+
+    #[error("key storage: {0}")]
+    KeyStorage(String),
+
+    #[error("relay {relay_url}: {message}")]
+    Relay { relay_url: String, message: String },
+
+    #[error("io: {0}")]
+    Io(#[from] std::io::Error),
 }
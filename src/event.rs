@@ -1,4 +1,5 @@
 use crate::information::Information;
+use crate::nostr_event_types::EventPointer;
 use crate::Error;
 
 use rand::random;
@@ -51,6 +52,7 @@ impl Event {
 
     /// Validate [Event].
     /// accepted_information_variant_ids can be set to [Information::ALL_VARIANT_IDS] to accept any information variant.
+    #[allow(clippy::useless_format)]
     pub fn validate(&self, accepted_information_variant_ids: &[&str]) -> Result<(), Error> {
         if self.outcome_count < 2 {
             return Err(Error::Validation(format!(
@@ -84,6 +86,7 @@ impl Event {
     }
 
     /// internal sha256 hash
+    #[allow(clippy::redundant_closure)]
     fn hash_sha256(&self) -> Result<[u8; 32], Error> {
         let mut hasher = Sha256::new();
 
@@ -134,6 +137,7 @@ impl Display for EventHashHex {
 
 impl EventHashHex {
     /// Checks if s has structure of event hex hash.
+    #[allow(clippy::redundant_pattern_matching)]
     pub fn is_valid_format(s: &str) -> bool {
         s.len() == 64 && matches!(s.find(|c: char| !c.is_ascii_hexdigit()), None)
     }
@@ -142,9 +146,13 @@ impl EventHashHex {
 impl FromStr for EventHashHex {  
     type Err = Error;
     
+    /// Accepts hex or a bech32 [EventPointer], whose hints are dropped.
+    #[allow(clippy::useless_format)]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         if Self::is_valid_format(s) {
             Ok(Self(s.to_owned()))
+        } else if let Ok(event_pointer) = EventPointer::from_bech32(s) {
+            Ok(event_pointer.event_hash_hex)
         } else {
             Err(Error::Validation(format!("invalid format")))
         }
@@ -184,6 +192,7 @@ impl EventPayout {
     }
 
     /// Validate [EventPayout]
+    #[allow(clippy::useless_format)]
     pub fn validate(&self, event: &Event) -> Result<(), Error> {
         let event_hash_hex = event.hash_hex()?;
         if self.event_hash_hex != event_hash_hex {
@@ -212,7 +221,7 @@ impl EventPayout {
     }
 }
 
-fn byte_array_to_hex_string(array: &[u8]) -> String {
+pub(crate) fn byte_array_to_hex_string(array: &[u8]) -> String {
     let mut s = String::with_capacity(array.len() * 2);
     for b in array {
         s.push_str(&format!("{b:02x}"))
@@ -221,7 +230,7 @@ fn byte_array_to_hex_string(array: &[u8]) -> String {
     s
 }
 
-fn hex_string_to_byte_array(hex_string: &str) -> Result<Vec<u8>, &str> {
+pub(crate) fn hex_string_to_byte_array(hex_string: &str) -> Result<Vec<u8>, &str> {
     let error = Err("invalid hex string");
 
     if hex_string.len() % 2 != 0 {
@@ -231,6 +240,10 @@ fn hex_string_to_byte_array(hex_string: &str) -> Result<Vec<u8>, &str> {
     let mut byte_array = Vec::with_capacity(hex_string.len() / 2);
 
     for chunk in hex_string.as_bytes().chunks(2) {
+        // from_str_radix would also accept a leading '+'
+        if !chunk.iter().all(u8::is_ascii_hexdigit) {
+            return error;
+        }
         let Ok(hex_chunk) = std::str::from_utf8(chunk) else {
             return error;
         };
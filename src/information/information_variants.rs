@@ -25,6 +25,7 @@ impl V1 {
     const MAX_DESCRIPTION_LENGTH: usize = 1024 * 10;
     const MAX_OUTCOME_TITLE_LENGTH: usize = 64;
 
+    #[allow(clippy::useless_format)]
     pub(super) fn validate(&self, outcomes: Outcome) -> Result<(), Error> {
         if self.title.len() > Self::MAX_TITLE_LENGTH {
             return Err(Error::Validation(format!(
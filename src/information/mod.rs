@@ -24,7 +24,16 @@ impl Information {
         }
     }
 
+    /// Get the time a payout is expected at, if the information variant states one.
+    pub fn expected_payout_unix_seconds(&self) -> Option<u64> {
+        match self {
+            Self::None => Option::None,
+            Self::V1(i) => Some(i.expected_payout_unix_seconds),
+        }
+    }
+
     /// Validate [Information]
+    #[allow(clippy::useless_format)]
     pub fn validate(
         &self,
         accepted_information_variant_ids: &[&str],
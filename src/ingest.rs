@@ -0,0 +1,113 @@
+//! Validation of incoming nostr events for relays and relay write policies.
+
+use std::collections::HashMap;
+
+use nostr::event::Event as NostrEvent;
+
+use crate::{
+    information::Information,
+    nostr_event_types::{
+        EventPayoutAttestation, EventPayoutAttestationCorrection, EventPayoutAttestationDispute,
+        EventPayoutBatchAttestation, FutureEventPayoutAttestationPledge, NewEvent, NostrEventUtils,
+        OracleProfileAnnouncement, PrivateEventPayoutAttestation, PrivateNewEvent,
+    },
+    Error, Event, EventHashHex,
+};
+
+/// Validates nostr events of the kinds in [crate::nostr_event_types] before a relay stores them.
+///
+/// Remembers the [Event] of every accepted [NewEvent] nostr event, so that later pledges, attestations, corrections
+/// and disputes referencing it are validated against it. Nostr events referencing unknown [Event]s are only interpreted.
+#[derive(Debug, Clone, Default)]
+pub struct IngestValidator {
+    events: HashMap<EventHashHex, Event>,
+}
+
+impl IngestValidator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validate nostr_event. Nostr events of other kinds only need a valid id and signature.
+    ///
+    /// Returns the NIP-01 `OK` message with machine readable prefix if nostr_event should be rejected.
+    pub fn validate(&self, nostr_event: &NostrEvent) -> Result<(), String> {
+        self.validate_kind(nostr_event)
+            .map_err(|e| format!("invalid: {e}"))
+    }
+
+    /// Remember the [Event] of an accepted [NewEvent] nostr event. Nostr events of other kinds are ignored.
+    pub fn accept(&mut self, nostr_event: &NostrEvent) {
+        if nostr_event.kind != NewEvent::KIND {
+            return;
+        }
+        let Ok(event) = NewEvent::interpret_nostr_event(nostr_event) else {
+            return;
+        };
+        if let Ok(event_hash_hex) = event.hash_hex() {
+            self.events.insert(event_hash_hex, event);
+        }
+    }
+
+    /// [Event] of an accepted [NewEvent] nostr event with [EventHashHex], if any.
+    pub fn event(&self, event_hash_hex: &EventHashHex) -> Option<&Event> {
+        self.events.get(event_hash_hex)
+    }
+
+    fn validate_kind(&self, nostr_event: &NostrEvent) -> Result<(), Error> {
+        nostr_event.verify()?;
+
+        match nostr_event.kind {
+            kind if kind == NewEvent::KIND => {
+                let event = NewEvent::interpret_nostr_event(nostr_event)?;
+                event.validate(Information::ALL_VARIANT_IDS)?;
+            }
+            kind if kind == FutureEventPayoutAttestationPledge::KIND => {
+                let (_, pledge) =
+                    FutureEventPayoutAttestationPledge::interpret_nostr_event(nostr_event)?;
+                if let Some(event) = self.event(&pledge.event_hash_hex) {
+                    pledge.validate(event)?;
+                }
+            }
+            kind if kind == EventPayoutAttestation::KIND => {
+                let (_, event_payout) = EventPayoutAttestation::interpret_nostr_event(nostr_event)?;
+                if let Some(event) = self.event(&event_payout.event_hash_hex) {
+                    event_payout.validate(event)?;
+                }
+            }
+            kind if kind == EventPayoutAttestationCorrection::KIND => {
+                let (_, correction) =
+                    EventPayoutAttestationCorrection::interpret_nostr_event(nostr_event)?;
+                if let (Some(event), Some(event_payout)) = (
+                    self.event(&correction.event_hash_hex),
+                    correction.replacement_event_payout(),
+                ) {
+                    event_payout.validate(event)?;
+                }
+            }
+            kind if kind == EventPayoutAttestationDispute::KIND => {
+                let (_, dispute) =
+                    EventPayoutAttestationDispute::interpret_nostr_event(nostr_event)?;
+                if let Some(event) = self.event(&dispute.event_hash_hex) {
+                    dispute.validate(event)?;
+                }
+            }
+            kind if kind == EventPayoutBatchAttestation::KIND => {
+                EventPayoutBatchAttestation::interpret_nostr_event(nostr_event)?;
+            }
+            kind if kind == PrivateNewEvent::KIND => {
+                PrivateNewEvent::interpret_nostr_event(nostr_event)?;
+            }
+            kind if kind == PrivateEventPayoutAttestation::KIND => {
+                PrivateEventPayoutAttestation::interpret_nostr_event(nostr_event)?;
+            }
+            kind if kind == OracleProfileAnnouncement::KIND => {
+                let (_, profile) = OracleProfileAnnouncement::interpret_nostr_event(nostr_event)?;
+                profile.validate()?;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
@@ -1,9 +1,38 @@
+pub mod aggregation;
+#[cfg(feature = "client")]
+pub mod client;
 mod error;
 mod event;
 pub mod information;
+pub mod ingest;
+pub mod merkle;
 pub mod nostr_event_types;
+mod oracle_profile;
+mod pledge;
+#[cfg(feature = "relay")]
+pub mod relay;
+#[cfg(feature = "remote-signer")]
+pub mod remote_signer;
+pub mod signer;
+#[cfg(test)]
 mod tests;
+pub mod write_policy;
 
 pub use error::Error;
 pub use event::*;
+pub use oracle_profile::*;
+pub use pledge::*;
 pub use nostr;
+
+/// Internal functions exposed for the fuzz targets in `fuzz/`. Not part of the public api.
+#[cfg(feature = "fuzzing")]
+#[doc(hidden)]
+pub mod fuzzing {
+    pub fn byte_array_to_hex_string(array: &[u8]) -> String {
+        crate::event::byte_array_to_hex_string(array)
+    }
+
+    pub fn hex_string_to_byte_array(hex_string: &str) -> Result<Vec<u8>, &str> {
+        crate::event::hex_string_to_byte_array(hex_string)
+    }
+}
@@ -0,0 +1,226 @@
+//! Merkle tree over [EventPayout]s, so that one nostr event can attest to many payouts.
+//!
+//! Used by [crate::nostr_event_types::EventPayoutBatchAttestation]. Leaves are
+//! `sha256(0x00 || event hash || units per outcome as u32 big endian)`, inner nodes are `sha256(0x01 || left || right)`.
+//! A node without a sibling is promoted to the next level unchanged.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    event::{byte_array_to_hex_string, hex_string_to_byte_array},
+    Error, EventHashHex, EventPayout,
+};
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+type Hash = [u8; 32];
+
+/// Merkle tree over [EventPayout]s of distinct [crate::Event]s, in the order they were given.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PayoutMerkleTree {
+    event_payouts: Vec<EventPayout>,
+    /// Hashes of every level, leaves first and root last.
+    levels: Vec<Vec<Hash>>,
+}
+
+impl PayoutMerkleTree {
+    /// Create [PayoutMerkleTree]. [EventPayout]s are not validated.
+    ///
+    /// Fails if event_payouts is empty, contains an [EventHashHex] that is not 64 lowercase hex characters or two payouts
+    /// of the same [crate::Event].
+    pub fn new(event_payouts: Vec<EventPayout>) -> Result<Self, Error> {
+        if event_payouts.is_empty() {
+            return Err(Error::Validation("no event payouts".to_string()));
+        }
+        if !event_payouts
+            .iter()
+            .all(|p| is_lowercase_hash_hex(&p.event_hash_hex.0))
+        {
+            return Err(Error::Validation(
+                "event hash hex is not 64 lowercase hex characters".to_string(),
+            ));
+        }
+        let mut seen = HashSet::new();
+        if !event_payouts.iter().all(|p| seen.insert(&p.event_hash_hex)) {
+            return Err(Error::Validation(
+                "more than one event payout for an event".to_string(),
+            ));
+        }
+
+        let leaves = event_payouts
+            .iter()
+            .map(leaf_hash)
+            .collect::<Result<Vec<Hash>, Error>>()?;
+        let mut levels = vec![leaves];
+        while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+            let next = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => node_hash(left, right),
+                    [single] => *single,
+                    _ => unreachable!("chunks of 2"),
+                })
+                .collect();
+            levels.push(next);
+        }
+
+        Ok(Self {
+            event_payouts,
+            levels,
+        })
+    }
+
+    /// Root of the tree as hex.
+    pub fn root_hex(&self) -> String {
+        let root = self.levels.last().and_then(|level| level.first());
+
+        byte_array_to_hex_string(root.expect("tree has at least one leaf"))
+    }
+
+    /// Number of [EventPayout]s in the tree.
+    pub fn leaf_count(&self) -> u64 {
+        self.event_payouts.len() as u64
+    }
+
+    pub fn event_payouts(&self) -> &[EventPayout] {
+        &self.event_payouts
+    }
+
+    /// [PayoutInclusionProof] of the [EventPayout] for [EventHashHex], [None] if the tree has none.
+    pub fn proof(&self, event_hash_hex: &EventHashHex) -> Option<PayoutInclusionProof> {
+        let leaf_index = self
+            .event_payouts
+            .iter()
+            .position(|p| &p.event_hash_hex == event_hash_hex)?;
+
+        let mut sibling_hashes_hex = Vec::new();
+        let mut index = leaf_index;
+        for level in self.levels.iter().take(self.levels.len() - 1) {
+            if let Some(sibling) = level.get(index ^ 1) {
+                sibling_hashes_hex.push(byte_array_to_hex_string(sibling));
+            }
+            index /= 2;
+        }
+
+        Some(PayoutInclusionProof {
+            event_payout: self.event_payouts[leaf_index].clone(),
+            leaf_index: leaf_index as u64,
+            leaf_count: self.leaf_count(),
+            sibling_hashes_hex,
+        })
+    }
+}
+
+/// Proof that an [EventPayout] is a leaf of a [PayoutMerkleTree], created by [PayoutMerkleTree::proof].
+///
+/// Its size grows with the logarithm of the leaf count, at most 13 sibling hashes for 5000 leaves.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct PayoutInclusionProof {
+    pub event_payout: EventPayout,
+    pub leaf_index: u64,
+    pub leaf_count: u64,
+    /// Sibling hashes as hex, from the leaf level upwards. Levels where the node has no sibling are skipped.
+    pub sibling_hashes_hex: Vec<String>,
+}
+
+impl PayoutInclusionProof {
+    /// Try to create json string from [PayoutInclusionProof]
+    pub fn try_to_json_string(&self) -> Result<String, Error> {
+        serde_json::to_string(self).map_err(|e| e.into())
+    }
+
+    /// Try to parse json string into [PayoutInclusionProof]. [PayoutInclusionProof] is not verified.
+    pub fn try_from_json_str(json: &str) -> Result<Self, Error> {
+        serde_json::from_str(json).map_err(|e| e.into())
+    }
+
+    /// Root of the tree the proof leads to, as hex.
+    pub fn root_hex(&self) -> Result<String, Error> {
+        if self.leaf_index >= self.leaf_count {
+            return Err(Error::Validation(
+                "leaf index is not below leaf count".to_string(),
+            ));
+        }
+
+        let mut hash = leaf_hash(&self.event_payout)?;
+        let mut siblings = self.sibling_hashes_hex.iter();
+        let mut index = self.leaf_index;
+        let mut width = self.leaf_count;
+        while width > 1 {
+            if index % 2 == 1 || index + 1 < width {
+                let sibling = siblings.next().ok_or(Error::Validation(
+                    "proof has too few sibling hashes".to_string(),
+                ))?;
+                let sibling = parse_hash(sibling)?;
+                hash = if index % 2 == 1 {
+                    node_hash(&sibling, &hash)
+                } else {
+                    node_hash(&hash, &sibling)
+                };
+            }
+            index /= 2;
+            width = width.div_ceil(2);
+        }
+        if siblings.next().is_some() {
+            return Err(Error::Validation(
+                "proof has too many sibling hashes".to_string(),
+            ));
+        }
+
+        Ok(byte_array_to_hex_string(&hash))
+    }
+
+    /// Verify that [PayoutInclusionProof::event_payout] is a leaf of the tree with root_hex and leaf_count.
+    pub fn verify(&self, root_hex: &str, leaf_count: u64) -> Result<(), Error> {
+        if self.leaf_count != leaf_count {
+            return Err(Error::Validation(
+                "proof leaf count does not match".to_string(),
+            ));
+        }
+        if !self.root_hex()?.eq_ignore_ascii_case(root_hex) {
+            return Err(Error::Validation(
+                "proof does not lead to merkle root".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+fn leaf_hash(event_payout: &EventPayout) -> Result<Hash, Error> {
+    let event_hash = parse_hash(&event_payout.event_hash_hex.0)?;
+
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(event_hash);
+    for units in event_payout.units_per_outcome.iter() {
+        hasher.update(units.to_be_bytes());
+    }
+
+    Ok(hasher.finalize().into())
+}
+
+fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+
+    hasher.finalize().into()
+}
+
+/// True if hex is a hash as written by this module, 64 lowercase hex characters.
+pub(crate) fn is_lowercase_hash_hex(hex: &str) -> bool {
+    hex.len() == 64 && hex.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+fn parse_hash(hex: &str) -> Result<Hash, Error> {
+    hex_string_to_byte_array(hex)
+        .map_err(|e| Error::Validation(e.to_string()))?
+        .try_into()
+        .map_err(|_| Error::Validation("hash is not 32 bytes".to_string()))
+}
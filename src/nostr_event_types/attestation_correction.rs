@@ -0,0 +1,310 @@
+use std::collections::HashSet;
+
+use nostr::{
+    event::{Event as NostrEvent, EventBuilder as NostrEventBuilder, EventId, Kind, TagStandard},
+    nips::nip10::Marker,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    event_hash_tags, read_event_hash_tag,
+    trait_dec::{verify_signature_verified_nostr_event, Res, SignatureVerified},
+    EventPayoutAttestation, InterpretOptions, NostrEventIdHex, NostrEventUtils, NostrPublicKeyHex,
+};
+use crate::{Error, EventHashHex, EventPayout, PayoutUnit};
+
+/// Marker of the `e` tag referencing the corrected nostr event.
+pub const CORRECTS_MARKER: &str = "corrects";
+
+/// Correction or retraction of an earlier [EventPayoutAttestation] or [EventPayoutAttestationCorrection].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct AttestationCorrection {
+    /// Id of the nostr event being corrected.
+    pub corrected_nostr_event_id: NostrEventIdHex,
+
+    /// Created from [crate::Event::hash_hex]
+    pub event_hash_hex: EventHashHex,
+
+    /// Replacement for [EventPayout::units_per_outcome]. [None] retracts the attestation without replacement.
+    pub units_per_outcome: Option<Vec<PayoutUnit>>,
+
+    /// Why the correction was made.
+    pub reason: String,
+}
+
+impl AttestationCorrection {
+    /// Returns the replacement [EventPayout], [None] if this is a retraction.
+    /// IMPORTANT: [EventPayout] is not validated.
+    pub fn replacement_event_payout(&self) -> Option<EventPayout> {
+        self.units_per_outcome
+            .as_ref()
+            .map(|units_per_outcome| EventPayout {
+                event_hash_hex: self.event_hash_hex.clone(),
+                units_per_outcome: units_per_outcome.clone(),
+            })
+    }
+}
+
+/// Content of [EventPayoutAttestationCorrection].
+#[derive(Serialize, Deserialize)]
+struct CorrectionContent {
+    units_per_outcome: Option<Vec<PayoutUnit>>,
+    reason: String,
+}
+
+/// [NostrEvent] that corrects or retracts an earlier [EventPayoutAttestation] or [EventPayoutAttestationCorrection] by the same signer.
+///
+/// - kind set to [EventPayoutAttestationCorrection::KIND]
+/// - content set to [AttestationCorrection::units_per_outcome] and [AttestationCorrection::reason] as json
+/// - tags from [event_hash_tags] containing [AttestationCorrection::event_hash_hex]
+/// - `e` tag with marker [CORRECTS_MARKER] containing [AttestationCorrection::corrected_nostr_event_id]
+///
+/// The corrected nostr event should also be deleted with [EventPayoutAttestationCorrection::create_nostr_deletion_event_builder].
+pub struct EventPayoutAttestationCorrection;
+
+impl NostrEventUtils for EventPayoutAttestationCorrection {
+    const KIND_U16: u16 = 6278;
+
+    type CreateParameter = AttestationCorrection;
+
+    /// Accepts [AttestationCorrection]
+    ///
+    /// Returns [NostrEventBuilder] with:
+    /// - kind set to [EventPayoutAttestationCorrection::KIND]
+    /// - content set to [AttestationCorrection::units_per_outcome] and [AttestationCorrection::reason] as json
+    /// - tags from [event_hash_tags] containing [AttestationCorrection::event_hash_hex]
+    /// - `e` tag with marker [CORRECTS_MARKER] containing [AttestationCorrection::corrected_nostr_event_id]
+    fn create_nostr_event_builder(correction: &Self::CreateParameter) -> Res<NostrEventBuilder> {
+        let content = serde_json::to_string(&CorrectionContent {
+            units_per_outcome: correction.units_per_outcome.clone(),
+            reason: correction.reason.clone(),
+        })?;
+        let corrected_nostr_event_id = EventId::from_hex(&correction.corrected_nostr_event_id.0)?;
+        let mut tags = event_hash_tags(&correction.event_hash_hex);
+        tags.push(
+            TagStandard::Event {
+                event_id: corrected_nostr_event_id,
+                relay_url: None,
+                marker: Some(Marker::Custom(CORRECTS_MARKER.to_owned())),
+                public_key: None,
+            }
+            .into(),
+        );
+        let builder = NostrEventBuilder::new(Self::KIND, content, tags);
+
+        Ok(builder)
+    }
+
+    type InterpretResult = (NostrPublicKeyHex, AttestationCorrection);
+
+    /// Accepts [NostrEvent].
+    ///
+    /// Returns [NostrPublicKeyHex] and the [AttestationCorrection] it signed.
+    /// IMPORTANT: [AttestationCorrection] is not validated.
+    fn interpret_nostr_event(nostr_event: &NostrEvent) -> Res<Self::InterpretResult> {
+        Self::interpret_nostr_event_with_options(nostr_event, &InterpretOptions::default())
+    }
+    /// Accepts [NostrEvent] and [InterpretOptions].
+    ///
+    /// Return information can be found in [`Self::interpret_nostr_event`]
+    fn interpret_nostr_event_with_options(
+        nostr_event: &NostrEvent,
+        options: &InterpretOptions,
+    ) -> Res<Self::InterpretResult> {
+        let signature_verified = SignatureVerified::verify(nostr_event)?;
+
+        Self::interpret_nostr_event_signature_verified(nostr_event, options, signature_verified)
+    }
+    /// Accepts [NostrEvent] whose signature is [SignatureVerified] and [InterpretOptions].
+    ///
+    /// Return information can be found in [`Self::interpret_nostr_event`]
+    fn interpret_nostr_event_signature_verified(
+        nostr_event: &NostrEvent,
+        options: &InterpretOptions,
+        signature_verified: SignatureVerified,
+    ) -> Res<Self::InterpretResult> {
+        verify_signature_verified_nostr_event(nostr_event, options, signature_verified)?;
+
+        let nostr_public_key_hex = NostrPublicKeyHex(nostr_event.pubkey.to_hex());
+        let event_hash_hex = read_event_hash_tag(nostr_event, options.event_hash_tag_mode)?;
+        let mut corrected = nostr_event
+            .tags
+            .iter()
+            .filter_map(|t| match t.as_standardized() {
+                Some(TagStandard::Event {
+                    event_id,
+                    marker: Some(Marker::Custom(marker)),
+                    ..
+                }) if marker == CORRECTS_MARKER => Some(event_id),
+                _ => None,
+            });
+        let Some(corrected_nostr_event_id) = corrected.next() else {
+            return Err(Error::Validation(
+                "nostr event does not reference a corrected nostr event".to_string(),
+            ));
+        };
+        if corrected.any(|id| id != corrected_nostr_event_id) {
+            return Err(Error::Validation(
+                "nostr event references more than one corrected nostr event".to_string(),
+            ));
+        }
+        let content: CorrectionContent = serde_json::from_str(&nostr_event.content)?;
+        let correction = AttestationCorrection {
+            corrected_nostr_event_id: NostrEventIdHex(corrected_nostr_event_id.to_hex()),
+            event_hash_hex,
+            units_per_outcome: content.units_per_outcome,
+            reason: content.reason,
+        };
+
+        Ok((nostr_public_key_hex, correction))
+    }
+}
+
+impl EventPayoutAttestationCorrection {
+    /// Returns NIP-09 deletion [NostrEventBuilder] requesting relays to delete [AttestationCorrection::corrected_nostr_event_id], with [AttestationCorrection::reason] as content.
+    pub fn create_nostr_deletion_event_builder(
+        correction: &AttestationCorrection,
+    ) -> Res<NostrEventBuilder> {
+        let corrected_nostr_event_id = EventId::from_hex(&correction.corrected_nostr_event_id.0)?;
+        let builder =
+            NostrEventBuilder::delete_with_reason([corrected_nostr_event_id], &correction.reason);
+
+        Ok(builder)
+    }
+}
+
+/// Attestation history of one signer for one [EventHashHex], created by [resolve_attestation_history].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AttestationHistory {
+    /// The payout currently in effect. [None] if nothing was attested or the attestation was retracted.
+    /// IMPORTANT: [EventPayout] is not validated.
+    pub effective_event_payout: Option<EventPayout>,
+
+    /// Every attestation and correction, oldest first.
+    pub entries: Vec<AttestationHistoryEntry>,
+}
+
+/// One [EventPayoutAttestation] or [EventPayoutAttestationCorrection] in [AttestationHistory].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AttestationHistoryEntry {
+    pub nostr_event_id: NostrEventIdHex,
+    pub created_at_unix_seconds: u64,
+
+    /// Attested units. [None] for retractions.
+    pub units_per_outcome: Option<Vec<PayoutUnit>>,
+
+    /// [None] for the original [EventPayoutAttestation].
+    pub correction: Option<AttestationCorrection>,
+
+    /// A later correction that was not deleted replaced this entry.
+    pub superseded: bool,
+
+    /// The signer requested deletion of this entry with a NIP-09 deletion event.
+    pub deleted: bool,
+}
+
+/// Resolves the payout a signer currently attests to for [EventHashHex].
+///
+/// Accepts any number of [NostrEvent]s. [EventPayoutAttestation], [EventPayoutAttestationCorrection] and NIP-09 deletion events signed by signer are used, everything else is ignored.
+///
+/// The effective payout is that of the newest entry that was neither superseded by a correction nor deleted.
+/// Deleted corrections do not supersede, so deleting the only correction restores the corrected entry.
+pub fn resolve_attestation_history(
+    nostr_events: &[NostrEvent],
+    signer: &NostrPublicKeyHex,
+    event_hash_hex: &EventHashHex,
+) -> AttestationHistory {
+    let signed = nostr_events
+        .iter()
+        .filter(|nostr_event| nostr_event.pubkey.to_hex() == signer.0);
+
+    resolve_signed_attestation_history(signed, event_hash_hex)
+}
+
+/// [resolve_attestation_history] of nostr_events already filtered to the ones signed by one signer.
+pub(crate) fn resolve_signed_attestation_history<'a>(
+    nostr_events: impl IntoIterator<Item = &'a NostrEvent>,
+    event_hash_hex: &EventHashHex,
+) -> AttestationHistory {
+    let mut entries = Vec::new();
+    let mut deleted_ids = HashSet::new();
+    for nostr_event in nostr_events {
+        let nostr_event_id = NostrEventIdHex(nostr_event.id.to_hex());
+        let created_at_unix_seconds = nostr_event.created_at.as_u64();
+
+        if nostr_event.kind == EventPayoutAttestation::KIND {
+            let Ok((_, event_payout)) = EventPayoutAttestation::interpret_nostr_event(nostr_event)
+            else {
+                continue;
+            };
+            if &event_payout.event_hash_hex != event_hash_hex {
+                continue;
+            }
+            entries.push(AttestationHistoryEntry {
+                nostr_event_id,
+                created_at_unix_seconds,
+                units_per_outcome: Some(event_payout.units_per_outcome),
+                correction: None,
+                superseded: false,
+                deleted: false,
+            });
+        } else if nostr_event.kind == EventPayoutAttestationCorrection::KIND {
+            let Ok((_, correction)) =
+                EventPayoutAttestationCorrection::interpret_nostr_event(nostr_event)
+            else {
+                continue;
+            };
+            if &correction.event_hash_hex != event_hash_hex {
+                continue;
+            }
+            entries.push(AttestationHistoryEntry {
+                nostr_event_id,
+                created_at_unix_seconds,
+                units_per_outcome: correction.units_per_outcome.clone(),
+                correction: Some(correction),
+                superseded: false,
+                deleted: false,
+            });
+        } else if nostr_event.kind == Kind::EventDeletion {
+            if nostr_event.verify().is_err() {
+                continue;
+            }
+            deleted_ids.extend(nostr_event.event_ids().map(|id| id.to_hex()));
+        }
+    }
+
+    entries.sort_by(|a, b| {
+        (a.created_at_unix_seconds, &a.nostr_event_id)
+            .cmp(&(b.created_at_unix_seconds, &b.nostr_event_id))
+    });
+    entries.dedup_by(|a, b| a.nostr_event_id == b.nostr_event_id);
+
+    for entry in entries.iter_mut() {
+        entry.deleted = deleted_ids.contains(&entry.nostr_event_id.0);
+    }
+    // a deleted correction supersedes nothing
+    let superseded_ids: HashSet<NostrEventIdHex> = entries
+        .iter()
+        .filter(|e| !e.deleted)
+        .filter_map(|e| e.correction.as_ref())
+        .map(|c| c.corrected_nostr_event_id.clone())
+        .collect();
+    for entry in entries.iter_mut() {
+        entry.superseded = superseded_ids.contains(&entry.nostr_event_id);
+    }
+
+    let effective_event_payout = entries
+        .iter()
+        .rev()
+        .find(|e| !e.superseded && !e.deleted)
+        .and_then(|e| e.units_per_outcome.clone())
+        .map(|units_per_outcome| EventPayout {
+            event_hash_hex: event_hash_hex.clone(),
+            units_per_outcome,
+        });
+
+    AttestationHistory {
+        effective_event_payout,
+        entries,
+    }
+}
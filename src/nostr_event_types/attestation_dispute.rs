@@ -0,0 +1,277 @@
+use nostr::{
+    event::{Event as NostrEvent, EventBuilder as NostrEventBuilder, EventId, TagStandard},
+    nips::nip10::Marker,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    event_hash_tags, read_event_hash_tag, resolve_attestation_history,
+    trait_dec::{verify_signature_verified_nostr_event, Res, SignatureVerified},
+    InterpretOptions, NostrEventIdHex, NostrEventUtils, NostrPublicKeyHex,
+};
+#[allow(unused_imports)]
+use super::{EventPayoutAttestation, EventPayoutAttestationCorrection};
+use crate::{Error, Event, EventHashHex, EventPayout, PayoutUnit};
+
+/// Marker of the `e` tag referencing the disputed nostr event.
+pub const DISPUTES_MARKER: &str = "disputes";
+
+/// Challenge of an [EventPayoutAttestation] or [EventPayoutAttestationCorrection] believed to be wrong.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct AttestationDispute {
+    /// Id of the nostr event being disputed.
+    pub disputed_nostr_event_id: NostrEventIdHex,
+
+    /// Created from [Event::hash_hex]
+    pub event_hash_hex: EventHashHex,
+
+    /// The payout the disputer believes is correct.
+    pub proposed_units_per_outcome: Vec<PayoutUnit>,
+
+    /// References to evidence supporting the dispute, for example urls or nostr event ids.
+    pub evidence: Vec<String>,
+}
+
+impl AttestationDispute {
+    // hard coded limits
+    const MAX_EVIDENCE_COUNT: usize = 16;
+    const MAX_EVIDENCE_LENGTH: usize = 1024;
+
+    /// Returns the proposed [EventPayout].
+    /// IMPORTANT: [EventPayout] is not validated.
+    pub fn proposed_event_payout(&self) -> EventPayout {
+        EventPayout {
+            event_hash_hex: self.event_hash_hex.clone(),
+            units_per_outcome: self.proposed_units_per_outcome.clone(),
+        }
+    }
+
+    /// Validate [AttestationDispute] against the [Event] it disputes the payout of.
+    pub fn validate(&self, event: &Event) -> Result<(), Error> {
+        self.proposed_event_payout().validate(event)?;
+        if self.evidence.len() > Self::MAX_EVIDENCE_COUNT {
+            return Err(Error::Validation("evidence count is over max".to_string()));
+        }
+        if self
+            .evidence
+            .iter()
+            .any(|e| e.len() > Self::MAX_EVIDENCE_LENGTH)
+        {
+            return Err(Error::Validation("evidence length is over max".to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Content of [EventPayoutAttestationDispute].
+#[derive(Serialize, Deserialize)]
+struct DisputeContent {
+    proposed_units_per_outcome: Vec<PayoutUnit>,
+    evidence: Vec<String>,
+}
+
+/// [NostrEvent] that disputes an [EventPayoutAttestation] or [EventPayoutAttestationCorrection].
+///
+/// - kind set to [EventPayoutAttestationDispute::KIND]
+/// - content set to [AttestationDispute::proposed_units_per_outcome] and [AttestationDispute::evidence] as json
+/// - tags from [event_hash_tags] containing [AttestationDispute::event_hash_hex]
+/// - `e` tag with marker [DISPUTES_MARKER] containing [AttestationDispute::disputed_nostr_event_id]
+pub struct EventPayoutAttestationDispute;
+
+impl NostrEventUtils for EventPayoutAttestationDispute {
+    const KIND_U16: u16 = 6279;
+
+    type CreateParameter = AttestationDispute;
+
+    /// Accepts [AttestationDispute]
+    ///
+    /// Returns [NostrEventBuilder] with:
+    /// - kind set to [EventPayoutAttestationDispute::KIND]
+    /// - content set to [AttestationDispute::proposed_units_per_outcome] and [AttestationDispute::evidence] as json
+    /// - tags from [event_hash_tags] containing [AttestationDispute::event_hash_hex]
+    /// - `e` tag with marker [DISPUTES_MARKER] containing [AttestationDispute::disputed_nostr_event_id]
+    fn create_nostr_event_builder(dispute: &Self::CreateParameter) -> Res<NostrEventBuilder> {
+        let content = serde_json::to_string(&DisputeContent {
+            proposed_units_per_outcome: dispute.proposed_units_per_outcome.clone(),
+            evidence: dispute.evidence.clone(),
+        })?;
+        let disputed_nostr_event_id = EventId::from_hex(&dispute.disputed_nostr_event_id.0)?;
+        let mut tags = event_hash_tags(&dispute.event_hash_hex);
+        tags.push(
+            TagStandard::Event {
+                event_id: disputed_nostr_event_id,
+                relay_url: None,
+                marker: Some(Marker::Custom(DISPUTES_MARKER.to_owned())),
+                public_key: None,
+            }
+            .into(),
+        );
+        let builder = NostrEventBuilder::new(Self::KIND, content, tags);
+
+        Ok(builder)
+    }
+
+    type InterpretResult = (NostrPublicKeyHex, AttestationDispute);
+
+    /// Accepts [NostrEvent].
+    ///
+    /// Returns [NostrPublicKeyHex] and the [AttestationDispute] it signed.
+    /// IMPORTANT: [AttestationDispute] is not validated.
+    fn interpret_nostr_event(nostr_event: &NostrEvent) -> Res<Self::InterpretResult> {
+        Self::interpret_nostr_event_with_options(nostr_event, &InterpretOptions::default())
+    }
+    /// Accepts [NostrEvent] and [InterpretOptions].
+    ///
+    /// Return information can be found in [`Self::interpret_nostr_event`]
+    fn interpret_nostr_event_with_options(
+        nostr_event: &NostrEvent,
+        options: &InterpretOptions,
+    ) -> Res<Self::InterpretResult> {
+        let signature_verified = SignatureVerified::verify(nostr_event)?;
+
+        Self::interpret_nostr_event_signature_verified(nostr_event, options, signature_verified)
+    }
+    /// Accepts [NostrEvent] whose signature is [SignatureVerified] and [InterpretOptions].
+    ///
+    /// Return information can be found in [`Self::interpret_nostr_event`]
+    fn interpret_nostr_event_signature_verified(
+        nostr_event: &NostrEvent,
+        options: &InterpretOptions,
+        signature_verified: SignatureVerified,
+    ) -> Res<Self::InterpretResult> {
+        verify_signature_verified_nostr_event(nostr_event, options, signature_verified)?;
+
+        let nostr_public_key_hex = NostrPublicKeyHex(nostr_event.pubkey.to_hex());
+        let event_hash_hex = read_event_hash_tag(nostr_event, options.event_hash_tag_mode)?;
+        let mut disputed = nostr_event
+            .tags
+            .iter()
+            .filter_map(|t| match t.as_standardized() {
+                Some(TagStandard::Event {
+                    event_id,
+                    marker: Some(Marker::Custom(marker)),
+                    ..
+                }) if marker == DISPUTES_MARKER => Some(event_id),
+                _ => None,
+            });
+        let Some(disputed_nostr_event_id) = disputed.next() else {
+            return Err(Error::Validation(
+                "nostr event does not reference a disputed nostr event".to_string(),
+            ));
+        };
+        if disputed.any(|id| id != disputed_nostr_event_id) {
+            return Err(Error::Validation(
+                "nostr event references more than one disputed nostr event".to_string(),
+            ));
+        }
+        let content: DisputeContent = serde_json::from_str(&nostr_event.content)?;
+        let dispute = AttestationDispute {
+            disputed_nostr_event_id: NostrEventIdHex(disputed_nostr_event_id.to_hex()),
+            event_hash_hex,
+            proposed_units_per_outcome: content.proposed_units_per_outcome,
+            evidence: content.evidence,
+        };
+
+        Ok((nostr_public_key_hex, dispute))
+    }
+}
+
+/// Finality of the payout an oracle attested to, created by [payout_finality].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PayoutFinality {
+    /// The oracle has no effective attestation.
+    NoAttestation,
+
+    /// The attestation is undisputed, but disputes are still accepted until window_end_unix_seconds.
+    PendingDisputeWindow {
+        event_payout: EventPayout,
+        window_end_unix_seconds: u64,
+    },
+
+    /// The attestation was disputed within its dispute window.
+    Contested {
+        event_payout: EventPayout,
+        disputes: Vec<(NostrPublicKeyHex, AttestationDispute)>,
+    },
+
+    /// The dispute window passed without disputes.
+    Final { event_payout: EventPayout },
+}
+
+/// Decides if the payout oracle attested to for [Event] is final.
+///
+/// Accepts any number of [NostrEvent]s. The effective attestation of oracle is resolved with [resolve_attestation_history], a correction restarts the dispute window.
+/// A dispute counts if it references the effective attestation, is valid for [Event], proposes a different payout and was created
+/// between the effective attestation and the end of the dispute window.
+///
+/// IMPORTANT: created_at is chosen by the disputer. A dispute dated within the window but published later still turns a
+/// [PayoutFinality::Final] payout [PayoutFinality::Contested], finality is only as good as the arrival times of the nostr events at the relays used.
+///
+/// Fails if [Event] can not be hashed or the effective attestation is not valid for [Event].
+pub fn payout_finality(
+    event: &Event,
+    oracle: &NostrPublicKeyHex,
+    nostr_events: &[NostrEvent],
+    dispute_window_seconds: u64,
+    now_unix_seconds: u64,
+) -> Result<PayoutFinality, Error> {
+    let event_hash_hex = event.hash_hex()?;
+    let history = resolve_attestation_history(nostr_events, oracle, &event_hash_hex);
+    let Some(event_payout) = history.effective_event_payout else {
+        return Ok(PayoutFinality::NoAttestation);
+    };
+    event_payout.validate(event)?;
+    let Some(effective) = history
+        .entries
+        .iter()
+        .rev()
+        .find(|e| !e.superseded && !e.deleted)
+    else {
+        return Ok(PayoutFinality::NoAttestation);
+    };
+    let window_end_unix_seconds = effective
+        .created_at_unix_seconds
+        .saturating_add(dispute_window_seconds);
+
+    let mut disputes = Vec::new();
+    for nostr_event in nostr_events {
+        if nostr_event.kind != EventPayoutAttestationDispute::KIND
+            || nostr_event.created_at.as_u64() < effective.created_at_unix_seconds
+            || nostr_event.created_at.as_u64() > window_end_unix_seconds
+        {
+            continue;
+        }
+        let Ok((disputer, dispute)) =
+            EventPayoutAttestationDispute::interpret_nostr_event(nostr_event)
+        else {
+            continue;
+        };
+        if dispute.disputed_nostr_event_id != effective.nostr_event_id
+            || dispute.event_hash_hex != event_hash_hex
+            || dispute.proposed_units_per_outcome == event_payout.units_per_outcome
+            || dispute.validate(event).is_err()
+        {
+            continue;
+        }
+        if !disputes.contains(&(disputer.clone(), dispute.clone())) {
+            disputes.push((disputer, dispute));
+        }
+    }
+
+    let finality = if !disputes.is_empty() {
+        PayoutFinality::Contested {
+            event_payout,
+            disputes,
+        }
+    } else if now_unix_seconds <= window_end_unix_seconds {
+        PayoutFinality::PendingDisputeWindow {
+            event_payout,
+            window_end_unix_seconds,
+        }
+    } else {
+        PayoutFinality::Final { event_payout }
+    };
+
+    Ok(finality)
+}
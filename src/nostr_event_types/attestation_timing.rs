@@ -0,0 +1,172 @@
+use nostr::event::Event as NostrEvent;
+
+use super::{
+    EventPayoutAttestation, FutureEventPayoutAttestationPledge, NostrEventIdHex, NostrEventUtils,
+};
+use crate::{Error, Event};
+
+/// How a timing issue found by [validate_attestation_timing] is reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimingSeverity {
+    /// Reported in [AttestationTimingReport::warnings].
+    Warning,
+
+    /// Reported in [AttestationTimingReport::errors].
+    Error,
+}
+
+/// Tolerance and severities used by [validate_attestation_timing].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AttestationTimingPolicy {
+    /// Seconds every check tolerates, to allow for clock skew between oracles and relays.
+    pub tolerance_seconds: u64,
+
+    /// Severity of [AttestationTimingIssue::BeforeExpectedPayout], [TimingSeverity::Error] by default.
+    pub before_expected_payout: TimingSeverity,
+
+    /// Severity of [AttestationTimingIssue::AfterPledgeDeadline], [TimingSeverity::Warning] by default.
+    pub after_pledge_deadline: TimingSeverity,
+
+    /// Severity of [AttestationTimingIssue::PledgeAfterAttestation], [TimingSeverity::Error] by default.
+    pub pledge_after_attestation: TimingSeverity,
+}
+
+impl Default for AttestationTimingPolicy {
+    fn default() -> Self {
+        Self {
+            tolerance_seconds: 0,
+            before_expected_payout: TimingSeverity::Error,
+            after_pledge_deadline: TimingSeverity::Warning,
+            pledge_after_attestation: TimingSeverity::Error,
+        }
+    }
+}
+
+/// Timing issue of an [EventPayoutAttestation] found by [validate_attestation_timing].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AttestationTimingIssue {
+    /// The attestation was created before the expected payout time of the [Event].
+    BeforeExpectedPayout {
+        attested_at_unix_seconds: u64,
+        expected_payout_unix_seconds: u64,
+    },
+
+    /// The attestation was created after the attestation deadline the oracle pledged.
+    AfterPledgeDeadline {
+        pledge_nostr_event_id: NostrEventIdHex,
+        attested_at_unix_seconds: u64,
+        attestation_deadline_unix_seconds: u64,
+    },
+
+    /// The pledge of the oracle was created after its attestation.
+    PledgeAfterAttestation {
+        pledge_nostr_event_id: NostrEventIdHex,
+        attested_at_unix_seconds: u64,
+        pledged_at_unix_seconds: u64,
+    },
+}
+
+/// Result of [validate_attestation_timing].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct AttestationTimingReport {
+    pub warnings: Vec<AttestationTimingIssue>,
+    pub errors: Vec<AttestationTimingIssue>,
+}
+
+impl AttestationTimingReport {
+    /// True if there are no [AttestationTimingReport::errors].
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    fn push(&mut self, severity: TimingSeverity, issue: AttestationTimingIssue) {
+        match severity {
+            TimingSeverity::Warning => self.warnings.push(issue),
+            TimingSeverity::Error => self.errors.push(issue),
+        }
+    }
+}
+
+/// Checks when an [EventPayoutAttestation] nostr event was created against [Event] and the pledges of its oracle.
+///
+/// Accepts any number of [NostrEvent]s, [FutureEventPayoutAttestationPledge]s signed by the oracle for [Event] are used, everything else is ignored.
+/// Every pledge is checked on its own. The attestation deadline is only checked for pledges with terms,
+/// the expected payout time only for [Event]s whose information has one.
+///
+/// Fails if attestation is not an [EventPayoutAttestation] whose payout is valid for [Event].
+pub fn validate_attestation_timing(
+    event: &Event,
+    attestation: &NostrEvent,
+    nostr_events: &[NostrEvent],
+    policy: &AttestationTimingPolicy,
+) -> Result<AttestationTimingReport, Error> {
+    if attestation.kind != EventPayoutAttestation::KIND {
+        return Err(Error::Validation(
+            "nostr event is not an attestation".to_string(),
+        ));
+    }
+    let (oracle, event_payout) = EventPayoutAttestation::interpret_nostr_event(attestation)?;
+    event_payout.validate(event)?;
+
+    let tolerance_seconds = policy.tolerance_seconds;
+    let attested_at_unix_seconds = attestation.created_at.as_u64();
+    let mut report = AttestationTimingReport::default();
+
+    if let Some(expected_payout_unix_seconds) = event.information.expected_payout_unix_seconds() {
+        if attested_at_unix_seconds.saturating_add(tolerance_seconds) < expected_payout_unix_seconds
+        {
+            report.push(
+                policy.before_expected_payout,
+                AttestationTimingIssue::BeforeExpectedPayout {
+                    attested_at_unix_seconds,
+                    expected_payout_unix_seconds,
+                },
+            );
+        }
+    }
+
+    for nostr_event in nostr_events {
+        if nostr_event.kind != FutureEventPayoutAttestationPledge::KIND {
+            continue;
+        }
+        let Ok((pledger, pledge)) =
+            FutureEventPayoutAttestationPledge::interpret_nostr_event(nostr_event)
+        else {
+            continue;
+        };
+        if pledger != oracle || pledge.event_hash_hex != event_payout.event_hash_hex {
+            continue;
+        }
+        let pledge_nostr_event_id = NostrEventIdHex(nostr_event.id.to_hex());
+
+        if let Some(terms) = &pledge.terms {
+            let attestation_deadline_unix_seconds = terms.attestation_deadline_unix_seconds;
+            if attested_at_unix_seconds
+                > attestation_deadline_unix_seconds.saturating_add(tolerance_seconds)
+            {
+                report.push(
+                    policy.after_pledge_deadline,
+                    AttestationTimingIssue::AfterPledgeDeadline {
+                        pledge_nostr_event_id: pledge_nostr_event_id.clone(),
+                        attested_at_unix_seconds,
+                        attestation_deadline_unix_seconds,
+                    },
+                );
+            }
+        }
+
+        let pledged_at_unix_seconds = nostr_event.created_at.as_u64();
+        if pledged_at_unix_seconds > attested_at_unix_seconds.saturating_add(tolerance_seconds) {
+            report.push(
+                policy.pledge_after_attestation,
+                AttestationTimingIssue::PledgeAfterAttestation {
+                    pledge_nostr_event_id,
+                    attested_at_unix_seconds,
+                    pledged_at_unix_seconds,
+                },
+            );
+        }
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,120 @@
+use nostr::event::{Event as NostrEvent, EventBuilder as NostrEventBuilder};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    trait_dec::{verify_signature_verified_nostr_event, Res, SignatureVerified},
+    InterpretOptions, NostrEventUtils, NostrPublicKeyHex,
+};
+use crate::{
+    merkle::{is_lowercase_hash_hex, PayoutInclusionProof, PayoutMerkleTree},
+    Error, EventPayout,
+};
+
+/// Merkle root and leaf count of a [PayoutMerkleTree], the content of an [EventPayoutBatchAttestation].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct BatchCommitment {
+    pub merkle_root_hex: String,
+    pub leaf_count: u64,
+}
+
+impl From<&PayoutMerkleTree> for BatchCommitment {
+    fn from(tree: &PayoutMerkleTree) -> Self {
+        Self {
+            merkle_root_hex: tree.root_hex(),
+            leaf_count: tree.leaf_count(),
+        }
+    }
+}
+
+/// [NostrEvent] attesting to every [EventPayout] of a [PayoutMerkleTree] at once.
+///
+/// - kind set to [EventPayoutBatchAttestation::KIND]
+/// - content set to [BatchCommitment] as json
+///
+/// There are no event hash tags, the [PayoutMerkleTree] or the [PayoutInclusionProof]s have to be shared separately.
+/// IMPORTANT: unlike [super::EventPayoutAttestation], batch attestations are not considered by
+/// [super::resolve_attestation_history] and [crate::aggregation::aggregate_event_payout].
+///
+/// IMPORTANT: only [PayoutMerkleTree::new] rejects two payouts of the same [crate::Event], a [PayoutInclusionProof] does
+/// not show that its payout is the only one. A signer can commit to conflicting payouts in one batch and share a
+/// different proof with each party. Trust a batch for an event only from a signer you would trust not to equivocate,
+/// or check uniqueness by rebuilding the [PayoutMerkleTree] from all its payouts and comparing the root.
+pub struct EventPayoutBatchAttestation;
+
+impl NostrEventUtils for EventPayoutBatchAttestation {
+    const KIND_U16: u16 = 6282;
+
+    type CreateParameter = BatchCommitment;
+
+    /// Accepts [BatchCommitment], a [PayoutMerkleTree] can be converted with [From].
+    ///
+    /// Returns [NostrEventBuilder] with:
+    /// - kind set to [EventPayoutBatchAttestation::KIND]
+    /// - content set to [BatchCommitment] as json
+    fn create_nostr_event_builder(commitment: &Self::CreateParameter) -> Res<NostrEventBuilder> {
+        let content = serde_json::to_string(commitment)?;
+        let builder = NostrEventBuilder::new(Self::KIND, content, []);
+
+        Ok(builder)
+    }
+
+    type InterpretResult = (NostrPublicKeyHex, BatchCommitment);
+
+    /// Accepts [NostrEvent].
+    ///
+    /// Returns [NostrPublicKeyHex] and the [BatchCommitment] it signed.
+    fn interpret_nostr_event(nostr_event: &NostrEvent) -> Res<Self::InterpretResult> {
+        Self::interpret_nostr_event_with_options(nostr_event, &InterpretOptions::default())
+    }
+    /// Accepts [NostrEvent] and [InterpretOptions].
+    ///
+    /// Return information can be found in [`Self::interpret_nostr_event`]
+    fn interpret_nostr_event_with_options(
+        nostr_event: &NostrEvent,
+        options: &InterpretOptions,
+    ) -> Res<Self::InterpretResult> {
+        let signature_verified = SignatureVerified::verify(nostr_event)?;
+
+        Self::interpret_nostr_event_signature_verified(nostr_event, options, signature_verified)
+    }
+    /// Accepts [NostrEvent] whose signature is [SignatureVerified] and [InterpretOptions].
+    ///
+    /// Return information can be found in [`Self::interpret_nostr_event`]
+    fn interpret_nostr_event_signature_verified(
+        nostr_event: &NostrEvent,
+        options: &InterpretOptions,
+        signature_verified: SignatureVerified,
+    ) -> Res<Self::InterpretResult> {
+        verify_signature_verified_nostr_event(nostr_event, options, signature_verified)?;
+
+        let nostr_public_key_hex = NostrPublicKeyHex(nostr_event.pubkey.to_hex());
+        let commitment: BatchCommitment = serde_json::from_str(&nostr_event.content)?;
+        if commitment.leaf_count == 0 {
+            return Err(Error::Validation("batch has no leaves".to_string()));
+        }
+        if !is_lowercase_hash_hex(&commitment.merkle_root_hex) {
+            return Err(Error::Validation(
+                "merkle root hex is not 64 lowercase hex characters".to_string(),
+            ));
+        }
+
+        Ok((nostr_public_key_hex, commitment))
+    }
+}
+
+impl EventPayoutBatchAttestation {
+    /// Interpret [NostrEvent] and verify that [PayoutInclusionProof::event_payout] is part of the batch.
+    ///
+    /// Returns [NostrPublicKeyHex] and the [EventPayout] it attested to.
+    /// IMPORTANT: [EventPayout] is not validated and may not be the only payout of its event in the batch, see
+    /// [EventPayoutBatchAttestation].
+    pub fn verify_event_payout(
+        nostr_event: &NostrEvent,
+        proof: &PayoutInclusionProof,
+    ) -> Res<(NostrPublicKeyHex, EventPayout)> {
+        let (nostr_public_key_hex, commitment) = Self::interpret_nostr_event(nostr_event)?;
+        proof.verify(&commitment.merkle_root_hex, commitment.leaf_count)?;
+
+        Ok((nostr_public_key_hex, proof.event_payout.clone()))
+    }
+}
@@ -0,0 +1,264 @@
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+
+use k256::{
+    elliptic_curve::{
+        group::Group, ops::Reduce, point::DecompressPoint, subtle::Choice, PrimeField,
+    },
+    AffinePoint, FieldBytes, ProjectivePoint, Scalar, U256,
+};
+use nostr::{
+    event::{Event as NostrEvent, EventId},
+    key::PublicKey,
+};
+use rand::Rng;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use sha2::{Digest, Sha256};
+
+use super::{
+    trait_dec::{Res, SignatureVerified},
+    InterpretOptions, Interpreted, NostrEventUtils,
+};
+use crate::Error;
+
+/// Number of signatures verified together.
+const SIGNATURE_BATCH_SIZE: usize = 1024;
+
+/// Failed batches are halved down to this size, smaller ones are verified one by one.
+const MIN_SIGNATURE_BATCH_SIZE: usize = 128;
+
+/// Result of [NostrEventUtils::interpret_nostr_events_bulk], every nostr event is reported by its index in the input.
+#[derive(Debug)]
+pub struct BulkInterpretation<T> {
+    /// Interpreted nostr events in input order.
+    pub interpreted: Vec<(usize, Interpreted<T>)>,
+
+    /// Nostr events that failed to interpret in input order, each with its own error.
+    pub invalid: Vec<(usize, Error)>,
+
+    /// Nostr events skipped because an earlier nostr event of the input has the same id and signature.
+    pub duplicates: Vec<usize>,
+}
+
+impl<T> BulkInterpretation<T> {
+    /// Returns true if no nostr event failed to interpret.
+    pub fn is_all_valid(&self) -> bool {
+        self.invalid.is_empty()
+    }
+}
+
+pub(super) fn interpret_bulk<T: NostrEventUtils + ?Sized>(
+    nostr_events: &[NostrEvent],
+    options: &InterpretOptions,
+) -> BulkInterpretation<T::InterpretResult>
+where
+    T::InterpretResult: Send,
+{
+    let mut seen = HashSet::with_capacity(nostr_events.len());
+    let mut unique = Vec::with_capacity(nostr_events.len());
+    let mut duplicates = Vec::new();
+    for (index, nostr_event) in nostr_events.iter().enumerate() {
+        if seen.insert((nostr_event.id, nostr_event.sig.serialize())) {
+            unique.push(index);
+        } else {
+            duplicates.push(index);
+        }
+    }
+
+    let interpret_batch = |batch: &[usize]| {
+        let batch_events: Vec<&NostrEvent> =
+            batch.iter().map(|index| &nostr_events[*index]).collect();
+        let mut verified = vec![false; batch.len()];
+        verify_batches(&batch_events, &mut verified);
+        batch
+            .iter()
+            .zip(verified)
+            .map(|(index, verified)| {
+                (
+                    *index,
+                    interpret_one::<T>(&nostr_events[*index], options, verified),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    #[cfg(feature = "rayon")]
+    let results: Vec<_> = unique
+        .par_chunks(SIGNATURE_BATCH_SIZE)
+        .flat_map_iter(interpret_batch)
+        .collect();
+    #[cfg(not(feature = "rayon"))]
+    let results: Vec<_> = unique
+        .chunks(SIGNATURE_BATCH_SIZE)
+        .flat_map(interpret_batch)
+        .collect();
+
+    let mut interpreted = Vec::with_capacity(results.len());
+    let mut invalid = Vec::new();
+    for (index, result) in results {
+        match result {
+            Ok(value) => interpreted.push((index, value)),
+            Err(e) => invalid.push((index, e)),
+        }
+    }
+
+    BulkInterpretation {
+        interpreted,
+        invalid,
+        duplicates,
+    }
+}
+
+fn interpret_one<T: NostrEventUtils + ?Sized>(
+    nostr_event: &NostrEvent,
+    options: &InterpretOptions,
+    signature_verified: bool,
+) -> Res<Interpreted<T::InterpretResult>> {
+    if nostr_event.kind != T::KIND {
+        return Err(Error::Validation(format!(
+            "nostr event kind is not {}",
+            T::KIND_U16
+        )));
+    }
+
+    if signature_verified {
+        T::interpret_nostr_event_signature_verified(
+            nostr_event,
+            options,
+            SignatureVerified::batch_verified(nostr_event),
+        )
+        .map(|interpret_result| Interpreted::new(nostr_event, interpret_result))
+    } else {
+        T::interpret_nostr_event_enveloped_with_options(nostr_event, options)
+    }
+}
+
+/// Marks the nostr events whose signatures are valid in a batch, halving failed batches to find the invalid ones.
+fn verify_batches(nostr_events: &[&NostrEvent], verified: &mut [bool]) {
+    if nostr_events.len() < MIN_SIGNATURE_BATCH_SIZE {
+        return;
+    }
+    if verify_signatures(nostr_events.iter().copied()) {
+        verified.fill(true);
+        return;
+    }
+    let middle = nostr_events.len() / 2;
+    let (verified_left, verified_right) = verified.split_at_mut(middle);
+    verify_batches(&nostr_events[..middle], verified_left);
+    verify_batches(&nostr_events[middle..], verified_right);
+}
+
+/// BIP340 batch verification of the signatures of nostr_events over their ids.
+///
+/// Checks a random linear combination of all signature equations at once, so returns false if any signature is
+/// invalid, with negligible probability of returning true then. Nostr events of the same author share one point.
+pub(crate) fn verify_signatures<'a>(nostr_events: impl Iterator<Item = &'a NostrEvent>) -> bool {
+    let mut rng = rand::thread_rng();
+    let mut terms = Vec::new();
+    let mut authors: HashMap<PublicKey, (AffinePoint, Scalar)> = HashMap::new();
+    let mut s_sum = Scalar::ZERO;
+    for (i, nostr_event) in nostr_events.enumerate() {
+        let sig = nostr_event.sig.serialize();
+        let (r, s) = sig.split_at(32);
+        let pubkey = nostr_event.pubkey.to_bytes();
+        let (Some(big_r), Some(s)) = (
+            lift_x(r),
+            Option::<Scalar>::from(Scalar::from_repr(*FieldBytes::from_slice(s))),
+        ) else {
+            return false;
+        };
+        let author = match authors.entry(nostr_event.pubkey) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let Some(big_p) = lift_x(&pubkey) else {
+                    return false;
+                };
+                entry.insert((big_p, Scalar::ZERO))
+            }
+        };
+        let e = <Scalar as Reduce<U256>>::reduce_bytes(&challenge(r, &pubkey, nostr_event.id));
+        // the first coefficient is 1, the others random 128 bit numbers
+        let a = if i == 0 {
+            Scalar::ONE
+        } else {
+            Scalar::from(rng.gen::<u128>())
+        };
+        s_sum += a * s;
+        author.1 += a * e;
+        terms.push((big_r, a));
+    }
+    if terms.is_empty() {
+        return true;
+    }
+    terms.extend(authors.into_values());
+    terms.push((AffinePoint::GENERATOR, -s_sum));
+
+    bool::from(multi_scalar_mul(&terms).is_identity())
+}
+
+/// Point with x coordinate and even y, as in BIP340.
+fn lift_x(x: &[u8]) -> Option<AffinePoint> {
+    AffinePoint::decompress(FieldBytes::from_slice(x), Choice::from(0)).into()
+}
+
+fn challenge(r: &[u8], pubkey: &[u8], id: EventId) -> FieldBytes {
+    let tag = Sha256::digest(b"BIP0340/challenge");
+    Sha256::new()
+        .chain_update(tag)
+        .chain_update(tag)
+        .chain_update(r)
+        .chain_update(pubkey)
+        .chain_update(id.as_bytes())
+        .finalize()
+}
+
+/// Sum of point times scalar of all terms with the bucket method, in variable time.
+///
+/// Only used on public values, it is much faster than multiplying each point on its own.
+fn multi_scalar_mul(terms: &[(AffinePoint, Scalar)]) -> ProjectivePoint {
+    let width = (usize::BITS - terms.len().leading_zeros()).clamp(4, 18) as usize - 2;
+    let digits: Vec<Vec<i64>> = terms
+        .iter()
+        .map(|(_, scalar)| signed_digits(&scalar.to_bytes(), width))
+        .collect();
+    let mut sum = ProjectivePoint::IDENTITY;
+    for window in (0..=256 / width).rev() {
+        for _ in 0..width {
+            sum = sum.double();
+        }
+        let mut buckets = vec![ProjectivePoint::IDENTITY; 1 << (width - 1)];
+        for ((point, _), digits) in terms.iter().zip(&digits) {
+            match digits[window] {
+                0 => {}
+                digit if digit > 0 => buckets[digit as usize - 1] += point,
+                digit => buckets[digit.unsigned_abs() as usize - 1] -= point,
+            }
+        }
+        // bucket i is added i + 1 times
+        let mut running = ProjectivePoint::IDENTITY;
+        for bucket in buckets.iter().rev() {
+            running += bucket;
+            sum += running;
+        }
+    }
+
+    sum
+}
+
+/// Digits of a big endian scalar in base 2^width, from the lowest, each between -2^(width-1) and 2^(width-1).
+fn signed_digits(scalar: &FieldBytes, width: usize) -> Vec<i64> {
+    let half = 1 << (width - 1);
+    let mut carry = 0;
+    (0..=256 / width)
+        .map(|window| {
+            let offset = window * width;
+            let bits = (offset..(offset + width).min(256))
+                .rev()
+                .fold(0, |bits, bit| {
+                    (bits << 1) | i64::from((scalar[31 - bit / 8] >> (bit % 8)) & 1)
+                });
+            let digit = bits + carry;
+            carry = i64::from(digit > half);
+            digit - (carry << width)
+        })
+        .collect()
+}
@@ -0,0 +1,313 @@
+use std::{any::Any, collections::HashMap, fmt::Debug, sync::Arc};
+
+use nostr::event::Event as NostrEvent;
+
+#[allow(unused_imports)]
+use super::PrivateNostrEventUtils;
+use super::{
+    trait_dec::{verify_nostr_event, Res},
+    AttestationCorrection, AttestationDispute, BatchCommitment, EventPayoutAttestation,
+    EventPayoutAttestationCorrection, EventPayoutAttestationDispute, EventPayoutBatchAttestation,
+    FutureEventPayoutAttestationPledge, InterpretOptions, NewEvent, NostrEventMetadata,
+    NostrEventUtils, OracleProfileAnnouncement, PrivateEnvelope, PrivateEventPayoutAttestation,
+    PrivateNewEvent, ReferencedEventPayout, ReferencedEventPayoutAttestation,
+};
+use crate::{AttestationPledge, Error, Event, OracleProfile};
+
+/// Any [NostrEvent] interpreted by [interpret_any] or [KindRegistry::interpret], one variant per kind.
+#[derive(Debug, Clone)]
+pub enum PredictionMarketMessage {
+    /// [NewEvent]. IMPORTANT: [Event] is not validated.
+    NewEvent {
+        metadata: NostrEventMetadata,
+        event: Event,
+    },
+
+    /// [FutureEventPayoutAttestationPledge]. IMPORTANT: [AttestationPledge] is not validated.
+    Pledge {
+        metadata: NostrEventMetadata,
+        pledge: AttestationPledge,
+    },
+
+    /// [EventPayoutAttestation]. IMPORTANT: [ReferencedEventPayout] is not validated.
+    Attestation {
+        metadata: NostrEventMetadata,
+        referenced_event_payout: ReferencedEventPayout,
+    },
+
+    /// [EventPayoutAttestationCorrection]. IMPORTANT: [AttestationCorrection] is not validated.
+    Correction {
+        metadata: NostrEventMetadata,
+        correction: AttestationCorrection,
+    },
+
+    /// [EventPayoutAttestationDispute]. IMPORTANT: [AttestationDispute] is not validated.
+    Dispute {
+        metadata: NostrEventMetadata,
+        dispute: AttestationDispute,
+    },
+
+    /// [OracleProfileAnnouncement]. IMPORTANT: [OracleProfile] is not validated.
+    OracleProfile {
+        metadata: NostrEventMetadata,
+        profile: OracleProfile,
+    },
+
+    /// [EventPayoutBatchAttestation]. Use [EventPayoutBatchAttestation::verify_event_payout] to read an [crate::EventPayout] of the batch.
+    BatchAttestation {
+        metadata: NostrEventMetadata,
+        commitment: BatchCommitment,
+    },
+
+    /// [PrivateNewEvent], not decrypted. Use [PrivateNostrEventUtils::decrypt_nostr_event] to read the [Event].
+    PrivateNewEvent {
+        metadata: NostrEventMetadata,
+        envelope: PrivateEnvelope,
+    },
+
+    /// [PrivateEventPayoutAttestation], not decrypted. Use [PrivateNostrEventUtils::decrypt_nostr_event] to read the [ReferencedEventPayout].
+    PrivateAttestation {
+        metadata: NostrEventMetadata,
+        envelope: PrivateEnvelope,
+    },
+
+    /// Kind registered with [KindRegistry::register], value is its [NostrEventUtils::InterpretResult].
+    /// Use [PredictionMarketMessage::custom_value] to read it.
+    Custom {
+        metadata: NostrEventMetadata,
+        kind: u16,
+        value: Arc<dyn Any + Send + Sync>,
+    },
+
+    /// Kind that is neither built in nor registered. Only the signature was verified.
+    Unknown {
+        metadata: NostrEventMetadata,
+        kind: u16,
+    },
+}
+
+impl PredictionMarketMessage {
+    pub fn metadata(&self) -> &NostrEventMetadata {
+        match self {
+            Self::NewEvent { metadata, .. }
+            | Self::Pledge { metadata, .. }
+            | Self::Attestation { metadata, .. }
+            | Self::Correction { metadata, .. }
+            | Self::Dispute { metadata, .. }
+            | Self::OracleProfile { metadata, .. }
+            | Self::BatchAttestation { metadata, .. }
+            | Self::PrivateNewEvent { metadata, .. }
+            | Self::PrivateAttestation { metadata, .. }
+            | Self::Custom { metadata, .. }
+            | Self::Unknown { metadata, .. } => metadata,
+        }
+    }
+
+    /// Kind of the nostr event the message was interpreted from.
+    pub fn kind(&self) -> u16 {
+        match self {
+            Self::NewEvent { .. } => NewEvent::KIND_U16,
+            Self::Pledge { .. } => FutureEventPayoutAttestationPledge::KIND_U16,
+            Self::Attestation { .. } => EventPayoutAttestation::KIND_U16,
+            Self::Correction { .. } => EventPayoutAttestationCorrection::KIND_U16,
+            Self::Dispute { .. } => EventPayoutAttestationDispute::KIND_U16,
+            Self::OracleProfile { .. } => OracleProfileAnnouncement::KIND_U16,
+            Self::BatchAttestation { .. } => EventPayoutBatchAttestation::KIND_U16,
+            Self::PrivateNewEvent { .. } => PrivateNewEvent::KIND_U16,
+            Self::PrivateAttestation { .. } => PrivateEventPayoutAttestation::KIND_U16,
+            Self::Custom { kind, .. } | Self::Unknown { kind, .. } => *kind,
+        }
+    }
+
+    /// Returns the [NostrEventUtils::InterpretResult] of T if this is a [PredictionMarketMessage::Custom] of T.
+    pub fn custom_value<T: NostrEventUtils>(&self) -> Option<&T::InterpretResult>
+    where
+        T::InterpretResult: 'static,
+    {
+        match self {
+            Self::Custom { kind, value, .. } if *kind == T::KIND_U16 => value.downcast_ref(),
+            _ => Option::None,
+        }
+    }
+}
+
+type CustomInterpreter =
+    Box<dyn Fn(&NostrEvent, &InterpretOptions) -> Res<Arc<dyn Any + Send + Sync>> + Send + Sync>;
+
+/// Dispatcher routing [NostrEvent]s to the interpreter of their kind, extensible with custom kinds.
+#[derive(Default)]
+pub struct KindRegistry {
+    custom: HashMap<u16, CustomInterpreter>,
+}
+
+impl Debug for KindRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut kinds: Vec<&u16> = self.custom.keys().collect();
+        kinds.sort();
+        f.debug_struct("KindRegistry")
+            .field("custom_kinds", &kinds)
+            .finish()
+    }
+}
+
+impl KindRegistry {
+    /// Kinds interpreted without registration.
+    pub const BUILT_IN_KINDS: &'static [u16] = &[
+        NewEvent::KIND_U16,
+        FutureEventPayoutAttestationPledge::KIND_U16,
+        EventPayoutAttestation::KIND_U16,
+        EventPayoutAttestationCorrection::KIND_U16,
+        EventPayoutAttestationDispute::KIND_U16,
+        OracleProfileAnnouncement::KIND_U16,
+        EventPayoutBatchAttestation::KIND_U16,
+        PrivateNewEvent::KIND_U16,
+        PrivateEventPayoutAttestation::KIND_U16,
+    ];
+
+    /// Create [KindRegistry] with only the built in kinds.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register T, so that its nostr events are interpreted to [PredictionMarketMessage::Custom].
+    ///
+    /// Fails if the kind of T is built in or already registered.
+    pub fn register<T: NostrEventUtils>(&mut self) -> Res<()>
+    where
+        T::InterpretResult: Send + Sync + 'static,
+    {
+        if Self::BUILT_IN_KINDS.contains(&T::KIND_U16) || self.custom.contains_key(&T::KIND_U16) {
+            return Err(Error::Validation(format!(
+                "kind {} is already registered",
+                T::KIND_U16
+            )));
+        }
+        self.custom.insert(
+            T::KIND_U16,
+            Box::new(|nostr_event, options| {
+                let value = T::interpret_nostr_event_with_options(nostr_event, options)?;
+                Ok(Arc::new(value))
+            }),
+        );
+
+        Ok(())
+    }
+
+    /// Interpret [NostrEvent] of any kind using default [InterpretOptions].
+    ///
+    /// See [KindRegistry::interpret_with_options].
+    pub fn interpret(&self, nostr_event: &NostrEvent) -> Res<PredictionMarketMessage> {
+        self.interpret_with_options(nostr_event, &InterpretOptions::default())
+    }
+
+    /// Interpret [NostrEvent] of any kind with the interpreter of its kind.
+    ///
+    /// Fails if the signature is invalid or the interpreter of the kind fails.
+    pub fn interpret_with_options(
+        &self,
+        nostr_event: &NostrEvent,
+        options: &InterpretOptions,
+    ) -> Res<PredictionMarketMessage> {
+        let metadata = NostrEventMetadata::from_nostr_event(nostr_event);
+        let kind = nostr_event.kind.as_u16();
+
+        let message = match kind {
+            NewEvent::KIND_U16 => PredictionMarketMessage::NewEvent {
+                metadata,
+                event: NewEvent::interpret_nostr_event_with_options(nostr_event, options)?,
+            },
+            FutureEventPayoutAttestationPledge::KIND_U16 => {
+                let (_, pledge) =
+                    FutureEventPayoutAttestationPledge::interpret_nostr_event_with_options(
+                        nostr_event,
+                        options,
+                    )?;
+                PredictionMarketMessage::Pledge { metadata, pledge }
+            }
+            EventPayoutAttestation::KIND_U16 => {
+                let (_, referenced_event_payout) =
+                    ReferencedEventPayoutAttestation::interpret_nostr_event_with_options(
+                        nostr_event,
+                        options,
+                    )?;
+                PredictionMarketMessage::Attestation {
+                    metadata,
+                    referenced_event_payout,
+                }
+            }
+            EventPayoutAttestationCorrection::KIND_U16 => {
+                let (_, correction) =
+                    EventPayoutAttestationCorrection::interpret_nostr_event_with_options(
+                        nostr_event,
+                        options,
+                    )?;
+                PredictionMarketMessage::Correction {
+                    metadata,
+                    correction,
+                }
+            }
+            EventPayoutAttestationDispute::KIND_U16 => {
+                let (_, dispute) =
+                    EventPayoutAttestationDispute::interpret_nostr_event_with_options(
+                        nostr_event,
+                        options,
+                    )?;
+                PredictionMarketMessage::Dispute { metadata, dispute }
+            }
+            OracleProfileAnnouncement::KIND_U16 => {
+                let (_, profile) = OracleProfileAnnouncement::interpret_nostr_event_with_options(
+                    nostr_event,
+                    options,
+                )?;
+                PredictionMarketMessage::OracleProfile { metadata, profile }
+            }
+            EventPayoutBatchAttestation::KIND_U16 => {
+                let (_, commitment) =
+                    EventPayoutBatchAttestation::interpret_nostr_event_with_options(
+                        nostr_event,
+                        options,
+                    )?;
+                PredictionMarketMessage::BatchAttestation {
+                    metadata,
+                    commitment,
+                }
+            }
+            PrivateNewEvent::KIND_U16 => PredictionMarketMessage::PrivateNewEvent {
+                metadata,
+                envelope: PrivateNewEvent::interpret_nostr_event_with_options(
+                    nostr_event,
+                    options,
+                )?,
+            },
+            PrivateEventPayoutAttestation::KIND_U16 => {
+                PredictionMarketMessage::PrivateAttestation {
+                    metadata,
+                    envelope: PrivateEventPayoutAttestation::interpret_nostr_event_with_options(
+                        nostr_event,
+                        options,
+                    )?,
+                }
+            }
+            kind => match self.custom.get(&kind) {
+                Some(interpret) => PredictionMarketMessage::Custom {
+                    metadata,
+                    kind,
+                    value: interpret(nostr_event, options)?,
+                },
+                Option::None => {
+                    verify_nostr_event(nostr_event, options)?;
+                    PredictionMarketMessage::Unknown { metadata, kind }
+                }
+            },
+        };
+
+        Ok(message)
+    }
+}
+
+/// Interpret [NostrEvent] of any built in kind using default [InterpretOptions].
+///
+/// Same as [KindRegistry::interpret] of [KindRegistry::new], use a [KindRegistry] to interpret custom kinds.
+pub fn interpret_any(nostr_event: &NostrEvent) -> Res<PredictionMarketMessage> {
+    KindRegistry::new().interpret(nostr_event)
+}
@@ -0,0 +1,94 @@
+use nostr::{
+    event::{Event as NostrEvent, Tag, TagStandard},
+    types::{Alphabet, Filter, SingleLetterTag},
+};
+
+use crate::{Error, EventHashHex};
+
+/// NIP-32 label namespace of the tag carrying [EventHashHex].
+///
+/// The hash is written as `["L", EVENT_HASH_LABEL_NAMESPACE]` and `["l", <event hash hex>, EVENT_HASH_LABEL_NAMESPACE]`.
+pub const EVENT_HASH_LABEL_NAMESPACE: &str = "prediction-market-event";
+
+/// Which tags are read to find the [EventHashHex] a nostr event references.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum EventHashTagMode {
+    /// Only the namespaced label tag is read.
+    Dedicated,
+    /// The namespaced label tag is read and, if missing, a legacy hashtag with event hash hex format.
+    #[default]
+    Transition,
+}
+
+/// Tags referencing [EventHashHex].
+///
+/// Contains the namespaced label tag and, for clients that do not read it yet, the legacy hashtag.
+pub fn event_hash_tags(event_hash_hex: &EventHashHex) -> Vec<Tag> {
+    vec![
+        TagStandard::LabelNamespace(EVENT_HASH_LABEL_NAMESPACE.to_owned()).into(),
+        TagStandard::Label(vec![
+            event_hash_hex.0.to_owned(),
+            EVENT_HASH_LABEL_NAMESPACE.to_owned(),
+        ])
+        .into(),
+        TagStandard::Hashtag(event_hash_hex.0.to_owned()).into(),
+    ]
+}
+
+/// Adds a constraint matching the namespaced label tag referencing [EventHashHex] to [Filter].
+pub fn filter_event_hash_label(filter: Filter, event_hash_hex: &EventHashHex) -> Filter {
+    filter.custom_tag(
+        SingleLetterTag::lowercase(Alphabet::L),
+        [event_hash_hex.0.to_owned()],
+    )
+}
+
+/// Reads the [EventHashHex] referenced by [NostrEvent].
+///
+/// Fails if there is no reference or if the references do not agree with each other.
+pub fn read_event_hash_tag(
+    nostr_event: &NostrEvent,
+    mode: EventHashTagMode,
+) -> Result<EventHashHex, Error> {
+    let mut labels = Vec::new();
+    let mut legacy_hashtags = Vec::new();
+    for tag in nostr_event.tags.iter() {
+        let tag = tag.as_slice();
+        match tag {
+            [kind, value, namespace, ..]
+                if kind == "l" && namespace == EVENT_HASH_LABEL_NAMESPACE =>
+            {
+                if !EventHashHex::is_valid_format(value) {
+                    return Err(Error::Validation(
+                        "nostr event hash label is not an event hash hex".to_string(),
+                    ));
+                }
+                labels.push(value.to_ascii_lowercase());
+            }
+            [kind, value, ..] if kind == "t" && EventHashHex::is_valid_format(value) => {
+                legacy_hashtags.push(value.to_ascii_lowercase());
+            }
+            _ => {}
+        }
+    }
+
+    let mut references = labels.iter().chain(legacy_hashtags.iter());
+    let Some(first) = references.next() else {
+        return Err(Error::Validation(
+            "nostr event does not reference an event hash".to_string(),
+        ));
+    };
+    if references.any(|r| r != first) {
+        return Err(Error::Validation(
+            "nostr event carries conflicting event hash references".to_string(),
+        ));
+    }
+
+    if labels.is_empty() && mode == EventHashTagMode::Dedicated {
+        return Err(Error::Validation(
+            "nostr event does not have an event hash label".to_string(),
+        ));
+    }
+
+    Ok(EventHashHex(first.to_owned()))
+}
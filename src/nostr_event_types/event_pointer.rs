@@ -0,0 +1,182 @@
+use std::str::FromStr;
+
+use bech32::{primitives::decode::CheckedHrpstring, Bech32, Hrp};
+use serde::{Deserialize, Serialize};
+
+use super::{trait_dec::Res, NostrPublicKeyHex};
+use crate::{
+    event::{byte_array_to_hex_string, hex_string_to_byte_array},
+    Error, EventHashHex,
+};
+
+/// Shareable NIP-19 style bech32 identifier of a [crate::Event], with hints on where and how to find nostr events about it.
+///
+/// Encoded with human readable part [EventPointer::HRP] and the TLV entries
+/// - 0: [EventPointer::event_hash_hex] as 32 bytes, exactly once
+/// - 1: [EventPointer::relays] as utf-8, any number of times
+/// - 2: [EventPointer::creator] as 32 bytes, at most once
+/// - 3: [EventPointer::kind] as big-endian u32, at most once
+///
+/// Unknown TLV entries are ignored when decoding.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct EventPointer {
+    pub event_hash_hex: EventHashHex,
+
+    /// Relays nostr events about the [crate::Event] can be found on.
+    pub relays: Vec<String>,
+
+    /// Signer of the pointed to nostr event, for example the creator of the [super::NewEvent] or the oracle of an [super::EventPayoutAttestation].
+    pub creator: Option<NostrPublicKeyHex>,
+
+    /// Kind of the pointed to nostr event, for example the kind of [super::NewEvent] or [super::FutureEventPayoutAttestationPledge].
+    pub kind: Option<u16>,
+}
+
+impl EventPointer {
+    /// Human readable part of the bech32 encoding.
+    pub const HRP: &'static str = "pmevent";
+
+    const TLV_EVENT_HASH: u8 = 0;
+    const TLV_RELAY: u8 = 1;
+    const TLV_CREATOR: u8 = 2;
+    const TLV_KIND: u8 = 3;
+
+    /// Encode [EventPointer] as bech32 string.
+    pub fn to_bech32(&self) -> Res<String> {
+        let mut data = Vec::new();
+        push_tlv(
+            &mut data,
+            Self::TLV_EVENT_HASH,
+            &hex_32_bytes(&self.event_hash_hex.0)?,
+        )?;
+        for relay in self.relays.iter() {
+            push_tlv(&mut data, Self::TLV_RELAY, relay.as_bytes())?;
+        }
+        if let Some(creator) = &self.creator {
+            push_tlv(&mut data, Self::TLV_CREATOR, &hex_32_bytes(&creator.0)?)?;
+        }
+        if let Some(kind) = self.kind {
+            push_tlv(&mut data, Self::TLV_KIND, &u32::from(kind).to_be_bytes())?;
+        }
+
+        let hrp = Hrp::parse_unchecked(Self::HRP);
+        let s = bech32::encode::<Bech32>(hrp, &data)?;
+
+        Ok(s)
+    }
+
+    /// Decode [EventPointer] from bech32 string.
+    pub fn from_bech32(s: &str) -> Res<Self> {
+        let checked = CheckedHrpstring::new::<Bech32>(s)?;
+        if checked.hrp() != Hrp::parse_unchecked(Self::HRP) {
+            return Err(Error::Validation(format!(
+                "bech32 human readable part is not {}",
+                Self::HRP
+            )));
+        }
+        let data: Vec<u8> = checked.byte_iter().collect();
+
+        let mut event_hash_hex = None;
+        let mut relays = Vec::new();
+        let mut creator = None;
+        let mut kind = None;
+        let mut rest = data.as_slice();
+        while !rest.is_empty() {
+            let [t, l, tail @ ..] = rest else {
+                return Err(Error::Validation("truncated tlv entry".to_string()));
+            };
+            let l = usize::from(*l);
+            if tail.len() < l {
+                return Err(Error::Validation("truncated tlv entry".to_string()));
+            }
+            let (value, tail) = tail.split_at(l);
+            rest = tail;
+
+            match *t {
+                Self::TLV_EVENT_HASH => {
+                    let hash = EventHashHex(byte_array_to_hex_string(exactly_32(value)?));
+                    if event_hash_hex.replace(hash).is_some() {
+                        return Err(Error::Validation("more than one event hash".to_string()));
+                    }
+                }
+                Self::TLV_RELAY => {
+                    let relay = String::from_utf8(value.to_vec())
+                        .map_err(|_| Error::Validation("relay is not utf-8".to_string()))?;
+                    relays.push(relay);
+                }
+                Self::TLV_CREATOR => {
+                    let pk = NostrPublicKeyHex(byte_array_to_hex_string(exactly_32(value)?));
+                    if creator.replace(pk).is_some() {
+                        return Err(Error::Validation("more than one creator".to_string()));
+                    }
+                }
+                Self::TLV_KIND => {
+                    let bytes: [u8; 4] = value
+                        .try_into()
+                        .map_err(|_| Error::Validation("kind is not 4 bytes".to_string()))?;
+                    let k = u16::try_from(u32::from_be_bytes(bytes))
+                        .map_err(|_| Error::Validation("kind is out of range".to_string()))?;
+                    if kind.replace(k).is_some() {
+                        return Err(Error::Validation("more than one kind".to_string()));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let Some(event_hash_hex) = event_hash_hex else {
+            return Err(Error::Validation("missing event hash".to_string()));
+        };
+
+        Ok(Self {
+            event_hash_hex,
+            relays,
+            creator,
+            kind,
+        })
+    }
+}
+
+impl From<EventHashHex> for EventPointer {
+    fn from(event_hash_hex: EventHashHex) -> Self {
+        Self {
+            event_hash_hex,
+            relays: Vec::new(),
+            creator: None,
+            kind: None,
+        }
+    }
+}
+
+impl FromStr for EventPointer {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_bech32(s)
+    }
+}
+
+fn push_tlv(data: &mut Vec<u8>, t: u8, value: &[u8]) -> Res<()> {
+    let l = u8::try_from(value.len())
+        .map_err(|_| Error::Validation("tlv value is longer than 255 bytes".to_string()))?;
+    data.push(t);
+    data.push(l);
+    data.extend_from_slice(value);
+
+    Ok(())
+}
+
+fn hex_32_bytes(hex: &str) -> Res<Vec<u8>> {
+    let bytes = hex_string_to_byte_array(hex).map_err(|e| Error::Validation(e.to_string()))?;
+    exactly_32(&bytes)?;
+
+    Ok(bytes)
+}
+
+fn exactly_32(bytes: &[u8]) -> Res<&[u8]> {
+    if bytes.len() != 32 {
+        return Err(Error::Validation("value is not 32 bytes".to_string()));
+    }
+
+    Ok(bytes)
+}
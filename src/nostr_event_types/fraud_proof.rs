@@ -0,0 +1,326 @@
+use std::collections::{HashMap, HashSet};
+
+use nostr::event::{Event as NostrEvent, EventId};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    trait_dec::Res, AttestationCorrection, EventPayoutAttestation,
+    EventPayoutAttestationCorrection, FutureEventPayoutAttestationPledge, NostrEventUtils,
+    NostrPublicKeyHex,
+};
+use crate::{AttestationPledge, Error, EventHashHex, PayoutUnit};
+
+/// Self-contained proof that an oracle misbehaved, created by [detect_fraud].
+///
+/// Bundles the nostr events signed by the oracle, so that anyone can check it with [FraudProof::verify] without fetching anything.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum FraudProof {
+    /// Two [EventPayoutAttestation]s by the same oracle for the same [crate::Event] with different payouts.
+    ConflictingAttestations {
+        first: NostrEvent,
+        second: NostrEvent,
+    },
+
+    /// [EventPayoutAttestationCorrection] made after the correction window of the nostr event it corrects.
+    LateCorrection {
+        corrected: NostrEvent,
+        correction: NostrEvent,
+    },
+}
+
+impl FraudProof {
+    /// Try to create json string from [FraudProof]
+    pub fn try_to_json_string(&self) -> Result<String, Error> {
+        serde_json::to_string(self).map_err(|e| e.into())
+    }
+
+    /// Try to parse json string into [FraudProof]. [FraudProof] is not verified.
+    pub fn try_from_json_str(json: &str) -> Result<Self, Error> {
+        serde_json::from_str(json).map_err(|e| e.into())
+    }
+
+    /// Public key of the oracle the proof is about.
+    pub fn oracle(&self) -> NostrPublicKeyHex {
+        let nostr_event = match self {
+            Self::ConflictingAttestations { first, .. } => first,
+            Self::LateCorrection { correction, .. } => correction,
+        };
+
+        NostrPublicKeyHex(nostr_event.pubkey.to_hex())
+    }
+
+    /// Verify signatures of the bundled nostr events and that they prove the misbehavior.
+    ///
+    /// correction_window_seconds is the window of your own policy, like [FraudDetectionOptions::correction_window_seconds].
+    /// A [FraudProof::LateCorrection] does not verify with [Option::None], corrections are then allowed at any time.
+    pub fn verify(&self, correction_window_seconds: Option<u64>) -> Res<()> {
+        match self {
+            Self::ConflictingAttestations { first, second } => {
+                let (first_oracle, first) = interpret_kind::<EventPayoutAttestation>(first)?;
+                let (second_oracle, second) = interpret_kind::<EventPayoutAttestation>(second)?;
+                if first_oracle != second_oracle {
+                    return Err(Error::Validation(
+                        "attestations are signed by different keys".to_string(),
+                    ));
+                }
+                if first.event_hash_hex != second.event_hash_hex {
+                    return Err(Error::Validation(
+                        "attestations are for different events".to_string(),
+                    ));
+                }
+                if first.units_per_outcome == second.units_per_outcome {
+                    return Err(Error::Validation(
+                        "attestations do not conflict".to_string(),
+                    ));
+                }
+            }
+            Self::LateCorrection {
+                corrected,
+                correction,
+            } => {
+                let Some(correction_window_seconds) = correction_window_seconds else {
+                    return Err(Error::Validation(
+                        "corrections are allowed at any time without correction window".to_string(),
+                    ));
+                };
+                let (oracle, correction_value) =
+                    interpret_kind::<EventPayoutAttestationCorrection>(correction)?;
+                let (corrected_oracle, corrected_event_hash_hex) = interpret_corrected(corrected)?;
+                if corrected_oracle != oracle
+                    || correction_value.corrected_nostr_event_id.0 != corrected.id.to_hex()
+                    || corrected_event_hash_hex != correction_value.event_hash_hex
+                {
+                    return Err(Error::Validation(
+                        "correction does not correct the nostr event".to_string(),
+                    ));
+                }
+                if !is_late(corrected, correction, correction_window_seconds) {
+                    return Err(Error::Validation(
+                        "correction was made within the correction window".to_string(),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// [FutureEventPayoutAttestationPledge] whose attestation deadline passed without an [EventPayoutAttestation], found by
+/// [detect_unfulfilled_pledges].
+///
+/// Not a [FraudProof]: an attestation made by the deadline may exist on relays that were not searched, and its absence
+/// can not be proven. late_attestation is the first attestation made after the deadline, if any.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct UnfulfilledPledge {
+    pub pledge: NostrEvent,
+    pub late_attestation: Option<NostrEvent>,
+}
+
+/// Options for [detect_fraud].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct FraudDetectionOptions {
+    /// Seconds after an attestation or correction within which it may be corrected. [Option::None] allows corrections at any time.
+    pub correction_window_seconds: Option<u64>,
+}
+
+/// Finds misbehavior of oracles and returns a [FraudProof] for each.
+///
+/// Accepts any number of [NostrEvent]s. Only [EventPayoutAttestation]s and [EventPayoutAttestationCorrection]s are
+/// used, other kinds and nostr events failing interpretation are ignored. Payouts of
+/// [super::EventPayoutBatchAttestation]s are not covered, they are only known with a [crate::merkle::PayoutInclusionProof].
+/// Reports:
+/// - an attestation with a payout that differs from the oldest attestation of the oracle for the same event, once per payout
+/// - a correction made after [FraudDetectionOptions::correction_window_seconds], if set
+///
+/// Corrections are not equivocation, a corrected attestation does not conflict with its correction.
+/// Unfulfilled pledges prove nothing on their own, use [detect_unfulfilled_pledges] for them.
+pub fn detect_fraud(
+    nostr_events: &[NostrEvent],
+    options: &FraudDetectionOptions,
+) -> Vec<FraudProof> {
+    let scan = Scan::new(nostr_events);
+    let mut fraud_proofs = Vec::new();
+
+    for group in scan.attestations.iter() {
+        let Some(((first, first_units), rest)) = group.split_first() else {
+            continue;
+        };
+        let mut reported: HashSet<&Vec<PayoutUnit>> = HashSet::new();
+        for (second, units) in rest {
+            if units != first_units && reported.insert(units) {
+                fraud_proofs.push(FraudProof::ConflictingAttestations {
+                    first: (*first).clone(),
+                    second: (*second).clone(),
+                });
+            }
+        }
+    }
+
+    if let Some(correction_window_seconds) = options.correction_window_seconds {
+        for (correction_nostr_event, correction) in scan.corrections {
+            let Some(corrected) = EventId::from_hex(&correction.corrected_nostr_event_id.0)
+                .ok()
+                .and_then(|id| scan.correctable.get(&id))
+            else {
+                continue;
+            };
+            let proof = FraudProof::LateCorrection {
+                corrected: (*corrected).clone(),
+                correction: correction_nostr_event.clone(),
+            };
+            if is_late(corrected, correction_nostr_event, correction_window_seconds)
+                && proof.verify(Some(correction_window_seconds)).is_ok()
+            {
+                fraud_proofs.push(proof);
+            }
+        }
+    }
+
+    fraud_proofs
+}
+
+/// Finds [FutureEventPayoutAttestationPledge]s with attestation deadline that have no [EventPayoutAttestation] of the
+/// oracle made by the deadline among nostr_events.
+///
+/// Pledges are reported once their deadline is before now_unix_seconds, or earlier if an attestation was made after it.
+/// Pledges with [crate::AttestationPledgeTerms::expiration_unix_seconds] are withdrawn before their deadline and never
+/// reported. Payouts of [super::EventPayoutBatchAttestation]s are not covered.
+pub fn detect_unfulfilled_pledges(
+    nostr_events: &[NostrEvent],
+    now_unix_seconds: u64,
+) -> Vec<UnfulfilledPledge> {
+    let scan = Scan::new(nostr_events);
+    let mut unfulfilled_pledges = Vec::new();
+
+    for (pledge_nostr_event, oracle, pledge) in scan.pledges {
+        let Some(attestation_deadline_unix_seconds) = pledge_deadline(&pledge) else {
+            continue;
+        };
+        let group = scan
+            .groups
+            .get(&(oracle, pledge.event_hash_hex))
+            .map(|index| scan.attestations[*index].as_slice())
+            .unwrap_or_default();
+        if group
+            .iter()
+            .any(|(a, _)| a.created_at.as_u64() <= attestation_deadline_unix_seconds)
+        {
+            continue;
+        }
+        let late_attestation = group.first().map(|(a, _)| (*a).clone());
+        if late_attestation.is_some() || now_unix_seconds > attestation_deadline_unix_seconds {
+            unfulfilled_pledges.push(UnfulfilledPledge {
+                pledge: pledge_nostr_event.clone(),
+                late_attestation,
+            });
+        }
+    }
+
+    unfulfilled_pledges
+}
+
+/// Interpreted attestations, corrections and pledges of the nostr events given to [detect_fraud].
+struct Scan<'a> {
+    /// Index in attestations of every (oracle, event hash).
+    groups: HashMap<(NostrPublicKeyHex, EventHashHex), usize>,
+    /// Attestations of every (oracle, event hash), oldest first, groups in order of their first attestation.
+    attestations: Vec<Vec<(&'a NostrEvent, Vec<PayoutUnit>)>>,
+    corrections: Vec<(&'a NostrEvent, AttestationCorrection)>,
+    pledges: Vec<(&'a NostrEvent, NostrPublicKeyHex, AttestationPledge)>,
+    /// Attestations and corrections by id.
+    correctable: HashMap<EventId, &'a NostrEvent>,
+}
+
+impl<'a> Scan<'a> {
+    fn new(nostr_events: &'a [NostrEvent]) -> Self {
+        let mut nostr_events: Vec<&NostrEvent> = nostr_events.iter().collect();
+        nostr_events.sort_by_key(|e| (e.created_at, e.id));
+        nostr_events.dedup_by_key(|e| e.id);
+
+        let mut scan = Self {
+            groups: HashMap::new(),
+            attestations: Vec::new(),
+            corrections: Vec::new(),
+            pledges: Vec::new(),
+            correctable: HashMap::new(),
+        };
+        for nostr_event in nostr_events {
+            if nostr_event.kind == EventPayoutAttestation::KIND {
+                let Ok((oracle, event_payout)) =
+                    EventPayoutAttestation::interpret_nostr_event(nostr_event)
+                else {
+                    continue;
+                };
+                let key = (oracle, event_payout.event_hash_hex);
+                let index = *scan.groups.entry(key).or_insert_with(|| {
+                    scan.attestations.push(Vec::new());
+                    scan.attestations.len() - 1
+                });
+                scan.attestations[index].push((nostr_event, event_payout.units_per_outcome));
+                scan.correctable.insert(nostr_event.id, nostr_event);
+            } else if nostr_event.kind == EventPayoutAttestationCorrection::KIND {
+                let Ok((_, correction)) =
+                    EventPayoutAttestationCorrection::interpret_nostr_event(nostr_event)
+                else {
+                    continue;
+                };
+                scan.corrections.push((nostr_event, correction));
+                scan.correctable.insert(nostr_event.id, nostr_event);
+            } else if nostr_event.kind == FutureEventPayoutAttestationPledge::KIND {
+                let Ok((oracle, pledge)) =
+                    FutureEventPayoutAttestationPledge::interpret_nostr_event(nostr_event)
+                else {
+                    continue;
+                };
+                scan.pledges.push((nostr_event, oracle, pledge));
+            }
+        }
+
+        scan
+    }
+}
+
+/// Interpret nostr_event as T, failing if it is of another kind.
+fn interpret_kind<T: NostrEventUtils>(nostr_event: &NostrEvent) -> Res<T::InterpretResult> {
+    if nostr_event.kind != T::KIND {
+        return Err(Error::Validation(format!(
+            "nostr event is not of kind {}",
+            T::KIND_U16
+        )));
+    }
+
+    T::interpret_nostr_event(nostr_event)
+}
+
+/// Oracle and [EventHashHex] of an [EventPayoutAttestation] or [EventPayoutAttestationCorrection].
+fn interpret_corrected(nostr_event: &NostrEvent) -> Res<(NostrPublicKeyHex, EventHashHex)> {
+    if nostr_event.kind == EventPayoutAttestation::KIND {
+        let (oracle, event_payout) = EventPayoutAttestation::interpret_nostr_event(nostr_event)?;
+        Ok((oracle, event_payout.event_hash_hex))
+    } else {
+        let (oracle, correction) = interpret_kind::<EventPayoutAttestationCorrection>(nostr_event)?;
+        Ok((oracle, correction.event_hash_hex))
+    }
+}
+
+/// Attestation deadline of a pledge that binds the oracle, [None] without terms or if it expires.
+fn pledge_deadline(pledge: &AttestationPledge) -> Option<u64> {
+    pledge
+        .terms
+        .as_ref()
+        .filter(|terms| terms.expiration_unix_seconds.is_none())
+        .map(|terms| terms.attestation_deadline_unix_seconds)
+}
+
+fn is_late(
+    corrected: &NostrEvent,
+    correction: &NostrEvent,
+    correction_window_seconds: u64,
+) -> bool {
+    correction.created_at.as_u64()
+        > corrected
+            .created_at
+            .as_u64()
+            .saturating_add(correction_window_seconds)
+}
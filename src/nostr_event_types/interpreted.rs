@@ -0,0 +1,74 @@
+use nostr::event::Event as NostrEvent;
+
+use super::{NostrEventIdHex, NostrPublicKeyHex};
+#[allow(unused_imports)]
+use super::{NostrEventUtils, PredictionMarketMessage};
+
+/// Metadata of the signed [NostrEvent] a value was interpreted from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NostrEventMetadata {
+    pub nostr_event_id: NostrEventIdHex,
+    pub author: NostrPublicKeyHex,
+    pub created_at_unix_seconds: u64,
+
+    /// Tags of the nostr event as signed, every tag as its list of strings.
+    pub tags: Vec<Vec<String>>,
+
+    /// Relay the nostr event was received from, if known.
+    pub relay_url: Option<String>,
+}
+
+impl NostrEventMetadata {
+    /// Read [NostrEventMetadata] from nostr_event, without relay url. The signature is not verified.
+    pub fn from_nostr_event(nostr_event: &NostrEvent) -> Self {
+        Self {
+            nostr_event_id: NostrEventIdHex(nostr_event.id.to_hex()),
+            author: NostrPublicKeyHex(nostr_event.pubkey.to_hex()),
+            created_at_unix_seconds: nostr_event.created_at.as_u64(),
+            tags: nostr_event
+                .tags
+                .iter()
+                .map(|t| t.as_slice().to_vec())
+                .collect(),
+            relay_url: Option::None,
+        }
+    }
+}
+
+/// Value interpreted from a signed [NostrEvent] together with the [NostrEventMetadata] of the nostr event.
+///
+/// Created by [NostrEventUtils::interpret_nostr_event_enveloped], where T is the [NostrEventUtils::InterpretResult].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Interpreted<T> {
+    pub metadata: NostrEventMetadata,
+    pub value: T,
+}
+
+impl<T> Interpreted<T> {
+    /// Wrap value interpreted from nostr_event. The signature is not verified.
+    pub fn new(nostr_event: &NostrEvent, value: T) -> Self {
+        Self {
+            metadata: NostrEventMetadata::from_nostr_event(nostr_event),
+            value,
+        }
+    }
+
+    /// Set the relay the nostr event was received from.
+    pub fn with_relay_url(mut self, relay_url: impl Into<String>) -> Self {
+        self.metadata.relay_url = Some(relay_url.into());
+        self
+    }
+
+    /// Returns the value without metadata, the shape returned by [NostrEventUtils::interpret_nostr_event].
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Map the value, keeping the metadata.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Interpreted<U> {
+        Interpreted {
+            metadata: self.metadata,
+            value: f(self.value),
+        }
+    }
+}
@@ -1,21 +1,52 @@
-use crate::{Error, Event as PredictionMarketEvent, EventHashHex, EventPayout, PayoutUnit};
+use crate::{
+    AttestationPledge, AttestationPledgeTerms, Error, Event as PredictionMarketEvent, EventHashHex,
+    EventPayout, PayoutUnit,
+};
 #[allow(unused_imports)]
 use nostr::{
     key::PublicKey, Event as NostrEvent, EventBuilder as NostrEventBuilder, Filter, JsonUtil, Kind,
     Tag, TagStandard, UnsignedEvent as NostrUnsignedEvent,
 };
+use nostr::{nips::nip19::Nip19Event, EventId, FromBech32};
 use serde::{Deserialize, Serialize};
 use std::{fmt::Display, str::FromStr};
-use trait_dec::Res;
-
+use trait_dec::{verify_signature_verified_nostr_event, Res};
+
+mod attestation_correction;
+pub use attestation_correction::*;
+mod attestation_dispute;
+pub use attestation_dispute::*;
+mod attestation_timing;
+pub use attestation_timing::*;
+mod batch_attestation;
+pub use batch_attestation::*;
+pub(crate) mod bulk;
+pub use bulk::BulkInterpretation;
+mod dispatch;
+pub use dispatch::*;
+mod event_hash_tag;
+pub use event_hash_tag::*;
+mod event_pointer;
+pub use event_pointer::*;
+mod fraud_proof;
+pub use fraud_proof::*;
+mod interpreted;
+pub use interpreted::*;
+mod new_event_reference;
+pub use new_event_reference::{NewEventReference, ReferencedEventPayout, NEW_EVENT_MARKER};
+use new_event_reference::{new_event_reference_tags, read_new_event_reference};
+mod oracle_profile;
+pub use oracle_profile::*;
+mod private_event;
+pub use private_event::*;
 mod trait_dec;
-pub use trait_dec::NostrEventUtils;
+pub use trait_dec::{CreateOptions, InterpretOptions, NostrEventUtils, SignatureVerified};
 
 /// [NostrEvent] containing a [PredictionMarketEvent]
 /// 
 /// - kind set to [NewEvent::KIND]
 /// - content set to [PredictionMarketEvent] as json.
-/// - hashtag containing [PredictionMarketEvent::hash_hex]
+/// - tags from [event_hash_tags] containing [PredictionMarketEvent::hash_hex]
 pub struct NewEvent;
 
 impl NostrEventUtils for NewEvent {
@@ -28,11 +59,11 @@ impl NostrEventUtils for NewEvent {
     /// Returns [NostrEventBuilder] with:
     /// - kind set to [NewEvent::KIND]
     /// - content set to [PredictionMarketEvent] as json.
-    /// - hashtag containing [PredictionMarketEvent::hash_hex]
+    /// - tags from [event_hash_tags] containing [PredictionMarketEvent::hash_hex]
     fn create_nostr_event_builder(event: &Self::CreateParameter) -> Res<NostrEventBuilder> {
         let event_json = event.try_to_json_string()?;
         let event_hash_hex = event.hash_hex()?;
-        let tags: Vec<Tag> = vec![TagStandard::Hashtag(event_hash_hex.0).into()];
+        let tags = event_hash_tags(&event_hash_hex);
         let builder = NostrEventBuilder::new(Self::KIND, event_json, tags);
 
         Ok(builder)
@@ -45,19 +76,36 @@ impl NostrEventUtils for NewEvent {
     /// Returns the [PredictionMarketEvent].
     /// IMPORTANT: the returned [PredictionMarketEvent] is not validated.
     fn interpret_nostr_event(nostr_event: &NostrEvent) -> Res<Self::InterpretResult> {
-        nostr_event.verify()?;
+        Self::interpret_nostr_event_with_options(nostr_event, &InterpretOptions::default())
+    }
+    /// Accepts [NostrEvent] and [InterpretOptions].
+    ///
+    /// Return information can be found in [`Self::interpret_nostr_event`]
+    fn interpret_nostr_event_with_options(
+        nostr_event: &NostrEvent,
+        options: &InterpretOptions,
+    ) -> Res<Self::InterpretResult> {
+        let signature_verified = SignatureVerified::verify(nostr_event)?;
+
+        Self::interpret_nostr_event_signature_verified(nostr_event, options, signature_verified)
+    }
+    /// Accepts [NostrEvent] whose signature is [SignatureVerified] and [InterpretOptions].
+    ///
+    /// Return information can be found in [`Self::interpret_nostr_event`]
+    fn interpret_nostr_event_signature_verified(
+        nostr_event: &NostrEvent,
+        options: &InterpretOptions,
+        signature_verified: SignatureVerified,
+    ) -> Res<Self::InterpretResult> {
+        verify_signature_verified_nostr_event(nostr_event, options, signature_verified)?;
 
         let event = PredictionMarketEvent::try_from_json_str(&nostr_event.content)?;
 
-        let Some(hash_tag) = nostr_event.hashtags().next().map(|s| s.to_owned()) else {
-            return Err(Error::Validation(format!(
-                "nostr event does not have any hash tags"
-            )));
-        };
-        if hash_tag != event.hash_hex()?.0 {
-            return Err(Error::Validation(format!(
-                "nostr event hash tag does not equal hash hex of contained event"
-            )));
+        let event_hash_hex = read_event_hash_tag(nostr_event, options.event_hash_tag_mode)?;
+        if event_hash_hex != event.hash_hex()? {
+            return Err(Error::Validation(
+                "nostr event hash tag does not equal hash hex of contained event".to_string(),
+            ));
         }
 
         Ok(event)
@@ -67,47 +115,82 @@ impl NostrEventUtils for NewEvent {
 /// [NostrEvent] that pledges the signer will make an [EventPayoutAttestation] for a specific [PredictionMarketEvent] in the future.
 /// 
 /// - kind set to [FutureEventPayoutAttestationPledge::KIND]
-/// - content is empty.
-/// - hashtag containing [EventHashHex]
+/// - content set to [AttestationPledge::terms] as json, empty if there are no terms.
+/// - tags from [event_hash_tags] containing [AttestationPledge::event_hash_hex]
+/// - `e` and `p` tags containing [AttestationPledge::new_event_reference], if known
 pub struct FutureEventPayoutAttestationPledge;
 
 impl NostrEventUtils for FutureEventPayoutAttestationPledge {
     const KIND_U16: u16 = 6276;
 
-    type CreateParameter = EventHashHex;
+    type CreateParameter = AttestationPledge;
 
-    /// Accepts [EventHashHex]
+    /// Accepts [AttestationPledge]
     ///
     /// Returns [NostrEventBuilder] with:
     /// - kind set to [FutureEventPayoutAttestationPledge::KIND]
-    /// - content is empty.
-    /// - hashtag containing [EventHashHex]
-    fn create_nostr_event_builder(
-        event_hash_hex: &Self::CreateParameter,
-    ) -> Res<NostrEventBuilder> {
-        let tags: Vec<Tag> = vec![TagStandard::Hashtag(event_hash_hex.0.to_owned()).into()];
-        let builder = NostrEventBuilder::new(Self::KIND, "", tags);
+    /// - content set to [AttestationPledge::terms] as json, empty if there are no terms.
+    /// - tags from [event_hash_tags] containing [AttestationPledge::event_hash_hex]
+    /// - `e` and `p` tags containing [AttestationPledge::new_event_reference], if known
+    fn create_nostr_event_builder(pledge: &Self::CreateParameter) -> Res<NostrEventBuilder> {
+        let content = match &pledge.terms {
+            Some(terms) => terms.try_to_json_string()?,
+            None => String::new(),
+        };
+        let mut tags = event_hash_tags(&pledge.event_hash_hex);
+        if let Some(reference) = &pledge.new_event_reference {
+            tags.extend(new_event_reference_tags(reference)?);
+        }
+        let builder = NostrEventBuilder::new(Self::KIND, content, tags);
 
         Ok(builder)
     }
 
-    type InterpretResult = (NostrPublicKeyHex, EventHashHex);
+    type InterpretResult = (NostrPublicKeyHex, AttestationPledge);
 
     /// Accepts [NostrEvent].
     ///
-    /// Returns [NostrPublicKeyHex] and the [EventHashHex] it pledges to make a [EventPayoutAttestation] to.
+    /// Returns [NostrPublicKeyHex] and the [AttestationPledge] to make a [EventPayoutAttestation].
+    /// IMPORTANT: [AttestationPledge] is not validated and [AttestationPledge::new_event_reference] is not verified.
     fn interpret_nostr_event(nostr_event: &NostrEvent) -> Res<Self::InterpretResult> {
-        nostr_event.verify()?;
+        Self::interpret_nostr_event_with_options(nostr_event, &InterpretOptions::default())
+    }
+    /// Accepts [NostrEvent] and [InterpretOptions].
+    ///
+    /// Return information can be found in [`Self::interpret_nostr_event`]
+    fn interpret_nostr_event_with_options(
+        nostr_event: &NostrEvent,
+        options: &InterpretOptions,
+    ) -> Res<Self::InterpretResult> {
+        let signature_verified = SignatureVerified::verify(nostr_event)?;
+
+        Self::interpret_nostr_event_signature_verified(nostr_event, options, signature_verified)
+    }
+    /// Accepts [NostrEvent] whose signature is [SignatureVerified] and [InterpretOptions].
+    ///
+    /// Return information can be found in [`Self::interpret_nostr_event`]
+    fn interpret_nostr_event_signature_verified(
+        nostr_event: &NostrEvent,
+        options: &InterpretOptions,
+        signature_verified: SignatureVerified,
+    ) -> Res<Self::InterpretResult> {
+        verify_signature_verified_nostr_event(nostr_event, options, signature_verified)?;
 
         let nostr_public_key_hex = NostrPublicKeyHex(nostr_event.pubkey.to_hex());
-        let Some(hash_tag) = nostr_event.hashtags().next().map(|s| s.to_owned()) else {
-            return Err(Error::Validation(format!(
-                "nostr event does not have any hash tags"
-            )));
+        let event_hash_hex = read_event_hash_tag(nostr_event, options.event_hash_tag_mode)?;
+        let terms = if nostr_event.content.is_empty() {
+            None
+        } else {
+            Some(AttestationPledgeTerms::try_from_json_str(&nostr_event.content)?)
+        };
+        let new_event_reference = read_new_event_reference(nostr_event)?;
+        let pledge = AttestationPledge {
+            event_hash_hex,
+            terms,
+            new_event_reference,
         };
-        let event_hash_hex = EventHashHex::from_str(&hash_tag)?;
 
-        Ok((nostr_public_key_hex, event_hash_hex))
+        Ok((nostr_public_key_hex, pledge))
     }
 }
 
@@ -115,7 +198,9 @@ impl NostrEventUtils for FutureEventPayoutAttestationPledge {
 ///
 /// - kind set to [EventPayoutAttestation::KIND]
 /// - content set [EventPayout::units_per_outcome] as json
-/// - hashtag containing [EventPayout::event_hash_hex]
+/// - tags from [event_hash_tags] containing [EventPayout::event_hash_hex]
+///
+/// Use [ReferencedEventPayoutAttestation] to also write and read the [NewEventReference].
 pub struct EventPayoutAttestation;
 
 impl NostrEventUtils for EventPayoutAttestation {
@@ -128,14 +213,9 @@ impl NostrEventUtils for EventPayoutAttestation {
     /// Returns [NostrEventBuilder] with:
     /// - kind set to [EventPayoutAttestation::KIND]
     /// - content set [EventPayout::units_per_outcome] as json
-    /// - hashtag containing [EventPayout::event_hash_hex]
+    /// - tags from [event_hash_tags] containing [EventPayout::event_hash_hex]
     fn create_nostr_event_builder(event_payout: &Self::CreateParameter) -> Res<NostrEventBuilder> {
-        let units_per_outcome_json = serde_json::to_string(&event_payout.units_per_outcome)?;
-        let tags: Vec<Tag> =
-            vec![TagStandard::Hashtag(event_payout.event_hash_hex.0.clone()).into()];
-        let builder = NostrEventBuilder::new(Self::KIND, units_per_outcome_json, tags);
-
-        Ok(builder)
+        event_payout_attestation_builder(event_payout, None)
     }
 
     type InterpretResult = (NostrPublicKeyHex, EventPayout);
@@ -145,23 +225,152 @@ impl NostrEventUtils for EventPayoutAttestation {
     /// Returns [NostrPublicKeyHex] and the [EventPayout] it signed.
     /// IMPORTANT: [EventPayout] is not validated.
     fn interpret_nostr_event(nostr_event: &NostrEvent) -> Res<Self::InterpretResult> {
-        nostr_event.verify()?;
+        Self::interpret_nostr_event_with_options(nostr_event, &InterpretOptions::default())
+    }
+    /// Accepts [NostrEvent] and [InterpretOptions].
+    ///
+    /// Return information can be found in [`Self::interpret_nostr_event`]
+    fn interpret_nostr_event_with_options(
+        nostr_event: &NostrEvent,
+        options: &InterpretOptions,
+    ) -> Res<Self::InterpretResult> {
+        let (nostr_public_key_hex, referenced) =
+            ReferencedEventPayoutAttestation::interpret_nostr_event_with_options(
+                nostr_event,
+                options,
+            )?;
+
+        Ok((nostr_public_key_hex, referenced.event_payout))
+    }
+    /// Accepts [NostrEvent] whose signature is [SignatureVerified] and [InterpretOptions].
+    ///
+    /// Return information can be found in [`Self::interpret_nostr_event`]
+    fn interpret_nostr_event_signature_verified(
+        nostr_event: &NostrEvent,
+        options: &InterpretOptions,
+        signature_verified: SignatureVerified,
+    ) -> Res<Self::InterpretResult> {
+        let (nostr_public_key_hex, referenced) =
+            ReferencedEventPayoutAttestation::interpret_nostr_event_signature_verified(
+                nostr_event,
+                options,
+                signature_verified,
+            )?;
+
+        Ok((nostr_public_key_hex, referenced.event_payout))
+    }
+}
+
+/// [EventPayoutAttestation] with the [NewEventReference] of the attested [PredictionMarketEvent], same kind.
+///
+/// - kind set to [EventPayoutAttestation::KIND]
+/// - content set [EventPayout::units_per_outcome] as json
+/// - tags from [event_hash_tags] containing [EventPayout::event_hash_hex]
+/// - `e` and `p` tags containing [ReferencedEventPayout::new_event_reference], if known
+pub struct ReferencedEventPayoutAttestation;
+
+impl NostrEventUtils for ReferencedEventPayoutAttestation {
+    const KIND_U16: u16 = EventPayoutAttestation::KIND_U16;
+
+    type CreateParameter = ReferencedEventPayout;
+
+    /// Accepts [ReferencedEventPayout], [EventPayout] can be converted with [From].
+    ///
+    /// Returns [NostrEventBuilder] with:
+    /// - kind set to [EventPayoutAttestation::KIND]
+    /// - content set [EventPayout::units_per_outcome] as json
+    /// - tags from [event_hash_tags] containing [EventPayout::event_hash_hex]
+    /// - `e` and `p` tags containing [ReferencedEventPayout::new_event_reference], if known
+    fn create_nostr_event_builder(param: &Self::CreateParameter) -> Res<NostrEventBuilder> {
+        event_payout_attestation_builder(&param.event_payout, param.new_event_reference.as_ref())
+    }
+
+    type InterpretResult = (NostrPublicKeyHex, ReferencedEventPayout);
+
+    /// Accepts [NostrEvent].
+    ///
+    /// Returns [NostrPublicKeyHex] and the [EventPayout] it signed, with the [NewEventReference] if present.
+    /// IMPORTANT: [EventPayout] is not validated and [NewEventReference] is not verified.
+    fn interpret_nostr_event(nostr_event: &NostrEvent) -> Res<Self::InterpretResult> {
+        Self::interpret_nostr_event_with_options(nostr_event, &InterpretOptions::default())
+    }
+    /// Accepts [NostrEvent] and [InterpretOptions].
+    ///
+    /// Return information can be found in [`Self::interpret_nostr_event`]
+    fn interpret_nostr_event_with_options(
+        nostr_event: &NostrEvent,
+        options: &InterpretOptions,
+    ) -> Res<Self::InterpretResult> {
+        let signature_verified = SignatureVerified::verify(nostr_event)?;
+
+        Self::interpret_nostr_event_signature_verified(nostr_event, options, signature_verified)
+    }
+    /// Accepts [NostrEvent] whose signature is [SignatureVerified] and [InterpretOptions].
+    ///
+    /// Return information can be found in [`Self::interpret_nostr_event`]
+    fn interpret_nostr_event_signature_verified(
+        nostr_event: &NostrEvent,
+        options: &InterpretOptions,
+        signature_verified: SignatureVerified,
+    ) -> Res<Self::InterpretResult> {
+        verify_signature_verified_nostr_event(nostr_event, options, signature_verified)?;
 
         let nostr_public_key_hex = NostrPublicKeyHex(nostr_event.pubkey.to_hex());
-        let Some(hash_tag) = nostr_event.hashtags().next().map(|s| s.to_owned()) else {
-            return Err(Error::Validation(format!(
-                "nostr event does not have any hash tags"
-            )));
-        };
-        let event_hash_hex = EventHashHex::from_str(&hash_tag)?;
+        let event_hash_hex = read_event_hash_tag(nostr_event, options.event_hash_tag_mode)?;
         let units_per_outcome: Vec<PayoutUnit> = serde_json::from_str(&nostr_event.content)?;
         let event_payout = EventPayout {
             event_hash_hex,
             units_per_outcome,
         };
+        let new_event_reference = read_new_event_reference(nostr_event)?;
+
+        Ok((
+            nostr_public_key_hex,
+            ReferencedEventPayout {
+                event_payout,
+                new_event_reference,
+            },
+        ))
+    }
+}
 
-        Ok((nostr_public_key_hex, event_payout))
+fn event_payout_attestation_builder(
+    event_payout: &EventPayout,
+    new_event_reference: Option<&NewEventReference>,
+) -> Res<NostrEventBuilder> {
+    let units_per_outcome_json = serde_json::to_string(&event_payout.units_per_outcome)?;
+    let mut tags = event_hash_tags(&event_payout.event_hash_hex);
+    if let Some(reference) = new_event_reference {
+        tags.extend(new_event_reference_tags(reference)?);
     }
+    let builder =
+        NostrEventBuilder::new(EventPayoutAttestation::KIND, units_per_outcome_json, tags);
+
+    Ok(builder)
+}
+
+/// Returns [Filter] matching [NewEvent], [FutureEventPayoutAttestationPledge], [EventPayoutAttestation], [EventPayoutAttestationCorrection], [EventPayoutAttestationDispute], [PrivateNewEvent] and [PrivateEventPayoutAttestation] events referencing [EventHashHex] with the namespaced label tag.
+///
+/// Fetches everything published about one [PredictionMarketEvent] with a single subscription.
+pub fn filter_all_kinds_event_hash(event_hash_hex: &EventHashHex) -> Filter {
+    filter_event_hash_label(filter_all_kinds(), event_hash_hex)
+}
+
+/// Returns [Filter] matching [NewEvent], [FutureEventPayoutAttestationPledge], [EventPayoutAttestation], [EventPayoutAttestationCorrection], [EventPayoutAttestationDispute], [PrivateNewEvent] and [PrivateEventPayoutAttestation] events referencing [EventHashHex] with the legacy hashtag.
+pub fn filter_all_kinds_event_hash_legacy(event_hash_hex: &EventHashHex) -> Filter {
+    filter_all_kinds().hashtag(&event_hash_hex.0)
+}
+
+fn filter_all_kinds() -> Filter {
+    Filter::new().kinds([
+        NewEvent::KIND,
+        FutureEventPayoutAttestationPledge::KIND,
+        EventPayoutAttestation::KIND,
+        EventPayoutAttestationCorrection::KIND,
+        EventPayoutAttestationDispute::KIND,
+        PrivateNewEvent::KIND,
+        PrivateEventPayoutAttestation::KIND,
+    ])
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -175,6 +384,7 @@ impl Display for NostrPublicKeyHex {
 
 impl NostrPublicKeyHex {
     /// Checks if s has structure of nostr public key hex.
+    #[allow(clippy::redundant_pattern_matching)]
     pub fn is_valid_format(s: &str) -> bool {
         s.len() == 64 && matches!(s.find(|c: char| !c.is_ascii_hexdigit()), None)
     }
@@ -183,6 +393,7 @@ impl NostrPublicKeyHex {
 impl FromStr for NostrPublicKeyHex {  
     type Err = Error;
     
+    #[allow(clippy::useless_format)]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         if Self::is_valid_format(s) {
             Ok(Self(s.to_owned()))
@@ -191,3 +402,36 @@ impl FromStr for NostrPublicKeyHex {
         }
     }
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NostrEventIdHex(pub String);
+
+impl Display for NostrEventIdHex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl NostrEventIdHex {
+    /// Checks if s has structure of nostr event id hex.
+    pub fn is_valid_format(s: &str) -> bool {
+        s.len() == 64 && s.find(|c: char| !c.is_ascii_hexdigit()).is_none()
+    }
+}
+
+impl FromStr for NostrEventIdHex {
+    type Err = Error;
+
+    /// Accepts hex or a NIP-19 `note` or `nevent`, whose hints are dropped.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if Self::is_valid_format(s) {
+            Ok(Self(s.to_owned()))
+        } else if let Ok(event_id) = EventId::from_bech32(s) {
+            Ok(Self(event_id.to_hex()))
+        } else if let Ok(nip19_event) = Nip19Event::from_bech32(s) {
+            Ok(Self(nip19_event.event_id.to_hex()))
+        } else {
+            Err(Error::Validation("invalid format".to_string()))
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use nostr::{
+    event::{Event as NostrEvent, EventId, Tag, TagStandard},
+    key::PublicKey,
+    nips::nip10::Marker,
+    types::UncheckedUrl,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{trait_dec::Res, NewEvent, NostrEventIdHex, NostrEventUtils, NostrPublicKeyHex};
+use crate::{Error, EventHashHex, EventPayout};
+
+/// Marker of the `e` tag referencing the [NewEvent] nostr event.
+pub const NEW_EVENT_MARKER: &str = "new-event";
+
+/// Reference to the [NewEvent] nostr event that published the [crate::Event] and its creator.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct NewEventReference {
+    /// Id of the [NewEvent] nostr event.
+    pub nostr_event_id: NostrEventIdHex,
+
+    /// Signer of the [NewEvent] nostr event, if known.
+    pub creator: Option<NostrPublicKeyHex>,
+
+    /// Relay the [NewEvent] nostr event can be found on, if known.
+    pub relay_hint: Option<String>,
+}
+
+impl NewEventReference {
+    /// Create [NewEventReference] to a [NewEvent] nostr event. The nostr event is not interpreted.
+    pub fn from_nostr_event(
+        new_event_nostr_event: &NostrEvent,
+        relay_hint: Option<String>,
+    ) -> Self {
+        Self {
+            nostr_event_id: NostrEventIdHex(new_event_nostr_event.id.to_hex()),
+            creator: Some(NostrPublicKeyHex(new_event_nostr_event.pubkey.to_hex())),
+            relay_hint,
+        }
+    }
+
+    /// Verify that new_event_nostr_event is the referenced [NewEvent] nostr event and contains the [crate::Event] with [EventHashHex].
+    pub fn verify(
+        &self,
+        new_event_nostr_event: &NostrEvent,
+        event_hash_hex: &EventHashHex,
+    ) -> Res<()> {
+        if new_event_nostr_event.id.to_hex() != self.nostr_event_id.0 {
+            return Err(Error::Validation(
+                "nostr event id does not equal referenced nostr event id".to_string(),
+            ));
+        }
+        if let Some(creator) = &self.creator {
+            if new_event_nostr_event.pubkey.to_hex() != creator.0 {
+                return Err(Error::Validation(
+                    "nostr event signer does not equal referenced creator".to_string(),
+                ));
+            }
+        }
+        if new_event_nostr_event.kind != NewEvent::KIND {
+            return Err(Error::Validation(
+                "referenced nostr event is not a new event".to_string(),
+            ));
+        }
+        let event = NewEvent::interpret_nostr_event(new_event_nostr_event)?;
+        if &event.hash_hex()? != event_hash_hex {
+            return Err(Error::Validation(
+                "referenced nostr event does not contain event with event hash".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// `e` tag with marker [NEW_EVENT_MARKER] and, if the creator is known, `p` tag.
+pub(super) fn new_event_reference_tags(reference: &NewEventReference) -> Res<Vec<Tag>> {
+    let event_id = EventId::from_hex(&reference.nostr_event_id.0)?;
+    let creator = match &reference.creator {
+        Some(creator) => Some(PublicKey::from_hex(&creator.0)?),
+        None => None,
+    };
+    let relay_url = reference.relay_hint.as_ref().map(UncheckedUrl::from);
+
+    let mut tags: Vec<Tag> = vec![TagStandard::Event {
+        event_id,
+        relay_url: relay_url.clone(),
+        marker: Some(Marker::Custom(NEW_EVENT_MARKER.to_owned())),
+        public_key: creator,
+    }
+    .into()];
+    if let Some(public_key) = creator {
+        tags.push(
+            TagStandard::PublicKey {
+                public_key,
+                relay_url,
+                alias: None,
+                uppercase: false,
+            }
+            .into(),
+        );
+    }
+
+    Ok(tags)
+}
+
+/// Reads the [NewEventReference] of [NostrEvent], [None] if there is no `e` tag with marker [NEW_EVENT_MARKER].
+///
+/// The creator is read from the `e` tag and the `p` tags. Fails if there are conflicting references or creators.
+pub(super) fn read_new_event_reference(nostr_event: &NostrEvent) -> Res<Option<NewEventReference>> {
+    let mut references = nostr_event
+        .tags
+        .iter()
+        .filter_map(|t| match t.as_standardized() {
+            Some(TagStandard::Event {
+                event_id,
+                relay_url,
+                marker: Some(Marker::Custom(marker)),
+                public_key,
+            }) if marker == NEW_EVENT_MARKER => Some(NewEventReference {
+                nostr_event_id: NostrEventIdHex(event_id.to_hex()),
+                creator: public_key.map(|pk| NostrPublicKeyHex(pk.to_hex())),
+                relay_hint: relay_url.as_ref().map(|url| url.to_string()),
+            }),
+            _ => None,
+        });
+    let Some(mut reference) = references.next() else {
+        return Ok(None);
+    };
+    if references
+        .any(|r| r.nostr_event_id != reference.nostr_event_id || r.creator != reference.creator)
+    {
+        return Err(Error::Validation(
+            "nostr event references more than one new event nostr event".to_string(),
+        ));
+    }
+    for public_key in nostr_event.public_keys() {
+        let tagged = NostrPublicKeyHex(public_key.to_hex());
+        match &reference.creator {
+            Some(creator) if creator != &tagged => {
+                return Err(Error::Validation(
+                    "p tag does not equal creator of referenced new event nostr event".to_string(),
+                ));
+            }
+            Some(_) => {}
+            None => reference.creator = Some(tagged),
+        }
+    }
+
+    Ok(Some(reference))
+}
+
+/// [EventPayout] with an optional [NewEventReference], see [super::EventPayoutAttestation].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct ReferencedEventPayout {
+    pub event_payout: EventPayout,
+
+    /// Reference to the [NewEvent] nostr event of the [crate::Event], if known.
+    pub new_event_reference: Option<NewEventReference>,
+}
+
+impl From<EventPayout> for ReferencedEventPayout {
+    fn from(event_payout: EventPayout) -> Self {
+        Self {
+            event_payout,
+            new_event_reference: None,
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+
+use nostr::{
+    event::{Event as NostrEvent, EventBuilder as NostrEventBuilder, Tag, TagStandard},
+    types::{Alphabet, Filter, SingleLetterTag},
+};
+
+use super::{
+    trait_dec::{verify_signature_verified_nostr_event, Res, SignatureVerified},
+    InterpretOptions, NostrEventUtils, NostrPublicKeyHex,
+};
+use crate::{Error, OracleProfile};
+
+/// NIP-32 label namespace of the tags carrying [OracleProfile::information_variant_ids].
+pub const INFORMATION_VARIANT_LABEL_NAMESPACE: &str = "prediction-market-event/information-variant";
+
+/// Replaceable [NostrEvent] announcing the [OracleProfile] of the signer. Relays keep only the latest one per signer.
+///
+/// - kind set to [OracleProfileAnnouncement::KIND]
+/// - content set to [OracleProfile] as json
+/// - hashtags containing [OracleProfile::categories]
+/// - one label in namespace [INFORMATION_VARIANT_LABEL_NAMESPACE] per [OracleProfile::information_variant_ids] entry
+///
+/// The tags only exist for discovery with [OracleProfileAnnouncement::filter_category] and [OracleProfileAnnouncement::filter_information_variant], the content is authoritative.
+pub struct OracleProfileAnnouncement;
+
+impl NostrEventUtils for OracleProfileAnnouncement {
+    const KIND_U16: u16 = 16275;
+
+    type CreateParameter = OracleProfile;
+
+    /// Accepts [OracleProfile]
+    ///
+    /// Returns [NostrEventBuilder] with:
+    /// - kind set to [OracleProfileAnnouncement::KIND]
+    /// - content set to [OracleProfile] as json
+    /// - hashtags containing [OracleProfile::categories]
+    /// - one label in namespace [INFORMATION_VARIANT_LABEL_NAMESPACE] per [OracleProfile::information_variant_ids] entry
+    fn create_nostr_event_builder(profile: &Self::CreateParameter) -> Res<NostrEventBuilder> {
+        let profile_json = profile.try_to_json_string()?;
+        let mut tags: Vec<Tag> = profile
+            .categories
+            .iter()
+            .map(|category| TagStandard::Hashtag(category.to_owned()).into())
+            .collect();
+        if !profile.information_variant_ids.is_empty() {
+            tags.push(
+                TagStandard::LabelNamespace(INFORMATION_VARIANT_LABEL_NAMESPACE.to_owned()).into(),
+            );
+            // NIP-32 labels carry one value each, relays only index the first
+            tags.extend(profile.information_variant_ids.iter().map(|id| {
+                Tag::from(TagStandard::Label(vec![
+                    id.to_owned(),
+                    INFORMATION_VARIANT_LABEL_NAMESPACE.to_owned(),
+                ]))
+            }));
+        }
+        let builder = NostrEventBuilder::new(Self::KIND, profile_json, tags);
+
+        Ok(builder)
+    }
+
+    type InterpretResult = (NostrPublicKeyHex, OracleProfile);
+
+    /// Accepts [NostrEvent].
+    ///
+    /// Returns [NostrPublicKeyHex] and its [OracleProfile].
+    /// Fails if the labels in namespace [INFORMATION_VARIANT_LABEL_NAMESPACE] do not list exactly [OracleProfile::information_variant_ids].
+    /// IMPORTANT: [OracleProfile] is not validated.
+    fn interpret_nostr_event(nostr_event: &NostrEvent) -> Res<Self::InterpretResult> {
+        Self::interpret_nostr_event_with_options(nostr_event, &InterpretOptions::default())
+    }
+    /// Accepts [NostrEvent] and [InterpretOptions].
+    ///
+    /// Return information can be found in [`Self::interpret_nostr_event`]
+    fn interpret_nostr_event_with_options(
+        nostr_event: &NostrEvent,
+        options: &InterpretOptions,
+    ) -> Res<Self::InterpretResult> {
+        let signature_verified = SignatureVerified::verify(nostr_event)?;
+
+        Self::interpret_nostr_event_signature_verified(nostr_event, options, signature_verified)
+    }
+    /// Accepts [NostrEvent] whose signature is [SignatureVerified] and [InterpretOptions].
+    ///
+    /// Return information can be found in [`Self::interpret_nostr_event`]
+    fn interpret_nostr_event_signature_verified(
+        nostr_event: &NostrEvent,
+        options: &InterpretOptions,
+        signature_verified: SignatureVerified,
+    ) -> Res<Self::InterpretResult> {
+        verify_signature_verified_nostr_event(nostr_event, options, signature_verified)?;
+
+        let nostr_public_key_hex = NostrPublicKeyHex(nostr_event.pubkey.to_hex());
+        let profile = OracleProfile::try_from_json_str(&nostr_event.content)?;
+
+        let mut labeled_ids = HashSet::new();
+        for tag in nostr_event.tags.iter() {
+            let Some(TagStandard::Label(label)) = tag.as_standardized() else {
+                continue;
+            };
+            match label.as_slice() {
+                [id, namespace] if namespace == INFORMATION_VARIANT_LABEL_NAMESPACE => {
+                    labeled_ids.insert(id.as_str());
+                }
+                [.., namespace] if namespace == INFORMATION_VARIANT_LABEL_NAMESPACE => {
+                    return Err(Error::Validation(
+                        "information variant label must have one value".to_string(),
+                    ));
+                }
+                _ => {}
+            }
+        }
+        if labeled_ids
+            != profile
+                .information_variant_ids
+                .iter()
+                .map(String::as_str)
+                .collect()
+        {
+            return Err(Error::Validation(
+                "information variant labels do not match oracle profile".to_string(),
+            ));
+        }
+
+        Ok((nostr_public_key_hex, profile))
+    }
+}
+
+impl OracleProfileAnnouncement {
+    /// Returns [Filter] created in [`Self::filter()`] matching only profiles of oracles attesting to events of category
+    pub fn filter_category(category: &str) -> Filter {
+        Self::filter().hashtag(category)
+    }
+
+    /// Returns [Filter] created in [`Self::filter()`] matching only profiles of oracles attesting to events with the [crate::information::Information] variant
+    pub fn filter_information_variant(information_variant_id: &str) -> Filter {
+        Self::filter().custom_tag(
+            SingleLetterTag::lowercase(Alphabet::L),
+            [information_variant_id],
+        )
+    }
+}
+
+/// Returns the latest [OracleProfile] of every signer in nostr_events.
+///
+/// Follows the NIP-01 rules for replaceable events: the newest [OracleProfileAnnouncement] wins, ties are broken by the lowest nostr event id.
+/// Nostr events that are not [OracleProfileAnnouncement]s or fail interpretation are ignored.
+/// IMPORTANT: [OracleProfile]s are not validated.
+pub fn latest_oracle_profiles(
+    nostr_events: &[NostrEvent],
+) -> HashMap<NostrPublicKeyHex, OracleProfile> {
+    let mut latest: HashMap<NostrPublicKeyHex, (&NostrEvent, OracleProfile)> = HashMap::new();
+    for nostr_event in nostr_events {
+        if nostr_event.kind != OracleProfileAnnouncement::KIND {
+            continue;
+        }
+        let Ok((nostr_public_key_hex, profile)) =
+            OracleProfileAnnouncement::interpret_nostr_event(nostr_event)
+        else {
+            continue;
+        };
+        match latest.entry(nostr_public_key_hex) {
+            Entry::Vacant(entry) => {
+                entry.insert((nostr_event, profile));
+            }
+            Entry::Occupied(mut entry) => {
+                let (current, _) = entry.get();
+                if (nostr_event.created_at, std::cmp::Reverse(nostr_event.id))
+                    > (current.created_at, std::cmp::Reverse(current.id))
+                {
+                    entry.insert((nostr_event, profile));
+                }
+            }
+        }
+    }
+
+    latest
+        .into_iter()
+        .map(|(nostr_public_key_hex, (_, profile))| (nostr_public_key_hex, profile))
+        .collect()
+}
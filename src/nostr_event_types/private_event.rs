@@ -0,0 +1,381 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use nostr::{
+    event::{Event as NostrEvent, EventBuilder as NostrEventBuilder, Tag, TagKind},
+    key::{Keys, PublicKey},
+    nips::nip44,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::{
+    event_hash_tags, read_event_hash_tag,
+    trait_dec::{parse_public_keys, verify_signature_verified_nostr_event, Res, SignatureVerified},
+    InterpretOptions, NewEventReference, NostrEventUtils, NostrPublicKeyHex, ReferencedEventPayout,
+};
+use crate::{
+    event::byte_array_to_hex_string, signer::Signer, Error, Event as PredictionMarketEvent,
+    EventHashHex, EventPayout, PayoutUnit,
+};
+
+/// Name of the tag containing the sha256 hex of the plaintext of a private kind.
+pub const COMMITMENT_TAG: &str = "commitment";
+
+/// [NostrEventUtils::CreateParameter] of the private kinds, value is encrypted to every recipient.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Private<T> {
+    pub value: T,
+
+    /// Public keys able to decrypt the value. The signer can only decrypt it if listed.
+    pub recipients: Vec<NostrPublicKeyHex>,
+}
+
+/// [NostrEventUtils::InterpretResult] of the private kinds, everything readable without decrypting.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PrivateEnvelope {
+    pub author: NostrPublicKeyHex,
+
+    /// Created from [crate::Event::hash_hex]
+    pub event_hash_hex: EventHashHex,
+
+    /// sha256 hex of the plaintext, checked after decrypting.
+    pub commitment: String,
+
+    pub recipients: Vec<NostrPublicKeyHex>,
+}
+
+/// Content of the private kinds, the plaintext encrypted with NIP-44 from an ephemeral key to every recipient.
+#[derive(Serialize, Deserialize)]
+struct EncryptedContent {
+    ephemeral_public_key: String,
+    ciphertexts: BTreeMap<String, String>,
+}
+
+/// Decrypting of [NostrEventUtils] kinds whose content is encrypted to a set of recipients.
+pub trait PrivateNostrEventUtils: NostrEventUtils<InterpretResult = PrivateEnvelope> {
+    /// Same as the [NostrEventUtils::InterpretResult] of the public kind.
+    type DecryptResult;
+
+    /// Decrypt [NostrEvent] with recipient using default [InterpretOptions].
+    ///
+    /// Return information can be found in [`Self::decrypt_nostr_event_with_options`]
+    fn decrypt_nostr_event<S: Signer + ?Sized>(
+        nostr_event: &NostrEvent,
+        recipient: &S,
+    ) -> Res<Self::DecryptResult> {
+        Self::decrypt_nostr_event_with_options(nostr_event, recipient, &InterpretOptions::default())
+    }
+
+    /// Decrypt [NostrEvent] with recipient using [InterpretOptions] and return [`Self::DecryptResult`].
+    ///
+    /// Fails if recipient is not a recipient, decryption fails or the plaintext does not match the commitment.
+    fn decrypt_nostr_event_with_options<S: Signer + ?Sized>(
+        nostr_event: &NostrEvent,
+        recipient: &S,
+        options: &InterpretOptions,
+    ) -> Res<Self::DecryptResult>;
+}
+
+/// Private [super::NewEvent] readable only by its recipients.
+///
+/// - kind set to [PrivateNewEvent::KIND]
+/// - content set to [PredictionMarketEvent] as json, encrypted to every recipient
+/// - tags from [event_hash_tags] containing [PredictionMarketEvent::hash_hex]
+/// - `p` tag for every recipient
+/// - [COMMITMENT_TAG] tag containing the sha256 hex of the plaintext
+pub struct PrivateNewEvent;
+
+impl NostrEventUtils for PrivateNewEvent {
+    const KIND_U16: u16 = 6280;
+
+    type CreateParameter = Private<PredictionMarketEvent>;
+
+    /// Accepts [Private] [PredictionMarketEvent]
+    ///
+    /// Returns [NostrEventBuilder] with:
+    /// - kind set to [PrivateNewEvent::KIND]
+    /// - content set to [PredictionMarketEvent] as json, encrypted to every recipient
+    /// - tags from [event_hash_tags] containing [PredictionMarketEvent::hash_hex]
+    /// - `p` tag for every recipient
+    /// - [COMMITMENT_TAG] tag containing the sha256 hex of the plaintext
+    fn create_nostr_event_builder(param: &Self::CreateParameter) -> Res<NostrEventBuilder> {
+        let plaintext = param.value.try_to_json_string()?;
+        let event_hash_hex = param.value.hash_hex()?;
+
+        create_private_builder(Self::KIND, &plaintext, &event_hash_hex, &param.recipients)
+    }
+
+    type InterpretResult = PrivateEnvelope;
+
+    /// Accepts [NostrEvent].
+    ///
+    /// Returns [PrivateEnvelope] without decrypting, see [PrivateNostrEventUtils::decrypt_nostr_event].
+    fn interpret_nostr_event(nostr_event: &NostrEvent) -> Res<Self::InterpretResult> {
+        Self::interpret_nostr_event_with_options(nostr_event, &InterpretOptions::default())
+    }
+    /// Accepts [NostrEvent] and [InterpretOptions].
+    ///
+    /// Return information can be found in [`Self::interpret_nostr_event`]
+    fn interpret_nostr_event_with_options(
+        nostr_event: &NostrEvent,
+        options: &InterpretOptions,
+    ) -> Res<Self::InterpretResult> {
+        let signature_verified = SignatureVerified::verify(nostr_event)?;
+
+        read_private_envelope(nostr_event, options, signature_verified)
+    }
+    /// Accepts [NostrEvent] whose signature is [SignatureVerified] and [InterpretOptions].
+    ///
+    /// Return information can be found in [`Self::interpret_nostr_event`]
+    fn interpret_nostr_event_signature_verified(
+        nostr_event: &NostrEvent,
+        options: &InterpretOptions,
+        signature_verified: SignatureVerified,
+    ) -> Res<Self::InterpretResult> {
+        read_private_envelope(nostr_event, options, signature_verified)
+    }
+}
+
+impl PrivateNostrEventUtils for PrivateNewEvent {
+    type DecryptResult = PredictionMarketEvent;
+
+    /// Returns the [PredictionMarketEvent], the same as [super::NewEvent].
+    /// IMPORTANT: the returned [PredictionMarketEvent] is not validated.
+    fn decrypt_nostr_event_with_options<S: Signer + ?Sized>(
+        nostr_event: &NostrEvent,
+        recipient: &S,
+        options: &InterpretOptions,
+    ) -> Res<Self::DecryptResult> {
+        let (envelope, plaintext) = decrypt_private(nostr_event, recipient, options)?;
+        let event = PredictionMarketEvent::try_from_json_str(&plaintext)?;
+        if event.hash_hex()? != envelope.event_hash_hex {
+            return Err(Error::Validation(
+                "nostr event hash tag does not equal hash hex of contained event".to_string(),
+            ));
+        }
+
+        Ok(event)
+    }
+}
+
+/// Plaintext of [PrivateEventPayoutAttestation], the salt keeps the commitment from being guessed.
+#[derive(Serialize, Deserialize)]
+struct AttestationPlaintext {
+    salt: String,
+    units_per_outcome: Vec<PayoutUnit>,
+    new_event_reference: Option<NewEventReference>,
+}
+
+/// Private [super::EventPayoutAttestation] readable only by its recipients.
+///
+/// - kind set to [PrivateEventPayoutAttestation::KIND]
+/// - content set to a random salt, [EventPayout::units_per_outcome] and [ReferencedEventPayout::new_event_reference] as json, encrypted to every recipient
+/// - tags from [event_hash_tags] containing [EventPayout::event_hash_hex]
+/// - `p` tag for every recipient
+/// - [COMMITMENT_TAG] tag containing the sha256 hex of the plaintext
+pub struct PrivateEventPayoutAttestation;
+
+impl NostrEventUtils for PrivateEventPayoutAttestation {
+    const KIND_U16: u16 = 6281;
+
+    type CreateParameter = Private<ReferencedEventPayout>;
+
+    /// Accepts [Private] [ReferencedEventPayout]
+    ///
+    /// Returns [NostrEventBuilder] with:
+    /// - kind set to [PrivateEventPayoutAttestation::KIND]
+    /// - content set to a random salt, [EventPayout::units_per_outcome] and [ReferencedEventPayout::new_event_reference] as json, encrypted to every recipient
+    /// - tags from [event_hash_tags] containing [EventPayout::event_hash_hex]
+    /// - `p` tag for every recipient
+    /// - [COMMITMENT_TAG] tag containing the sha256 hex of the plaintext
+    fn create_nostr_event_builder(param: &Self::CreateParameter) -> Res<NostrEventBuilder> {
+        let event_payout = &param.value.event_payout;
+        let plaintext = serde_json::to_string(&AttestationPlaintext {
+            salt: byte_array_to_hex_string(&rand::random::<[u8; 32]>()),
+            units_per_outcome: event_payout.units_per_outcome.clone(),
+            new_event_reference: param.value.new_event_reference.clone(),
+        })?;
+
+        create_private_builder(
+            Self::KIND,
+            &plaintext,
+            &event_payout.event_hash_hex,
+            &param.recipients,
+        )
+    }
+
+    type InterpretResult = PrivateEnvelope;
+
+    /// Accepts [NostrEvent].
+    ///
+    /// Returns [PrivateEnvelope] without decrypting, see [PrivateNostrEventUtils::decrypt_nostr_event].
+    fn interpret_nostr_event(nostr_event: &NostrEvent) -> Res<Self::InterpretResult> {
+        Self::interpret_nostr_event_with_options(nostr_event, &InterpretOptions::default())
+    }
+    /// Accepts [NostrEvent] and [InterpretOptions].
+    ///
+    /// Return information can be found in [`Self::interpret_nostr_event`]
+    fn interpret_nostr_event_with_options(
+        nostr_event: &NostrEvent,
+        options: &InterpretOptions,
+    ) -> Res<Self::InterpretResult> {
+        let signature_verified = SignatureVerified::verify(nostr_event)?;
+
+        read_private_envelope(nostr_event, options, signature_verified)
+    }
+    /// Accepts [NostrEvent] whose signature is [SignatureVerified] and [InterpretOptions].
+    ///
+    /// Return information can be found in [`Self::interpret_nostr_event`]
+    fn interpret_nostr_event_signature_verified(
+        nostr_event: &NostrEvent,
+        options: &InterpretOptions,
+        signature_verified: SignatureVerified,
+    ) -> Res<Self::InterpretResult> {
+        read_private_envelope(nostr_event, options, signature_verified)
+    }
+}
+
+impl PrivateNostrEventUtils for PrivateEventPayoutAttestation {
+    type DecryptResult = (NostrPublicKeyHex, ReferencedEventPayout);
+
+    /// Returns [NostrPublicKeyHex] and the [EventPayout] it signed, with the [NewEventReference] if present, the same as [super::EventPayoutAttestation].
+    /// IMPORTANT: [EventPayout] is not validated and [NewEventReference] is not verified.
+    fn decrypt_nostr_event_with_options<S: Signer + ?Sized>(
+        nostr_event: &NostrEvent,
+        recipient: &S,
+        options: &InterpretOptions,
+    ) -> Res<Self::DecryptResult> {
+        let (envelope, plaintext) = decrypt_private(nostr_event, recipient, options)?;
+        let plaintext: AttestationPlaintext = serde_json::from_str(&plaintext)?;
+
+        Ok((
+            envelope.author,
+            ReferencedEventPayout {
+                event_payout: EventPayout {
+                    event_hash_hex: envelope.event_hash_hex,
+                    units_per_outcome: plaintext.units_per_outcome,
+                },
+                new_event_reference: plaintext.new_event_reference,
+            },
+        ))
+    }
+}
+
+fn create_private_builder(
+    kind: nostr::Kind,
+    plaintext: &str,
+    event_hash_hex: &EventHashHex,
+    recipients: &[NostrPublicKeyHex],
+) -> Res<NostrEventBuilder> {
+    let mut recipients = parse_public_keys(recipients)?;
+    recipients.sort();
+    recipients.dedup();
+    if recipients.is_empty() {
+        return Err(Error::Validation(
+            "private nostr event has no recipients".to_string(),
+        ));
+    }
+
+    let ephemeral_keys = Keys::generate();
+    let mut ciphertexts = BTreeMap::new();
+    for recipient in recipients.iter() {
+        let ciphertext = nip44::encrypt(
+            ephemeral_keys.secret_key(),
+            recipient,
+            plaintext,
+            nip44::Version::V2,
+        )?;
+        ciphertexts.insert(recipient.to_hex(), ciphertext);
+    }
+    let content = serde_json::to_string(&EncryptedContent {
+        ephemeral_public_key: ephemeral_keys.public_key.to_hex(),
+        ciphertexts,
+    })?;
+
+    let mut tags = event_hash_tags(event_hash_hex);
+    tags.extend(recipients.into_iter().map(Tag::public_key));
+    tags.push(Tag::custom(
+        TagKind::Custom(COMMITMENT_TAG.into()),
+        [commitment(plaintext)],
+    ));
+
+    Ok(NostrEventBuilder::new(kind, content, tags))
+}
+
+fn read_private_envelope(
+    nostr_event: &NostrEvent,
+    options: &InterpretOptions,
+    signature_verified: SignatureVerified,
+) -> Res<PrivateEnvelope> {
+    let (envelope, _) = read_private(nostr_event, options, signature_verified)?;
+
+    Ok(envelope)
+}
+
+fn read_private(
+    nostr_event: &NostrEvent,
+    options: &InterpretOptions,
+    signature_verified: SignatureVerified,
+) -> Res<(PrivateEnvelope, EncryptedContent)> {
+    verify_signature_verified_nostr_event(nostr_event, options, signature_verified)?;
+
+    let event_hash_hex = read_event_hash_tag(nostr_event, options.event_hash_tag_mode)?;
+    let mut commitments = nostr_event.tags.iter().filter_map(|t| match t.as_slice() {
+        [kind, commitment, ..] if kind == COMMITMENT_TAG => Some(commitment),
+        _ => None,
+    });
+    let (Some(commitment), Option::None) = (commitments.next(), commitments.next()) else {
+        return Err(Error::Validation(
+            "nostr event does not have exactly one commitment tag".to_string(),
+        ));
+    };
+    let content: EncryptedContent = serde_json::from_str(&nostr_event.content)?;
+    let recipients = content
+        .ciphertexts
+        .keys()
+        .map(|pk| pk.parse())
+        .collect::<Res<Vec<NostrPublicKeyHex>>>()?;
+    let tagged: BTreeSet<String> = nostr_event.public_keys().map(|pk| pk.to_hex()).collect();
+    if tagged != recipients.iter().map(|pk| pk.0.clone()).collect() {
+        return Err(Error::Validation(
+            "p tags do not match the recipients of the private nostr event".to_string(),
+        ));
+    }
+
+    let envelope = PrivateEnvelope {
+        author: NostrPublicKeyHex(nostr_event.pubkey.to_hex()),
+        event_hash_hex,
+        commitment: commitment.to_owned(),
+        recipients,
+    };
+
+    Ok((envelope, content))
+}
+
+/// Returns the [PrivateEnvelope] and the plaintext decrypted by recipient, checked against the commitment.
+fn decrypt_private<S: Signer + ?Sized>(
+    nostr_event: &NostrEvent,
+    recipient: &S,
+    options: &InterpretOptions,
+) -> Res<(PrivateEnvelope, String)> {
+    let signature_verified = SignatureVerified::verify(nostr_event)?;
+    let (envelope, content) = read_private(nostr_event, options, signature_verified)?;
+    let Some(ciphertext) = content.ciphertexts.get(&recipient.public_key()?.to_hex()) else {
+        return Err(Error::Validation(
+            "signer is not a recipient of the private nostr event".to_string(),
+        ));
+    };
+    let ephemeral_public_key = PublicKey::from_hex(&content.ephemeral_public_key)?;
+    let plaintext = recipient.nip44_decrypt(&ephemeral_public_key, ciphertext)?;
+    if commitment(&plaintext) != envelope.commitment {
+        return Err(Error::Validation(
+            "decrypted content does not match the commitment".to_string(),
+        ));
+    }
+
+    Ok((envelope, plaintext))
+}
+
+fn commitment(plaintext: &str) -> String {
+    byte_array_to_hex_string(&Sha256::digest(plaintext.as_bytes()))
+}
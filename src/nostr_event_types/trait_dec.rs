@@ -1,6 +1,5 @@
-use std::{fmt::Debug, hash::Hash};
+use std::{fmt::Debug, future::Future, hash::Hash};
 
-use nostr::{event::Kind, key::Keys};
 #[allow(unused_imports)]
 use nostr::{
     event::{Event as NostrEvent, EventBuilder as NostrEventBuilder},
@@ -9,10 +8,69 @@ use nostr::{
     util::JsonUtil,
     UnsignedEvent as NostrUnsignedEvent,
 };
+use nostr::{
+    event::{EventId, Kind, Tag},
+    types::Timestamp,
+};
+
+use super::{
+    bulk::interpret_bulk, filter_event_hash_label, BulkInterpretation, EventHashTagMode,
+    Interpreted, NostrPublicKeyHex,
+};
+use crate::{
+    signer::{AsyncSigner, Signer, ZeroizingKeys},
+    AttestationPledge, Error, EventHashHex,
+};
 
 pub type Res<T> = Result<T, crate::Error>;
 pub type JsonString = String;
 
+/// Options for [NostrEventUtils::interpret_nostr_event_with_options].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct InterpretOptions {
+    /// Which tags are read to find the referenced [EventHashHex].
+    pub event_hash_tag_mode: EventHashTagMode,
+
+    /// Min NIP-13 proof of work difficulty of the nostr event id, 0 accepts every nostr event.
+    pub min_pow_difficulty: u8,
+
+    /// Reject nostr events whose NIP-40 expiration is before this time. [Option::None] accepts expired nostr events.
+    pub reject_expired_at_unix_seconds: Option<u64>,
+}
+
+/// Options for [NostrEventUtils::create_nostr_signed_event_with_options].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct CreateOptions {
+    /// NIP-40 expiration, relays may delete the nostr event after it.
+    pub expiration_unix_seconds: Option<u64>,
+
+    /// NIP-13 proof of work difficulty mined into the nostr event id, 0 mines nothing.
+    pub pow_difficulty: u8,
+}
+
+impl CreateOptions {
+    /// [CreateOptions] expiring a pledge together with [crate::AttestationPledgeTerms::expiration_unix_seconds], if set.
+    pub fn pledge_expiration(pledge: &AttestationPledge) -> Self {
+        Self {
+            expiration_unix_seconds: pledge
+                .terms
+                .as_ref()
+                .and_then(|terms| terms.expiration_unix_seconds),
+            ..Self::default()
+        }
+    }
+
+    /// [CreateOptions] expiring a new event once trading closes at trading_close_unix_seconds.
+    ///
+    /// Relays may delete the new event after it, keep the [crate::Event] to check attestations made later.
+    pub fn new_event_expiration(trading_close_unix_seconds: u64) -> Self {
+        Self {
+            expiration_unix_seconds: Some(trading_close_unix_seconds),
+            ..Self::default()
+        }
+    }
+}
+
 pub trait NostrEventUtils {
     const KIND_U16: u16;
     const KIND: Kind = Kind::Custom(Self::KIND_U16);
@@ -21,6 +79,18 @@ pub trait NostrEventUtils {
 
     /// Creates [NostrEventBuilder] using [`Self::CreateParameter`] parameters.
     fn create_nostr_event_builder(param: &Self::CreateParameter) -> Res<NostrEventBuilder>;
+    /// Creates [NostrEventBuilder] using [`Self::create_nostr_event_builder`] with the tags and proof of work of [CreateOptions].
+    fn create_nostr_event_builder_with_options(
+        param: &Self::CreateParameter,
+        options: &CreateOptions,
+    ) -> Res<NostrEventBuilder> {
+        let mut builder = Self::create_nostr_event_builder(param)?;
+        if let Some(expiration_unix_seconds) = options.expiration_unix_seconds {
+            builder = builder.add_tags([Tag::expiration(Timestamp::from(expiration_unix_seconds))]);
+        }
+
+        Ok(builder.pow(options.pow_difficulty))
+    }
     /// Returns [NostrUnsignedEvent] as [JsonString] using event builder created in [`Self::create_nostr_event_builder`]
     fn create_nostr_unsigned_event_json(
         param: &Self::CreateParameter,
@@ -33,29 +103,166 @@ pub trait NostrEventUtils {
 
         Ok(nostr_unsigned_event_json)
     }
+    /// Returns [NostrEvent] signed by [Signer] using event builder created in [`Self::create_nostr_event_builder`]
+    fn create_nostr_signed_event<S: Signer + ?Sized>(
+        param: &Self::CreateParameter,
+        signer: &S,
+    ) -> Res<NostrEvent> {
+        Self::create_nostr_signed_event_with_options(param, &CreateOptions::default(), signer)
+    }
+    /// Returns [NostrEvent] signed by [Signer] using event builder created in [`Self::create_nostr_event_builder_with_options`]
+    ///
+    /// Proof of work is mined on the calling thread before signing, every difficulty step doubles the expected time.
+    fn create_nostr_signed_event_with_options<S: Signer + ?Sized>(
+        param: &Self::CreateParameter,
+        options: &CreateOptions,
+        signer: &S,
+    ) -> Res<NostrEvent> {
+        let builder = Self::create_nostr_event_builder_with_options(param, options)?;
+        let nostr_unsigned_event = builder.to_unsigned_event(signer.public_key()?);
+
+        signer.sign_event(nostr_unsigned_event)
+    }
+    /// Returns [NostrEvent] signed by [AsyncSigner] using event builder created in [`Self::create_nostr_event_builder`]
+    fn create_nostr_signed_event_async<S: AsyncSigner + ?Sized>(
+        param: &Self::CreateParameter,
+        signer: &S,
+    ) -> impl Future<Output = Res<NostrEvent>> + Send {
+        let builder = Self::create_nostr_event_builder(param);
+        async move {
+            let nostr_unsigned_event = builder?.to_unsigned_event(signer.public_key_async().await?);
+
+            signer.sign_event_async(nostr_unsigned_event).await
+        }
+    }
+    /// Returns [NostrEvent] signed by [AsyncSigner] using event builder created in [`Self::create_nostr_event_builder_with_options`]
+    ///
+    /// Proof of work is mined when the future is polled, blocking its executor thread.
+    fn create_nostr_signed_event_async_with_options<S: AsyncSigner + ?Sized>(
+        param: &Self::CreateParameter,
+        options: &CreateOptions,
+        signer: &S,
+    ) -> impl Future<Output = Res<NostrEvent>> + Send {
+        let builder = Self::create_nostr_event_builder_with_options(param, options);
+        async move {
+            let nostr_unsigned_event = builder?.to_unsigned_event(signer.public_key_async().await?);
+
+            signer.sign_event_async(nostr_unsigned_event).await
+        }
+    }
+    /// Returns [NostrEvent] signed by [Signer] as json using event builder created in [`Self::create_nostr_event_builder`]
+    fn create_nostr_signed_event_json_with_signer<S: Signer + ?Sized>(
+        param: &Self::CreateParameter,
+        signer: &S,
+    ) -> Res<JsonString> {
+        let nostr_event = Self::create_nostr_signed_event(param, signer)?;
+        let nostr_event_json = nostr_event.try_as_json()?;
+
+        Ok(nostr_event_json)
+    }
     /// Returns [NostrEvent] as [JsonString] using event builder created in [`Self::create_nostr_event_builder`]
+    ///
+    /// secret_key is hex or bech32 `nsec`. Prefer [`Self::create_nostr_signed_event_json_with_signer`] with a [ZeroizingKeys]
+    /// decrypted from `ncryptsec` or derived from a mnemonic, to keep the secret key out of strings.
     fn create_nostr_signed_event_json(
         param: &Self::CreateParameter,
         secret_key: &str,
     ) -> Res<JsonString> {
-        let keys = Keys::parse(secret_key)?;
-        let builder = Self::create_nostr_event_builder(param)?;
-        let nostr_event = builder.to_event(&keys)?;
-        let nostr_event_json = nostr_event.try_as_json()?;
+        let keys = ZeroizingKeys::parse(secret_key)?;
 
-        Ok(nostr_event_json)
+        Self::create_nostr_signed_event_json_with_signer(param, &keys)
     }
 
     type InterpretResult: Debug + Clone + PartialEq + Eq + Hash;
 
     /// Interpret [NostrEvent] and return [`Self::InterpretResult`].
     fn interpret_nostr_event(nostr_event: &NostrEvent) -> Res<Self::InterpretResult>;
+    /// Interpret [NostrEvent] using [InterpretOptions] and return [`Self::InterpretResult`].
+    ///
+    /// Defaults to [`Self::interpret_nostr_event`], ignoring options. Every kind of this crate applies them.
+    fn interpret_nostr_event_with_options(
+        nostr_event: &NostrEvent,
+        options: &InterpretOptions,
+    ) -> Res<Self::InterpretResult> {
+        let _ = options;
+        Self::interpret_nostr_event(nostr_event)
+    }
+    /// Interpret [NostrEvent] whose signature is [SignatureVerified] using [InterpretOptions], skipping the signature check.
+    ///
+    /// Used by [`Self::interpret_nostr_events_bulk_with_options`] after batch verification. Defaults to
+    /// [`Self::interpret_nostr_event_with_options`], verifying the signature again.
+    #[doc(hidden)]
+    fn interpret_nostr_event_signature_verified(
+        nostr_event: &NostrEvent,
+        options: &InterpretOptions,
+        signature_verified: SignatureVerified,
+    ) -> Res<Self::InterpretResult> {
+        let _ = signature_verified;
+        Self::interpret_nostr_event_with_options(nostr_event, options)
+    }
+    /// Interpret [NostrEvent] using [InterpretOptions] and return [`Self::InterpretResult`] wrapped in [Interpreted] with the metadata of the nostr event.
+    ///
+    /// Return information can be found in [`Self::interpret_nostr_event`]
+    fn interpret_nostr_event_enveloped_with_options(
+        nostr_event: &NostrEvent,
+        options: &InterpretOptions,
+    ) -> Res<Interpreted<Self::InterpretResult>> {
+        let interpret_result = Self::interpret_nostr_event_with_options(nostr_event, options)?;
+
+        Ok(Interpreted::new(nostr_event, interpret_result))
+    }
+    /// Interpret [NostrEvent] using default [InterpretOptions] and return [`Self::InterpretResult`] wrapped in [Interpreted] with the metadata of the nostr event.
+    ///
+    /// Return information can be found in [`Self::interpret_nostr_event`]
+    fn interpret_nostr_event_enveloped(
+        nostr_event: &NostrEvent,
+    ) -> Res<Interpreted<Self::InterpretResult>> {
+        Self::interpret_nostr_event_enveloped_with_options(
+            nostr_event,
+            &InterpretOptions::default(),
+        )
+    }
+    /// Interpret many [NostrEvent]s of [`Self::KIND`] using default [InterpretOptions].
+    ///
+    /// Return information can be found in [`Self::interpret_nostr_events_bulk_with_options`]
+    fn interpret_nostr_events_bulk(
+        nostr_events: &[NostrEvent],
+    ) -> BulkInterpretation<Self::InterpretResult>
+    where
+        Self::InterpretResult: Send,
+    {
+        Self::interpret_nostr_events_bulk_with_options(nostr_events, &InterpretOptions::default())
+    }
+    /// Interpret many [NostrEvent]s of [`Self::KIND`] using [InterpretOptions], for backfilling from relay dumps.
+    ///
+    /// Nostr events are interpreted in parallel across all cores if the `rayon` feature is enabled, sequentially otherwise.
+    /// Signatures are verified with BIP340 batch verification, a failed batch is halved until the invalid nostr events are
+    /// verified one by one.
+    /// A nostr event of another kind is reported as invalid, a repeated nostr event is interpreted only once.
+    fn interpret_nostr_events_bulk_with_options(
+        nostr_events: &[NostrEvent],
+        options: &InterpretOptions,
+    ) -> BulkInterpretation<Self::InterpretResult>
+    where
+        Self::InterpretResult: Send,
+    {
+        interpret_bulk::<Self>(nostr_events, options)
+    }
     /// Accepts [NostrEvent] as [JsonString].
     ///
     /// Return information can be found in [`Self::interpret_nostr_event`]
     fn interpret_nostr_event_json(json: &str) -> Res<Self::InterpretResult> {
+        Self::interpret_nostr_event_json_with_options(json, &InterpretOptions::default())
+    }
+    /// Accepts [NostrEvent] as [JsonString] and [InterpretOptions].
+    ///
+    /// Return information can be found in [`Self::interpret_nostr_event`]
+    fn interpret_nostr_event_json_with_options(
+        json: &str,
+        options: &InterpretOptions,
+    ) -> Res<Self::InterpretResult> {
         let nostr_event = NostrEvent::from_json(json)?;
-        let interpret_result = Self::interpret_nostr_event(&nostr_event)?;
+        let interpret_result = Self::interpret_nostr_event_with_options(&nostr_event, options)?;
 
         Ok(interpret_result)
     }
@@ -68,4 +275,92 @@ pub trait NostrEventUtils {
     fn filter_json() -> JsonString {
         Self::filter().as_json()
     }
+    /// Returns [Filter] created in [`Self::filter()`] matching only events referencing [EventHashHex] with the namespaced label tag
+    fn filter_event_hash(event_hash_hex: &EventHashHex) -> Filter {
+        filter_event_hash_label(Self::filter(), event_hash_hex)
+    }
+    /// Returns [Filter] created in [`Self::filter()`] matching only events referencing [EventHashHex] with the legacy hashtag
+    fn filter_event_hash_legacy(event_hash_hex: &EventHashHex) -> Filter {
+        Self::filter().hashtag(&event_hash_hex.0)
+    }
+    /// Returns [Filter] created in [`Self::filter()`] matching only events signed by one of the authors
+    fn filter_authors(authors: &[NostrPublicKeyHex]) -> Res<Filter> {
+        let authors = parse_public_keys(authors)?;
+
+        Ok(Self::filter().authors(authors))
+    }
+    /// Returns [Filter] created in [`Self::filter()`] matching only events referencing [EventHashHex] signed by one of the authors
+    fn filter_event_hash_authors(
+        event_hash_hex: &EventHashHex,
+        authors: &[NostrPublicKeyHex],
+    ) -> Res<Filter> {
+        let authors = parse_public_keys(authors)?;
+
+        Ok(Self::filter_event_hash(event_hash_hex).authors(authors))
+    }
+    /// Returns [Filter] created in [`Self::filter()`] matching only events created at or after since_unix_seconds
+    fn filter_since(since_unix_seconds: u64) -> Filter {
+        Self::filter().since(Timestamp::from(since_unix_seconds))
+    }
+}
+
+/// Proof that the signature of one nostr event was verified, only created by this crate.
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy)]
+pub struct SignatureVerified(EventId);
+
+impl SignatureVerified {
+    /// Verify the signature of nostr_event.
+    pub(super) fn verify(nostr_event: &NostrEvent) -> Res<Self> {
+        if !nostr_event.verify_signature() {
+            return Err(nostr::event::Error::InvalidSignature.into());
+        }
+
+        Ok(Self(nostr_event.id))
+    }
+
+    /// Signature of nostr_event was verified in a batch by [interpret_bulk].
+    pub(super) fn batch_verified(nostr_event: &NostrEvent) -> Self {
+        Self(nostr_event.id)
+    }
+}
+
+/// Verify id and signature of nostr_event and check it against the proof of work and expiration of [InterpretOptions].
+pub(super) fn verify_nostr_event(nostr_event: &NostrEvent, options: &InterpretOptions) -> Res<()> {
+    verify_signature_verified_nostr_event(
+        nostr_event,
+        options,
+        SignatureVerified::verify(nostr_event)?,
+    )
+}
+
+/// Same as [verify_nostr_event] for nostr_event whose signature is [SignatureVerified].
+pub(super) fn verify_signature_verified_nostr_event(
+    nostr_event: &NostrEvent,
+    options: &InterpretOptions,
+    signature_verified: SignatureVerified,
+) -> Res<()> {
+    if signature_verified.0 != nostr_event.id || !nostr_event.verify_id() {
+        return Err(nostr::event::Error::InvalidId.into());
+    }
+
+    if !nostr_event.check_pow(options.min_pow_difficulty) {
+        return Err(Error::Validation(
+            "nostr event proof of work is below min difficulty".to_string(),
+        ));
+    }
+    if let Some(now_unix_seconds) = options.reject_expired_at_unix_seconds {
+        if nostr_event.is_expired_at(&Timestamp::from(now_unix_seconds)) {
+            return Err(Error::Validation("nostr event is expired".to_string()));
+        }
+    }
+
+    Ok(())
+}
+
+pub(super) fn parse_public_keys(public_keys: &[NostrPublicKeyHex]) -> Res<Vec<PublicKey>> {
+    public_keys
+        .iter()
+        .map(|pk| PublicKey::from_hex(&pk.0).map_err(|e| e.into()))
+        .collect()
 }
@@ -0,0 +1,121 @@
+use crate::{AttestationFee, Error};
+
+use serde::{Deserialize, Serialize};
+
+/// Machine-readable description of the events an oracle attests to, and under which terms.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct OracleProfile {
+    /// Display name of the oracle.
+    pub name: String,
+
+    /// Ids of the [crate::information::Information] variants the oracle attests to, see [crate::information::Information::ALL_VARIANT_IDS].
+    pub information_variant_ids: Vec<String>,
+
+    /// Categories of events the oracle attests to, for example "sports" or "weather".
+    pub categories: Vec<String>,
+
+    /// Fees charged for making an attestation. Empty if the oracle does not charge fees.
+    pub fee_schedule: Vec<OracleFeeScheduleEntry>,
+
+    /// Typical time between the expected payout time of an event and the attestation.
+    pub typical_resolution_latency_seconds: Option<u64>,
+
+    /// How the oracle resolves events, handles ambiguity and makes corrections.
+    pub policy: Option<String>,
+
+    /// How to contact the operator of the oracle, for example an email address or a url.
+    pub contact: Option<String>,
+
+    /// Relays the oracle publishes to.
+    pub relays: Vec<String>,
+}
+
+impl OracleProfile {
+    // hard coded string length limits
+    const MAX_NAME_LENGTH: usize = 64;
+    const MAX_CATEGORY_LENGTH: usize = 64;
+    const MAX_POLICY_LENGTH: usize = 1024 * 10;
+    const MAX_CONTACT_LENGTH: usize = 256;
+    const MAX_RELAY_LENGTH: usize = 256;
+
+    /// Try to create json string from [OracleProfile]
+    pub fn try_to_json_string(&self) -> Result<String, Error> {
+        serde_json::to_string(self).map_err(|e| e.into())
+    }
+
+    /// Try to parse json string into [OracleProfile]. [OracleProfile] is not validated.
+    pub fn try_from_json_str(json: &str) -> Result<Self, Error> {
+        serde_json::from_str(json).map_err(|e| e.into())
+    }
+
+    /// Checks if the oracle attests to events with the [crate::information::Information] variant.
+    pub fn supports_information_variant(&self, information_variant_id: &str) -> bool {
+        self.information_variant_ids
+            .iter()
+            .any(|id| id == information_variant_id)
+    }
+
+    /// Validate [OracleProfile].
+    /// Unknown information variant ids are accepted, so that profiles stay valid for clients that do not know newer variants.
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.name.is_empty() {
+            return Err(Error::Validation("name is empty".to_string()));
+        }
+        if self.name.len() > Self::MAX_NAME_LENGTH {
+            return Err(Error::Validation("name length is over max".to_string()));
+        }
+        if self.information_variant_ids.is_empty() {
+            return Err(Error::Validation(
+                "no information variant ids are supported".to_string(),
+            ));
+        }
+        for category in self.categories.iter() {
+            if category.is_empty() {
+                return Err(Error::Validation("category is empty".to_string()));
+            }
+            if category.len() > Self::MAX_CATEGORY_LENGTH {
+                return Err(Error::Validation("category length is over max".to_string()));
+            }
+            if category.chars().any(|c| c.is_uppercase()) {
+                return Err(Error::Validation("category is not lowercase".to_string()));
+            }
+        }
+        for entry in self.fee_schedule.iter() {
+            entry.fee.validate()?;
+            if let Some(category) = &entry.category {
+                if !self.categories.contains(category) {
+                    return Err(Error::Validation(
+                        "fee schedule entry category is not a category of the oracle".to_string(),
+                    ));
+                }
+            }
+        }
+        if let Some(policy) = &self.policy {
+            if policy.len() > Self::MAX_POLICY_LENGTH {
+                return Err(Error::Validation("policy length is over max".to_string()));
+            }
+        }
+        if let Some(contact) = &self.contact {
+            if contact.len() > Self::MAX_CONTACT_LENGTH {
+                return Err(Error::Validation("contact length is over max".to_string()));
+            }
+        }
+        for relay in self.relays.iter() {
+            if relay.len() > Self::MAX_RELAY_LENGTH {
+                return Err(Error::Validation("relay length is over max".to_string()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// One entry of [OracleProfile::fee_schedule].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct OracleFeeScheduleEntry {
+    /// Category the fee applies to, [None] if it applies to all categories.
+    pub category: Option<String>,
+
+    /// Fee charged for making an attestation.
+    pub fee: AttestationFee,
+}
@@ -0,0 +1,143 @@
+use crate::{nostr_event_types::NewEventReference, Error, Event, EventHashHex};
+
+use serde::{Deserialize, Serialize};
+
+/// Pledge to make an attestation to the [crate::EventPayout] of a specific [Event] in the future.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct AttestationPledge {
+    /// Created from [Event::hash_hex]
+    pub event_hash_hex: EventHashHex,
+
+    /// Terms the attestation will be made under. [None] for pledges that do not state any terms.
+    pub terms: Option<AttestationPledgeTerms>,
+
+    /// Reference to the [crate::nostr_event_types::NewEvent] nostr event of the [Event], if known.
+    pub new_event_reference: Option<NewEventReference>,
+}
+
+impl AttestationPledge {
+    /// Create new [AttestationPledge]. [AttestationPledge] is not validated.
+    pub fn new(event: &Event, terms: Option<AttestationPledgeTerms>) -> Result<Self, Error> {
+        let event_hash_hex = event.hash_hex()?;
+
+        Ok(Self {
+            event_hash_hex,
+            terms,
+            new_event_reference: None,
+        })
+    }
+
+    /// Validate [AttestationPledge]
+    pub fn validate(&self, event: &Event) -> Result<(), Error> {
+        let event_hash_hex = event.hash_hex()?;
+        if self.event_hash_hex != event_hash_hex {
+            return Err(Error::Validation("event hashes do not match".to_string()));
+        }
+
+        if let Some(terms) = &self.terms {
+            terms.validate(event)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<EventHashHex> for AttestationPledge {
+    fn from(event_hash_hex: EventHashHex) -> Self {
+        Self {
+            event_hash_hex,
+            terms: None,
+            new_event_reference: None,
+        }
+    }
+}
+
+/// Terms an oracle pledges to make its attestation under.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct AttestationPledgeTerms {
+    /// Latest time the attestation will be published at.
+    pub attestation_deadline_unix_seconds: u64,
+
+    /// Fee charged for making the attestation.
+    pub fee: Option<AttestationFee>,
+
+    /// Reference to the methodology used to resolve the event, for example a url.
+    pub resolution_methodology: Option<String>,
+
+    /// Time after which the pledge is withdrawn.
+    pub expiration_unix_seconds: Option<u64>,
+}
+
+impl AttestationPledgeTerms {
+    // hard coded string length limits
+    const MAX_RESOLUTION_METHODOLOGY_LENGTH: usize = 1024;
+
+    /// Try to create json string from [AttestationPledgeTerms]
+    pub fn try_to_json_string(&self) -> Result<String, Error> {
+        serde_json::to_string(self).map_err(|e| e.into())
+    }
+
+    /// Try to parse json string into [AttestationPledgeTerms]. [AttestationPledgeTerms] is not validated.
+    pub fn try_from_json_str(json: &str) -> Result<Self, Error> {
+        serde_json::from_str(json).map_err(|e| e.into())
+    }
+
+    /// Validate [AttestationPledgeTerms] against the [Event] they are pledged for.
+    pub fn validate(&self, event: &Event) -> Result<(), Error> {
+        if let Some(expected_payout_unix_seconds) = event.information.expected_payout_unix_seconds()
+        {
+            if self.attestation_deadline_unix_seconds < expected_payout_unix_seconds {
+                return Err(Error::Validation(
+                    "attestation deadline is before expected payout time of event".to_string(),
+                ));
+            }
+        }
+        if let Some(fee) = &self.fee {
+            fee.validate()?;
+        }
+        if let Some(resolution_methodology) = &self.resolution_methodology {
+            if resolution_methodology.len() > Self::MAX_RESOLUTION_METHODOLOGY_LENGTH {
+                return Err(Error::Validation(
+                    "resolution methodology length is over max".to_string(),
+                ));
+            }
+        }
+        if let Some(expiration_unix_seconds) = self.expiration_unix_seconds {
+            if expiration_unix_seconds > self.attestation_deadline_unix_seconds {
+                return Err(Error::Validation(
+                    "pledge expiration is after attestation deadline".to_string(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Fee charged by an oracle for making an attestation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct AttestationFee {
+    /// Fee amount in the smallest unit of [AttestationFee::denomination].
+    pub amount: u64,
+
+    /// What the fee is paid in, for example "sat".
+    pub denomination: String,
+}
+
+impl AttestationFee {
+    // hard coded string length limits
+    const MAX_DENOMINATION_LENGTH: usize = 32;
+
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        if self.denomination.is_empty() {
+            return Err(Error::Validation("fee denomination is empty".to_string()));
+        }
+        if self.denomination.len() > Self::MAX_DENOMINATION_LENGTH {
+            return Err(Error::Validation(
+                "fee denomination length is over max".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
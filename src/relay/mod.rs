@@ -0,0 +1,216 @@
+//! Minimal NIP-01 nostr relay validating the kinds in [crate::nostr_event_types] on ingest.
+//!
+//! Requires the `relay` feature. Also available as the `prediction-market-relay` binary.
+//! Nostr events are validated with [crate::ingest::IngestValidator] and rejected with an `OK false` message if invalid.
+
+use std::{
+    collections::HashMap,
+    io,
+    net::SocketAddr,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures_util::{SinkExt, StreamExt};
+use nostr::{
+    event::Event as NostrEvent,
+    message::{ClientMessage, RelayMessage, SubscriptionId},
+    types::Filter,
+    util::JsonUtil,
+};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::broadcast,
+    task::{JoinHandle, JoinSet},
+};
+use tokio_tungstenite::{accept_async, tungstenite::Message};
+
+use crate::Error;
+use store::{Inserted, Store};
+
+mod store;
+
+/// Number of live nostr events buffered per connection before a slow connection misses some.
+const LIVE_CAPACITY: usize = 1024;
+
+/// Pause after failing to accept a connection, so that errors like running out of file descriptors do not spin.
+const ACCEPT_ERROR_PAUSE: Duration = Duration::from_millis(100);
+
+/// Nostr relay storing nostr events in memory or in a file. Cloning shares the store.
+#[derive(Clone)]
+pub struct Relay {
+    store: Arc<Mutex<Store>>,
+    live: broadcast::Sender<NostrEvent>,
+}
+
+impl Relay {
+    /// Create [Relay] keeping nostr events only in memory.
+    pub fn in_memory() -> Self {
+        Self::with_store(Store::in_memory())
+    }
+
+    /// Create [Relay] keeping nostr events in memory and appending them to the file at path as json lines.
+    /// Nostr events already in the file are loaded.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Ok(Self::with_store(Store::open(path.as_ref())?))
+    }
+
+    fn with_store(store: Store) -> Self {
+        let (live, _) = broadcast::channel(LIVE_CAPACITY);
+
+        Self {
+            store: Arc::new(Mutex::new(store)),
+            live,
+        }
+    }
+
+    /// Validate, store and forward nostr_event to matching subscriptions, as if it was published to the relay.
+    ///
+    /// Returns the NIP-01 `OK` message with machine readable prefix if nostr_event is rejected.
+    /// With a file, resolves once nostr_event was appended to it. If appending fails, nostr_event stays in memory
+    /// until the relay restarts but is not forwarded.
+    pub async fn ingest(&self, nostr_event: NostrEvent) -> Result<(), String> {
+        let inserted = self.store.lock().unwrap().insert(nostr_event.clone())?;
+        match inserted {
+            Inserted::Duplicate => return Ok(()),
+            Inserted::Stored(Some(appended)) => appended
+                .await
+                .unwrap_or_else(|_| Err(io::Error::other("writer thread stopped")))
+                .map_err(|e| format!("error: failed to store nostr event: {e}"))?,
+            Inserted::Stored(Option::None) | Inserted::Ephemeral => {}
+        }
+        let _ = self.live.send(nostr_event);
+
+        Ok(())
+    }
+
+    /// Stored nostr events matching any of filters, newest first.
+    pub fn query(&self, filters: &[Filter]) -> Vec<NostrEvent> {
+        self.store.lock().unwrap().query(filters)
+    }
+
+    /// Accept websocket connections on listener until the returned future is dropped.
+    ///
+    /// Failing to accept a connection, for example when out of file descriptors, is passed to on_accept_error and
+    /// accepting continues after a short pause.
+    pub async fn serve(self, listener: TcpListener, mut on_accept_error: impl FnMut(Error)) {
+        let mut connections = JoinSet::new();
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    connections.spawn(self.clone().handle_connection(stream));
+                }
+                Err(e) => {
+                    on_accept_error(e.into());
+                    tokio::time::sleep(ACCEPT_ERROR_PAUSE).await;
+                }
+            }
+            while connections.try_join_next().is_some() {}
+        }
+    }
+
+    /// Bind to address and [Relay::serve] on a new task, ignoring errors accepting connections.
+    pub async fn spawn(self, address: &str) -> Result<RelayHandle, Error> {
+        let listener = TcpListener::bind(address).await?;
+        let local_addr = listener.local_addr()?;
+        let task = tokio::spawn(self.serve(listener, |_| {}));
+
+        Ok(RelayHandle { local_addr, task })
+    }
+
+    async fn handle_connection(self, stream: TcpStream) {
+        let Ok(mut ws) = accept_async(stream).await else {
+            return;
+        };
+        let mut live = self.live.subscribe();
+        let mut subscriptions: HashMap<SubscriptionId, Vec<Filter>> = HashMap::new();
+
+        loop {
+            let replies = tokio::select! {
+                message = ws.next() => match message {
+                    Some(Ok(Message::Text(text))) => {
+                        self.handle_client_message(text.as_str(), &mut subscriptions).await
+                    }
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | Option::None => return,
+                    Some(Ok(_)) => continue,
+                },
+                nostr_event = live.recv() => match nostr_event {
+                    Ok(nostr_event) => subscriptions
+                        .iter()
+                        .filter(|(_, filters)| filters.iter().any(|f| f.match_event(&nostr_event)))
+                        .map(|(id, _)| RelayMessage::event(id.clone(), nostr_event.clone()))
+                        .collect(),
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return,
+                },
+            };
+            for reply in replies {
+                if ws.send(Message::text(reply.as_json())).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+
+    async fn handle_client_message(
+        &self,
+        text: &str,
+        subscriptions: &mut HashMap<SubscriptionId, Vec<Filter>>,
+    ) -> Vec<RelayMessage> {
+        let Ok(client_message) = ClientMessage::from_json(text) else {
+            return vec![RelayMessage::notice("error: could not parse message")];
+        };
+
+        match client_message {
+            ClientMessage::Event(nostr_event) => {
+                let id = nostr_event.id;
+                match self.ingest(*nostr_event).await {
+                    Ok(()) => vec![RelayMessage::ok(id, true, "")],
+                    Err(message) => vec![RelayMessage::ok(id, false, message)],
+                }
+            }
+            ClientMessage::Req {
+                subscription_id,
+                filters,
+            } => {
+                let mut replies: Vec<RelayMessage> = self
+                    .query(&filters)
+                    .into_iter()
+                    .map(|e| RelayMessage::event(subscription_id.clone(), e))
+                    .collect();
+                replies.push(RelayMessage::eose(subscription_id.clone()));
+                subscriptions.insert(subscription_id, filters);
+                replies
+            }
+            ClientMessage::Close(subscription_id) => {
+                subscriptions.remove(&subscription_id);
+                vec![]
+            }
+            _ => vec![RelayMessage::notice("error: unsupported message")],
+        }
+    }
+}
+
+/// [Relay] running on a task, created by [Relay::spawn]. Dropping [RelayHandle] stops the [Relay] and closes its connections.
+pub struct RelayHandle {
+    local_addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl RelayHandle {
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// `ws://` url of the [Relay].
+    pub fn url(&self) -> String {
+        format!("ws://{}", self.local_addr)
+    }
+}
+
+impl Drop for RelayHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
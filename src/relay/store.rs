@@ -0,0 +1,179 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::Path,
+    sync::mpsc,
+    thread,
+};
+
+use nostr::{
+    event::{Event as NostrEvent, EventId, Kind},
+    types::Filter,
+    util::JsonUtil,
+};
+
+use tokio::sync::oneshot;
+
+use crate::{ingest::IngestValidator, Error};
+
+/// Resolves once a nostr event was appended to the file of the [Store].
+pub(super) type Appended = oneshot::Receiver<io::Result<()>>;
+
+/// What [Store::insert] did with an accepted nostr event.
+pub(super) enum Inserted {
+    /// Stored, and for replaceable kinds the older version removed. Also being appended to the file, if any.
+    Stored(Option<Appended>),
+    /// Ephemeral kind, only forwarded to subscriptions.
+    Ephemeral,
+    /// Already stored.
+    Duplicate,
+}
+
+/// Nostr events accepted by the relay, kept in memory and optionally appended to a file of json lines.
+///
+/// The file is a log of every accepted nostr event. Replaced and deleted nostr events stay in it and are
+/// removed again when the log is replayed by [Store::open].
+pub(super) struct Store {
+    nostr_events: HashMap<EventId, NostrEvent>,
+    deleted: HashSet<EventId>,
+    validator: IngestValidator,
+    log: Option<Log>,
+}
+
+impl Store {
+    pub(super) fn in_memory() -> Self {
+        Self {
+            nostr_events: HashMap::new(),
+            deleted: HashSet::new(),
+            validator: IngestValidator::new(),
+            log: None,
+        }
+    }
+
+    /// Open the file at path, creating it if it does not exist, and replay its nostr events.
+    /// Lines that are not valid nostr events or would be rejected are skipped.
+    pub(super) fn open(path: &Path) -> Result<Self, Error> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .read(true)
+            .open(path)?;
+        let mut store = Self::in_memory();
+        for line in BufReader::new(&file).lines() {
+            if let Ok(nostr_event) = NostrEvent::from_json(line?) {
+                let _ = store.insert(nostr_event);
+            }
+        }
+        store.log = Some(Log::spawn(file));
+
+        Ok(store)
+    }
+
+    /// Validate and store nostr_event following the NIP-01 rules for replaceable and ephemeral kinds
+    /// and the NIP-09 rules for deletion events.
+    ///
+    /// Returns the NIP-01 `OK` message with machine readable prefix if nostr_event is rejected.
+    /// Appending to the file happens on another thread, a failed append is only reported through [Appended].
+    pub(super) fn insert(&mut self, nostr_event: NostrEvent) -> Result<Inserted, String> {
+        if self.nostr_events.contains_key(&nostr_event.id) {
+            return Ok(Inserted::Duplicate);
+        }
+        if self.deleted.contains(&nostr_event.id) {
+            return Err("blocked: nostr event was deleted".to_string());
+        }
+        self.validator.validate(&nostr_event)?;
+        if nostr_event.kind.is_ephemeral() {
+            return Ok(Inserted::Ephemeral);
+        }
+
+        if nostr_event.kind.is_replaceable() || nostr_event.kind.is_parameterized_replaceable() {
+            let replaced: Vec<&NostrEvent> = self
+                .nostr_events
+                .values()
+                .filter(|e| {
+                    e.pubkey == nostr_event.pubkey
+                        && e.kind == nostr_event.kind
+                        && e.identifier() == nostr_event.identifier()
+                })
+                .collect();
+            if replaced
+                .iter()
+                .any(|e| (e.created_at, nostr_event.id) > (nostr_event.created_at, e.id))
+            {
+                return Err("duplicate: have a newer nostr event".to_string());
+            }
+            let replaced: Vec<EventId> = replaced.iter().map(|e| e.id).collect();
+            for id in replaced {
+                self.nostr_events.remove(&id);
+            }
+        }
+        if nostr_event.kind == Kind::EventDeletion {
+            for id in nostr_event.event_ids() {
+                let own = self
+                    .nostr_events
+                    .get(id)
+                    .is_some_and(|e| e.pubkey == nostr_event.pubkey);
+                if own {
+                    self.nostr_events.remove(id);
+                    self.deleted.insert(*id);
+                }
+            }
+        }
+
+        let appended = self
+            .log
+            .as_ref()
+            .map(|log| log.append(nostr_event.as_json()));
+        self.validator.accept(&nostr_event);
+        self.nostr_events.insert(nostr_event.id, nostr_event);
+
+        Ok(Inserted::Stored(appended))
+    }
+
+    /// Stored nostr events matching any of filters, newest first. [Filter::limit] limits the matches of its filter.
+    pub(super) fn query(&self, filters: &[Filter]) -> Vec<NostrEvent> {
+        let mut newest_first: Vec<&NostrEvent> = self.nostr_events.values().collect();
+        newest_first.sort_by(|a, b| (b.created_at, a.id).cmp(&(a.created_at, b.id)));
+
+        let mut ids = HashSet::new();
+        for filter in filters {
+            let matches = newest_first.iter().filter(|e| filter.match_event(e));
+            let limit = filter.limit.unwrap_or(usize::MAX);
+            ids.extend(matches.take(limit).map(|e| e.id));
+        }
+
+        newest_first
+            .into_iter()
+            .filter(|e| ids.contains(&e.id))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Appends json lines to a file on a dedicated thread, in the order they were accepted by the [Store].
+struct Log {
+    lines: mpsc::Sender<(String, oneshot::Sender<io::Result<()>>)>,
+}
+
+impl Log {
+    /// Start the writer thread, it stops when [Log] is dropped.
+    fn spawn(mut file: File) -> Self {
+        let (lines, received) = mpsc::channel::<(String, oneshot::Sender<io::Result<()>>)>();
+        thread::spawn(move || {
+            for (line, appended) in received {
+                let _ = appended.send(writeln!(file, "{line}").and_then(|_| file.flush()));
+            }
+        });
+
+        Self { lines }
+    }
+
+    fn append(&self, line: String) -> Appended {
+        let (appended, receiver) = oneshot::channel();
+        // if the writer thread stopped, appended is dropped and the receiver reports it
+        let _ = self.lines.send((line, appended));
+
+        receiver
+    }
+}
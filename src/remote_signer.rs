@@ -0,0 +1,241 @@
+//! NIP-46 remote signing client, so that oracle keys can stay on an isolated machine running a bunker.
+//!
+//! Requires the `remote-signer` feature. Requests are encrypted with NIP-44, responses are decrypted with NIP-44 or NIP-04.
+
+use std::{future::Future, time::Duration};
+
+use futures_util::StreamExt;
+use nostr::{
+    event::{Event as NostrEvent, EventBuilder as NostrEventBuilder, Kind, Tag},
+    key::{Keys, PublicKey},
+    nips::{
+        nip04, nip44,
+        nip46::{Message as NostrConnectMessage, NostrConnectURI, Request, ResponseResult},
+    },
+    types::Filter,
+    util::JsonUtil,
+    UnsignedEvent as NostrUnsignedEvent,
+};
+use tokio::sync::Mutex;
+
+use crate::{
+    client::{Client, NostrEventSubscription, SubscriptionItem, DEFAULT_TIMEOUT},
+    signer::{unsigned_event_id, AsyncSigner},
+    Error,
+};
+
+/// [AsyncSigner] asking a NIP-46 remote signer (bunker) to sign nostr events.
+///
+/// Every returned nostr event is verified and must be exactly the requested one. Requests are sent one at a time.
+/// Requires a tokio runtime.
+pub struct RemoteSigner {
+    client: Client,
+    client_keys: Keys,
+    remote_signer_public_key: PublicKey,
+    user_public_key: PublicKey,
+    responses: Mutex<NostrEventSubscription>,
+    timeout: Duration,
+}
+
+impl RemoteSigner {
+    /// Connect to the remote signer of a `bunker://` uri using [DEFAULT_TIMEOUT].
+    ///
+    /// See [RemoteSigner::connect_with_timeout].
+    pub async fn connect(bunker_uri: &str) -> Result<Self, Error> {
+        Self::connect_with_timeout(bunker_uri, DEFAULT_TIMEOUT).await
+    }
+
+    /// Connect to the remote signer of a `bunker://` uri, send `connect` with the secret of the uri and ask for the user public key.
+    ///
+    /// timeout is used for connecting to relays and for every response of the remote signer.
+    pub async fn connect_with_timeout(bunker_uri: &str, timeout: Duration) -> Result<Self, Error> {
+        let NostrConnectURI::Bunker {
+            signer_public_key,
+            relays,
+            secret,
+        } = NostrConnectURI::parse(bunker_uri).map_err(signer_error)?
+        else {
+            return Err(Error::Signer("expected a bunker:// uri".to_string()));
+        };
+
+        let mut client = Client::with_timeout(timeout);
+        let mut errors = Vec::new();
+        for relay in relays {
+            if let Err(e) = client.add_relay(relay.as_str()).await {
+                errors.push(e);
+            }
+        }
+        if client.relay_urls().is_empty() {
+            return Err(errors
+                .pop()
+                .unwrap_or_else(|| Error::Signer("bunker uri has no relays".to_string())));
+        }
+
+        let client_keys = Keys::generate();
+        let responses = client.subscribe_nostr_events(vec![Filter::new()
+            .kind(Kind::NostrConnect)
+            .pubkey(client_keys.public_key)]);
+        let mut remote_signer = Self {
+            client,
+            client_keys,
+            remote_signer_public_key: signer_public_key,
+            user_public_key: signer_public_key,
+            responses: Mutex::new(responses),
+            timeout,
+        };
+
+        let result = remote_signer
+            .request(Request::Connect {
+                public_key: signer_public_key,
+                secret: secret.clone(),
+            })
+            .await?;
+        match result {
+            ResponseResult::Connect => {}
+            // some remote signers answer with the secret instead of "ack"
+            ResponseResult::EncryptionDecryption(s) if Some(&s) == secret.as_ref() => {}
+            result => {
+                return Err(Error::Signer(format!(
+                    "unexpected response to connect: {result}"
+                )))
+            }
+        }
+        remote_signer.user_public_key = remote_signer
+            .request(Request::GetPublicKey)
+            .await?
+            .to_get_public_key()
+            .map_err(signer_error)?;
+
+        Ok(remote_signer)
+    }
+
+    /// Public key the remote signer signs nostr events with.
+    pub fn user_public_key(&self) -> PublicKey {
+        self.user_public_key
+    }
+
+    /// Public key the remote signer communicates with, may differ from [RemoteSigner::user_public_key].
+    pub fn remote_signer_public_key(&self) -> PublicKey {
+        self.remote_signer_public_key
+    }
+
+    /// Send `ping` and wait for `pong`.
+    pub async fn ping(&self) -> Result<(), Error> {
+        self.request(Request::Ping)
+            .await?
+            .to_pong()
+            .map_err(signer_error)
+    }
+
+    /// Ask the remote signer to sign unsigned_event.
+    ///
+    /// Fails with [Error::Signer] if the remote signer refuses, does not answer within the timeout
+    /// or returns a nostr event other than unsigned_event.
+    pub async fn sign(&self, unsigned_event: NostrUnsignedEvent) -> Result<NostrEvent, Error> {
+        if unsigned_event.pubkey != self.user_public_key {
+            return Err(Error::Signer(
+                "unsigned event public key is not the user public key".to_string(),
+            ));
+        }
+        let id = unsigned_event_id(&unsigned_event);
+
+        let nostr_event = self
+            .request(Request::SignEvent(unsigned_event))
+            .await?
+            .to_sign_event()
+            .map_err(signer_error)?;
+        nostr_event.verify()?;
+        if nostr_event.id != id {
+            return Err(Error::Signer(
+                "remote signer returned a different nostr event".to_string(),
+            ));
+        }
+
+        Ok(nostr_event)
+    }
+
+    async fn request(&self, request: Request) -> Result<ResponseResult, Error> {
+        let message = NostrConnectMessage::request(request);
+        let request_id = message.id().to_owned();
+        let content = nip44::encrypt(
+            self.client_keys.secret_key(),
+            &self.remote_signer_public_key,
+            message.as_json(),
+            nip44::Version::V2,
+        )
+        .map_err(signer_error)?;
+        let nostr_event = NostrEventBuilder::new(
+            Kind::NostrConnect,
+            content,
+            [Tag::public_key(self.remote_signer_public_key)],
+        )
+        .to_event(&self.client_keys)?;
+
+        // held until the response arrives, so that responses of other requests are not consumed
+        let mut responses = self.responses.lock().await;
+        let mut results = self.client.publish_nostr_event(&nostr_event).await;
+        if results.iter().all(|(_, result)| result.is_err()) {
+            if let Some((_, Err(e))) = results.pop() {
+                return Err(e);
+            }
+        }
+
+        let response = tokio::time::timeout(self.timeout, async {
+            while let Some(item) = responses.next().await {
+                let SubscriptionItem::Interpreted { nostr_event, .. } = item else {
+                    continue;
+                };
+                if nostr_event.pubkey != self.remote_signer_public_key {
+                    continue;
+                }
+                let Some(NostrConnectMessage::Response { id, result, error }) =
+                    self.decrypt(&nostr_event.content)
+                else {
+                    continue;
+                };
+                if id == request_id {
+                    return Ok((result, error));
+                }
+            }
+
+            Err(Error::Signer("disconnected from every relay".to_string()))
+        })
+        .await
+        .map_err(|_| Error::Signer("timed out waiting for response".to_string()))??;
+
+        match response {
+            (Some(ResponseResult::AuthUrl), Some(auth_url)) => Err(Error::Signer(format!(
+                "authorization required at {auth_url}"
+            ))),
+            (_, Some(error)) => Err(Error::Signer(format!("rejected: {error}"))),
+            (Some(result), Option::None) => Ok(result),
+            (Option::None, Option::None) => Err(Error::Signer("empty response".to_string())),
+        }
+    }
+
+    fn decrypt(&self, content: &str) -> Option<NostrConnectMessage> {
+        let secret_key = self.client_keys.secret_key();
+        let json = nip44::decrypt(secret_key, &self.remote_signer_public_key, content)
+            .ok()
+            .or_else(|| nip04::decrypt(secret_key, &self.remote_signer_public_key, content).ok())?;
+
+        NostrConnectMessage::from_json(json).ok()
+    }
+}
+
+impl AsyncSigner for RemoteSigner {
+    fn public_key_async(&self) -> impl Future<Output = Result<PublicKey, Error>> + Send {
+        std::future::ready(Ok(self.user_public_key))
+    }
+
+    fn sign_event_async(
+        &self,
+        unsigned_event: NostrUnsignedEvent,
+    ) -> impl Future<Output = Result<NostrEvent, Error>> + Send {
+        self.sign(unsigned_event)
+    }
+}
+
+fn signer_error(e: impl ToString) -> Error {
+    Error::Signer(e.to_string())
+}
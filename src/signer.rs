@@ -0,0 +1,304 @@
+//! Signers of nostr events, so that secret keys do not have to be passed around as strings.
+//!
+//! [Signer] signs synchronously, [AsyncSigner] can also sign in another process or device.
+//! Every [Signer] is an [AsyncSigner].
+
+use std::{future::Future, sync::Mutex};
+
+use nostr::{
+    event::{Event as NostrEvent, EventId, UnsignedEvent as NostrUnsignedEvent},
+    key::{Keys, PublicKey, SecretKey},
+    nips::nip44,
+    secp256k1::{Keypair, Message},
+    SECP256K1,
+};
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+#[allow(unused_imports)]
+use crate::nostr_event_types::NostrEventUtils;
+use crate::Error;
+
+/// Signs [NostrUnsignedEvent]s synchronously, used by [NostrEventUtils::create_nostr_signed_event].
+pub trait Signer {
+    /// Public key of the nostr events signed by this signer.
+    fn public_key(&self) -> Result<PublicKey, Error>;
+
+    /// Sign unsigned_event, its public key must be [Signer::public_key].
+    fn sign_event(&self, unsigned_event: NostrUnsignedEvent) -> Result<NostrEvent, Error>;
+
+    /// Decrypt a NIP-44 payload encrypted to [Signer::public_key] by public_key. Fails with [Error::Signer] unless implemented.
+    fn nip44_decrypt(&self, public_key: &PublicKey, payload: &str) -> Result<String, Error> {
+        let _ = (public_key, payload);
+        Err(Error::Signer("nip44 decryption is not supported".to_string()))
+    }
+}
+
+/// Signs [NostrUnsignedEvent]s asynchronously, used by [NostrEventUtils::create_nostr_signed_event_async].
+///
+/// Implemented for every [Signer]. Implement it directly for keys kept in another process or device.
+pub trait AsyncSigner: Sync {
+    /// Public key of the nostr events signed by this signer.
+    fn public_key_async(&self) -> impl Future<Output = Result<PublicKey, Error>> + Send;
+
+    /// Sign unsigned_event, its public key must be [AsyncSigner::public_key_async].
+    fn sign_event_async(
+        &self,
+        unsigned_event: NostrUnsignedEvent,
+    ) -> impl Future<Output = Result<NostrEvent, Error>> + Send;
+}
+
+impl<S: Signer + Sync + ?Sized> AsyncSigner for S {
+    fn public_key_async(&self) -> impl Future<Output = Result<PublicKey, Error>> + Send {
+        std::future::ready(self.public_key())
+    }
+
+    fn sign_event_async(
+        &self,
+        unsigned_event: NostrUnsignedEvent,
+    ) -> impl Future<Output = Result<NostrEvent, Error>> + Send {
+        std::future::ready(self.sign_event(unsigned_event))
+    }
+}
+
+impl Signer for Keys {
+    fn public_key(&self) -> Result<PublicKey, Error> {
+        Ok(self.public_key)
+    }
+
+    fn sign_event(&self, unsigned_event: NostrUnsignedEvent) -> Result<NostrEvent, Error> {
+        Ok(unsigned_event.sign(self)?)
+    }
+
+    fn nip44_decrypt(&self, public_key: &PublicKey, payload: &str) -> Result<String, Error> {
+        Ok(nip44::decrypt(self.secret_key(), public_key, payload)?)
+    }
+}
+
+/// [Signer] holding its secret key in memory that is zeroed when dropped.
+///
+/// Unlike [Keys], no copy of the secret key outlives a signature.
+pub struct ZeroizingKeys {
+    public_key: PublicKey,
+    secret_key: Zeroizing<[u8; SecretKey::LEN]>,
+}
+
+impl std::fmt::Debug for ZeroizingKeys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ZeroizingKeys")
+            .field("public_key", &self.public_key)
+            .finish()
+    }
+}
+
+impl ZeroizingKeys {
+    /// Create [ZeroizingKeys] from secret key bytes.
+    pub fn from_bytes(secret_key: Zeroizing<[u8; SecretKey::LEN]>) -> Result<Self, Error> {
+        let mut keypair = keypair(&secret_key)?;
+        let public_key = PublicKey::from(keypair.x_only_public_key().0);
+        keypair.non_secure_erase();
+
+        Ok(Self {
+            public_key,
+            secret_key,
+        })
+    }
+
+    /// Create [ZeroizingKeys] from secret key as hex or bech32 `nsec`.
+    pub fn parse(secret_key: &str) -> Result<Self, Error> {
+        Self::from_secret_key(SecretKey::parse(secret_key)?)
+    }
+
+    /// Create [ZeroizingKeys] with a random secret key.
+    pub fn generate() -> Self {
+        Self::from_secret_key(SecretKey::generate()).expect("generated secret key is valid")
+    }
+
+    fn from_secret_key(mut secret_key: SecretKey) -> Result<Self, Error> {
+        let bytes = Zeroizing::new(secret_key.secret_bytes());
+        secret_key.non_secure_erase();
+
+        Self::from_bytes(bytes)
+    }
+}
+
+/// Key storage, requires the `key-storage` feature.
+#[cfg(feature = "key-storage")]
+impl ZeroizingKeys {
+    /// Derive [ZeroizingKeys] from a BIP-39 mnemonic of the english wordlist as in NIP-06, path `m/44'/1237'/<account>'/0/0`.
+    pub fn from_mnemonic(
+        mnemonic: &str,
+        passphrase: Option<&str>,
+        account: u32,
+    ) -> Result<Self, Error> {
+        use nostr::{
+            bip39::Mnemonic,
+            bitcoin::{
+                bip32::{ChildNumber, DerivationPath, Xpriv},
+                Network,
+            },
+        };
+
+        let mnemonic = Mnemonic::parse_in_normalized(nostr::bip39::Language::English, mnemonic)
+            .map_err(key_storage_error)?;
+        let seed = Zeroizing::new(mnemonic.to_seed_normalized(passphrase.unwrap_or_default()));
+
+        let mut root_key =
+            Xpriv::new_master(Network::Bitcoin, &seed[..]).map_err(key_storage_error)?;
+        let path = DerivationPath::from(vec![
+            ChildNumber::from_hardened_idx(44).map_err(key_storage_error)?,
+            ChildNumber::from_hardened_idx(1237).map_err(key_storage_error)?,
+            ChildNumber::from_hardened_idx(account).map_err(key_storage_error)?,
+            ChildNumber::from_normal_idx(0).map_err(key_storage_error)?,
+            ChildNumber::from_normal_idx(0).map_err(key_storage_error)?,
+        ]);
+        let child_key = root_key.derive_priv(&SECP256K1, &path);
+        root_key.private_key.non_secure_erase();
+        let mut child_key = child_key.map_err(key_storage_error)?;
+        let bytes = Zeroizing::new(child_key.private_key.secret_bytes());
+        child_key.private_key.non_secure_erase();
+
+        Self::from_bytes(bytes)
+    }
+
+    /// Decrypt [ZeroizingKeys] from a NIP-49 `ncryptsec` with password.
+    pub fn from_ncryptsec(ncryptsec: &str, password: &str) -> Result<Self, Error> {
+        use nostr::{nips::nip49::EncryptedSecretKey, FromBech32};
+
+        let encrypted = EncryptedSecretKey::from_bech32(ncryptsec).map_err(key_storage_error)?;
+        let secret_key = encrypted
+            .to_secret_key(password)
+            .map_err(key_storage_error)?;
+
+        Self::from_secret_key(secret_key)
+    }
+
+    /// Encrypt the secret key with password to a NIP-49 `ncryptsec`.
+    ///
+    /// log_n is the scrypt cost, every increment doubles the time and memory needed to decrypt. NIP-49 suggests at least 16.
+    pub fn to_ncryptsec(&self, password: &str, log_n: u8) -> Result<String, Error> {
+        use nostr::{
+            nips::nip49::{EncryptedSecretKey, KeySecurity},
+            ToBech32,
+        };
+
+        let mut secret_key = SecretKey::from_slice(&self.secret_key[..])?;
+        let encrypted = EncryptedSecretKey::new(&secret_key, password, log_n, KeySecurity::Medium);
+        secret_key.non_secure_erase();
+
+        encrypted
+            .map_err(key_storage_error)?
+            .to_bech32()
+            .map_err(key_storage_error)
+    }
+}
+
+#[cfg(feature = "key-storage")]
+fn key_storage_error(e: impl ToString) -> Error {
+    Error::KeyStorage(e.to_string())
+}
+
+impl Signer for ZeroizingKeys {
+    fn public_key(&self) -> Result<PublicKey, Error> {
+        Ok(self.public_key)
+    }
+
+    fn sign_event(&self, unsigned_event: NostrUnsignedEvent) -> Result<NostrEvent, Error> {
+        let message = Message::from_digest(unsigned_event_id(&unsigned_event).to_bytes());
+
+        let mut keypair = keypair(&self.secret_key)?;
+        let signature =
+            SECP256K1.sign_schnorr_with_aux_rand(&message, &keypair, &rand::random::<[u8; 32]>());
+        keypair.non_secure_erase();
+
+        Ok(unsigned_event.add_signature(signature)?)
+    }
+
+    fn nip44_decrypt(&self, public_key: &PublicKey, payload: &str) -> Result<String, Error> {
+        let mut secret_key = SecretKey::from_slice(&self.secret_key[..])?;
+        let plaintext = nip44::decrypt(&secret_key, public_key, payload);
+        secret_key.non_secure_erase();
+
+        Ok(plaintext?)
+    }
+}
+
+/// Id of the nostr event unsigned_event becomes once signed.
+pub(crate) fn unsigned_event_id(unsigned_event: &NostrUnsignedEvent) -> EventId {
+    unsigned_event.id.unwrap_or_else(|| {
+        EventId::new(
+            &unsigned_event.pubkey,
+            &unsigned_event.created_at,
+            &unsigned_event.kind,
+            &unsigned_event.tags,
+            &unsigned_event.content,
+        )
+    })
+}
+
+fn keypair(secret_key: &[u8; SecretKey::LEN]) -> Result<Keypair, Error> {
+    Keypair::from_seckey_slice(&SECP256K1, secret_key)
+        .map_err(|e| Error::NostrKey(nostr::key::Error::from(e)))
+}
+
+/// [Signer] for tests, with keys derived from a seed and a record of the nostr events it signed.
+///
+/// IMPORTANT: anyone knowing the seed can sign, never use it for real nostr events.
+#[derive(Debug)]
+pub struct TestSigner {
+    keys: Keys,
+    failing: bool,
+    signed: Mutex<Vec<EventId>>,
+}
+
+impl TestSigner {
+    /// Create [TestSigner] whose secret key is the sha256 of seed.
+    pub fn new(seed: &str) -> Self {
+        let secret_key: [u8; 32] = Sha256::digest(seed.as_bytes()).into();
+        let secret_key = SecretKey::from_slice(&secret_key).expect("sha256 is a valid secret key");
+
+        Self {
+            keys: Keys::new(secret_key),
+            failing: false,
+            signed: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Create [TestSigner] that fails every signature, for testing signer errors.
+    pub fn failing(seed: &str) -> Self {
+        Self {
+            failing: true,
+            ..Self::new(seed)
+        }
+    }
+
+    /// Keys of the signer.
+    pub fn keys(&self) -> &Keys {
+        &self.keys
+    }
+
+    /// Ids of the nostr events signed so far, in signing order.
+    pub fn signed_event_ids(&self) -> Vec<EventId> {
+        self.signed.lock().unwrap().clone()
+    }
+}
+
+impl Signer for TestSigner {
+    fn public_key(&self) -> Result<PublicKey, Error> {
+        Ok(self.keys.public_key)
+    }
+
+    fn sign_event(&self, unsigned_event: NostrUnsignedEvent) -> Result<NostrEvent, Error> {
+        if self.failing {
+            return Err(Error::Signer("test signer refused to sign".to_string()));
+        }
+        let nostr_event = unsigned_event.sign(&self.keys)?;
+        self.signed.lock().unwrap().push(nostr_event.id);
+
+        Ok(nostr_event)
+    }
+
+    fn nip44_decrypt(&self, public_key: &PublicKey, payload: &str) -> Result<String, Error> {
+        self.keys.nip44_decrypt(public_key, payload)
+    }
+}
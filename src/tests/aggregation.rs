@@ -0,0 +1,309 @@
+use std::collections::HashMap;
+
+use crate::{
+    aggregation::{aggregate_event_payout, AggregationRule},
+    information::Information,
+    nostr_event_types::{
+        AttestationCorrection, EventPayoutAttestation, EventPayoutAttestationCorrection,
+        NostrEventIdHex, NostrEventUtils, NostrPublicKeyHex,
+    },
+    Error, Event, EventPayout, PayoutUnit,
+};
+
+struct Oracles {
+    keys: Vec<::nostr::Keys>,
+}
+
+impl Oracles {
+    fn new(n: usize) -> Self {
+        Self {
+            keys: (0..n).map(|_| ::nostr::Keys::generate()).collect(),
+        }
+    }
+
+    fn public_keys(&self) -> Vec<NostrPublicKeyHex> {
+        self.keys
+            .iter()
+            .map(|k| NostrPublicKeyHex(k.public_key.to_hex()))
+            .collect()
+    }
+
+    fn attest(&self, i: usize, event: &Event, units: Vec<PayoutUnit>) -> ::nostr::Event {
+        let event_payout = EventPayout::new(event, units).unwrap();
+        EventPayoutAttestation::create_nostr_event_builder(&event_payout)
+            .unwrap()
+            .to_event(&self.keys[i])
+            .unwrap()
+    }
+}
+
+#[test]
+fn aggregation_m_of_n_identical_1() {
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let oracles = Oracles::new(4);
+    let nostr_events = vec![
+        oracles.attest(0, &event, vec![10, 0]),
+        oracles.attest(1, &event, vec![10, 0]),
+        oracles.attest(2, &event, vec![0, 10]),
+        // invalid, does not sum up to units to payout
+        oracles.attest(3, &event, vec![1, 0]),
+        // not an accepted oracle
+        Oracles::new(1).attest(0, &event, vec![0, 10]),
+    ];
+
+    let report = aggregate_event_payout(
+        &event,
+        &oracles.public_keys(),
+        &nostr_events,
+        &AggregationRule::MOfNIdentical { m: 2 },
+    )
+    .unwrap();
+    assert_eq!(
+        report.event_payout,
+        Some(EventPayout::new(&event, vec![10, 0]).unwrap())
+    );
+    assert_eq!(report.agreeing, oracles.public_keys()[0..2]);
+    assert_eq!(report.dissenting, oracles.public_keys()[2..3]);
+    assert!(report.missing.is_empty());
+    assert_eq!(report.invalid.len(), 1);
+    assert_eq!(report.invalid[0].oracle, oracles.public_keys()[3]);
+    println!("{report:?}");
+
+    let report = aggregate_event_payout(
+        &event,
+        &oracles.public_keys(),
+        &nostr_events,
+        &AggregationRule::MOfNIdentical { m: 3 },
+    )
+    .unwrap();
+    assert!(report.event_payout.is_none());
+    assert!(report.agreeing.is_empty());
+    assert_eq!(report.dissenting, oracles.public_keys()[0..3]);
+
+    // two different payouts reaching m is a conflict
+    let report = aggregate_event_payout(
+        &event,
+        &oracles.public_keys(),
+        &nostr_events,
+        &AggregationRule::MOfNIdentical { m: 1 },
+    )
+    .unwrap();
+    assert!(report.event_payout.is_none());
+
+    // oracles without attestations are missing
+    let report = aggregate_event_payout(
+        &event,
+        &oracles.public_keys(),
+        &nostr_events[0..1],
+        &AggregationRule::MOfNIdentical { m: 1 },
+    )
+    .unwrap();
+    assert_eq!(report.agreeing, oracles.public_keys()[0..1]);
+    assert_eq!(report.missing, oracles.public_keys()[1..4]);
+
+    let res = aggregate_event_payout(
+        &event,
+        &oracles.public_keys(),
+        &nostr_events,
+        &AggregationRule::MOfNIdentical { m: 0 },
+    );
+    assert!(matches!(res, Err(Error::Validation(_))));
+}
+
+#[test]
+fn aggregation_m_of_n_identical_corrections_1() {
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let oracles = Oracles::new(2);
+    let mistake = oracles.attest(1, &event, vec![0, 10]);
+    let correction = EventPayoutAttestationCorrection::create_nostr_event_builder(
+        &AttestationCorrection {
+            corrected_nostr_event_id: NostrEventIdHex(mistake.id.to_hex()),
+            event_hash_hex: event.hash_hex().unwrap(),
+            units_per_outcome: Some(vec![10, 0]),
+            reason: "mistake".into(),
+        },
+    )
+    .unwrap()
+    .to_event(&oracles.keys[1])
+    .unwrap();
+    let nostr_events = vec![oracles.attest(0, &event, vec![10, 0]), mistake, correction];
+
+    let report = aggregate_event_payout(
+        &event,
+        &oracles.public_keys(),
+        &nostr_events,
+        &AggregationRule::MOfNIdentical { m: 2 },
+    )
+    .unwrap();
+    assert_eq!(
+        report.event_payout,
+        Some(EventPayout::new(&event, vec![10, 0]).unwrap())
+    );
+    assert_eq!(report.agreeing, oracles.public_keys());
+}
+
+#[test]
+fn aggregation_per_outcome_median_1() {
+    let event = Event::new_with_random_nonce(3, 10, Information::None);
+    let oracles = Oracles::new(3);
+    let nostr_events = vec![
+        oracles.attest(0, &event, vec![6, 4, 0]),
+        oracles.attest(1, &event, vec![5, 4, 1]),
+        oracles.attest(2, &event, vec![0, 0, 10]),
+    ];
+
+    // medians 5, 4, 1 already sum up to 10
+    let report = aggregate_event_payout(
+        &event,
+        &oracles.public_keys(),
+        &nostr_events,
+        &AggregationRule::PerOutcomeMedian {
+            min_attestations: 3,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        report.event_payout,
+        Some(EventPayout::new(&event, vec![5, 4, 1]).unwrap())
+    );
+    assert_eq!(report.agreeing, oracles.public_keys()[1..2]);
+    assert_eq!(report.dissenting.len(), 2);
+
+    // lower medians 0, 0, 0 of [6, 4, 0] and [0, 0, 10] can not be scaled
+    let report = aggregate_event_payout(
+        &event,
+        &oracles.public_keys(),
+        &[nostr_events[0].clone(), nostr_events[2].clone()],
+        &AggregationRule::PerOutcomeMedian {
+            min_attestations: 2,
+        },
+    )
+    .unwrap();
+    assert!(report.event_payout.is_none());
+
+    let report = aggregate_event_payout(
+        &event,
+        &oracles.public_keys(),
+        &nostr_events[0..2],
+        &AggregationRule::PerOutcomeMedian {
+            min_attestations: 3,
+        },
+    )
+    .unwrap();
+    assert!(report.event_payout.is_none());
+    assert_eq!(report.missing, oracles.public_keys()[2..3]);
+}
+
+#[test]
+fn aggregation_per_outcome_median_scaling_1() {
+    let event = Event::new_with_random_nonce(3, 10, Information::None);
+    let oracles = Oracles::new(3);
+    let nostr_events = vec![
+        oracles.attest(0, &event, vec![4, 4, 2]),
+        oracles.attest(1, &event, vec![3, 3, 4]),
+        oracles.attest(2, &event, vec![3, 3, 4]),
+    ];
+
+    // medians 3, 3, 4 already sum up to 10
+    let report = aggregate_event_payout(
+        &event,
+        &oracles.public_keys(),
+        &nostr_events,
+        &AggregationRule::PerOutcomeMedian {
+            min_attestations: 1,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        report.event_payout,
+        Some(EventPayout::new(&event, vec![3, 3, 4]).unwrap())
+    );
+
+    let nostr_events = vec![
+        oracles.attest(0, &event, vec![4, 4, 2]),
+        oracles.attest(1, &event, vec![2, 6, 2]),
+    ];
+    // lower medians 2, 4, 2 scaled to 10 is 2.5, 5, 2.5, the remaining unit goes to the lower outcome
+    let report = aggregate_event_payout(
+        &event,
+        &oracles.public_keys(),
+        &nostr_events,
+        &AggregationRule::PerOutcomeMedian {
+            min_attestations: 2,
+        },
+    )
+    .unwrap();
+    let event_payout = report.event_payout.unwrap();
+    assert_eq!(event_payout.units_per_outcome, vec![3, 5, 2]);
+    assert!(matches!(event_payout.validate(&event), Ok(())));
+}
+
+#[test]
+fn aggregation_weighted_vote_1() {
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let oracles = Oracles::new(3);
+    let pks = oracles.public_keys();
+    let nostr_events = vec![
+        oracles.attest(0, &event, vec![10, 0]),
+        oracles.attest(1, &event, vec![0, 10]),
+        oracles.attest(2, &event, vec![0, 10]),
+    ];
+    let weights = HashMap::from([(pks[0].clone(), 5), (pks[1].clone(), 2), (pks[2].clone(), 2)]);
+
+    let report = aggregate_event_payout(
+        &event,
+        &pks,
+        &nostr_events,
+        &AggregationRule::WeightedVote {
+            weights: weights.clone(),
+            min_weight: 5,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        report.event_payout,
+        Some(EventPayout::new(&event, vec![10, 0]).unwrap())
+    );
+    assert_eq!(report.agreeing, pks[0..1]);
+    assert_eq!(report.dissenting, pks[1..3]);
+
+    let report = aggregate_event_payout(
+        &event,
+        &pks,
+        &nostr_events,
+        &AggregationRule::WeightedVote {
+            weights: weights.clone(),
+            min_weight: 6,
+        },
+    )
+    .unwrap();
+    assert!(report.event_payout.is_none());
+
+    // tie
+    let mut tied = weights.clone();
+    tied.insert(pks[0].clone(), 4);
+    let report = aggregate_event_payout(
+        &event,
+        &pks,
+        &nostr_events,
+        &AggregationRule::WeightedVote {
+            weights: tied,
+            min_weight: 1,
+        },
+    )
+    .unwrap();
+    assert!(report.event_payout.is_none());
+
+    let mut incomplete = weights;
+    incomplete.remove(&pks[2]);
+    let res = aggregate_event_payout(
+        &event,
+        &pks,
+        &nostr_events,
+        &AggregationRule::WeightedVote {
+            weights: incomplete,
+            min_weight: 1,
+        },
+    );
+    assert!(matches!(res, Err(Error::Validation(_))));
+}
@@ -0,0 +1,270 @@
+use super::util::{attestation, pledge, sign, v1_event, DEADLINE, EXPECTED_PAYOUT};
+use crate::{
+    information::Information,
+    nostr_event_types::{
+        validate_attestation_timing, AttestationTimingIssue, AttestationTimingPolicy,
+        FutureEventPayoutAttestationPledge, NostrEventIdHex, NostrEventUtils, TimingSeverity,
+    },
+    AttestationPledge, Error, Event,
+};
+
+#[test]
+fn attestation_timing_1() {
+    let event = v1_event();
+    let oracle = ::nostr::Keys::generate();
+    let other_oracle = ::nostr::Keys::generate();
+    let policy = AttestationTimingPolicy::default();
+    let early_pledge = pledge(&event, &oracle, EXPECTED_PAYOUT - 1000, Option::None);
+
+    // on time, other oracles' pledges are ignored
+    let on_time = attestation(&event, vec![10, 0], &oracle, EXPECTED_PAYOUT + 10);
+    let nostr_events = [
+        early_pledge.clone(),
+        pledge(&event, &other_oracle, DEADLINE + 100, Option::None),
+    ];
+    let report = validate_attestation_timing(&event, &on_time, &nostr_events, &policy).unwrap();
+    assert!(report.is_ok());
+    assert!(report.warnings.is_empty());
+
+    // attested early
+    let early = attestation(&event, vec![10, 0], &oracle, EXPECTED_PAYOUT - 60);
+    let report = validate_attestation_timing(&event, &early, &[], &policy).unwrap();
+    assert_eq!(
+        report.errors,
+        vec![AttestationTimingIssue::BeforeExpectedPayout {
+            attested_at_unix_seconds: EXPECTED_PAYOUT - 60,
+            expected_payout_unix_seconds: EXPECTED_PAYOUT,
+        }]
+    );
+    // within tolerance
+    let tolerant = AttestationTimingPolicy {
+        tolerance_seconds: 60,
+        ..Default::default()
+    };
+    assert!(validate_attestation_timing(&event, &early, &[], &tolerant)
+        .unwrap()
+        .is_ok());
+
+    // attested after the pledged deadline, a warning by default
+    let late = attestation(&event, vec![10, 0], &oracle, DEADLINE + 1);
+    let report =
+        validate_attestation_timing(&event, &late, std::slice::from_ref(&early_pledge), &policy)
+            .unwrap();
+    assert!(report.is_ok());
+    assert_eq!(
+        report.warnings,
+        vec![AttestationTimingIssue::AfterPledgeDeadline {
+            pledge_nostr_event_id: NostrEventIdHex(early_pledge.id.to_hex()),
+            attested_at_unix_seconds: DEADLINE + 1,
+            attestation_deadline_unix_seconds: DEADLINE,
+        }]
+    );
+    let strict = AttestationTimingPolicy {
+        after_pledge_deadline: TimingSeverity::Error,
+        ..Default::default()
+    };
+    assert!(
+        !validate_attestation_timing(&event, &late, &[early_pledge], &strict)
+            .unwrap()
+            .is_ok()
+    );
+
+    // pledged after attesting
+    let late_pledge = pledge(&event, &oracle, EXPECTED_PAYOUT + 20, Option::None);
+    let report = validate_attestation_timing(
+        &event,
+        &on_time,
+        std::slice::from_ref(&late_pledge),
+        &policy,
+    )
+    .unwrap();
+    assert_eq!(
+        report.errors,
+        vec![AttestationTimingIssue::PledgeAfterAttestation {
+            pledge_nostr_event_id: NostrEventIdHex(late_pledge.id.to_hex()),
+            attested_at_unix_seconds: EXPECTED_PAYOUT + 10,
+            pledged_at_unix_seconds: EXPECTED_PAYOUT + 20,
+        }]
+    );
+
+    // not an attestation, or not valid for event
+    assert!(matches!(
+        validate_attestation_timing(&event, &late_pledge, &[], &policy),
+        Err(Error::Validation(_))
+    ));
+    assert!(validate_attestation_timing(&v1_event(), &on_time, &[], &policy).is_err());
+
+    // events without expected payout time are not checked against it
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let early = attestation(&event, vec![10, 0], &oracle, 0);
+    assert_eq!(
+        validate_attestation_timing(&event, &early, &[], &policy).unwrap(),
+        Default::default()
+    );
+}
+
+#[test]
+fn attestation_timing_tolerance_1() {
+    let event = v1_event();
+    let oracle = ::nostr::Keys::generate();
+    let policy = |tolerance_seconds| AttestationTimingPolicy {
+        tolerance_seconds,
+        ..Default::default()
+    };
+
+    // before expected payout
+    let at_expected_payout = attestation(&event, vec![10, 0], &oracle, EXPECTED_PAYOUT);
+    assert_eq!(
+        validate_attestation_timing(&event, &at_expected_payout, &[], &policy(0)).unwrap(),
+        Default::default()
+    );
+    let early = attestation(&event, vec![10, 0], &oracle, EXPECTED_PAYOUT - 60);
+    assert!(
+        validate_attestation_timing(&event, &early, &[], &policy(60))
+            .unwrap()
+            .is_ok()
+    );
+    assert_eq!(
+        validate_attestation_timing(&event, &early, &[], &policy(59))
+            .unwrap()
+            .errors,
+        vec![AttestationTimingIssue::BeforeExpectedPayout {
+            attested_at_unix_seconds: EXPECTED_PAYOUT - 60,
+            expected_payout_unix_seconds: EXPECTED_PAYOUT,
+        }]
+    );
+
+    // after pledge deadline
+    let early_pledge = pledge(&event, &oracle, EXPECTED_PAYOUT - 1000, Option::None);
+    let pledges = std::slice::from_ref(&early_pledge);
+    let at_deadline = attestation(&event, vec![10, 0], &oracle, DEADLINE);
+    assert_eq!(
+        validate_attestation_timing(&event, &at_deadline, pledges, &policy(0)).unwrap(),
+        Default::default()
+    );
+    let late = attestation(&event, vec![10, 0], &oracle, DEADLINE + 60);
+    assert_eq!(
+        validate_attestation_timing(&event, &late, pledges, &policy(60)).unwrap(),
+        Default::default()
+    );
+    assert_eq!(
+        validate_attestation_timing(&event, &late, pledges, &policy(59))
+            .unwrap()
+            .warnings,
+        vec![AttestationTimingIssue::AfterPledgeDeadline {
+            pledge_nostr_event_id: NostrEventIdHex(early_pledge.id.to_hex()),
+            attested_at_unix_seconds: DEADLINE + 60,
+            attestation_deadline_unix_seconds: DEADLINE,
+        }]
+    );
+
+    // pledge after attestation
+    let same_time_pledge = pledge(&event, &oracle, EXPECTED_PAYOUT, Option::None);
+    assert_eq!(
+        validate_attestation_timing(&event, &at_expected_payout, &[same_time_pledge], &policy(0))
+            .unwrap(),
+        Default::default()
+    );
+    let late_pledge = pledge(&event, &oracle, EXPECTED_PAYOUT + 60, Option::None);
+    let pledges = std::slice::from_ref(&late_pledge);
+    assert!(
+        validate_attestation_timing(&event, &at_expected_payout, pledges, &policy(60))
+            .unwrap()
+            .is_ok()
+    );
+    assert_eq!(
+        validate_attestation_timing(&event, &at_expected_payout, pledges, &policy(59))
+            .unwrap()
+            .errors,
+        vec![AttestationTimingIssue::PledgeAfterAttestation {
+            pledge_nostr_event_id: NostrEventIdHex(late_pledge.id.to_hex()),
+            attested_at_unix_seconds: EXPECTED_PAYOUT,
+            pledged_at_unix_seconds: EXPECTED_PAYOUT + 60,
+        }]
+    );
+}
+
+#[test]
+fn attestation_timing_severity_1() {
+    let event = v1_event();
+    let oracle = ::nostr::Keys::generate();
+    // early attestation, late pledge, and an attestation after the deadline of the late pledge
+    let early = attestation(&event, vec![10, 0], &oracle, EXPECTED_PAYOUT - 1);
+    let late_pledge = pledge(&event, &oracle, DEADLINE + 10, Option::None);
+    let late = attestation(&event, vec![10, 0], &oracle, DEADLINE + 1);
+    let pledges = std::slice::from_ref(&late_pledge);
+    let before_expected_payout = AttestationTimingIssue::BeforeExpectedPayout {
+        attested_at_unix_seconds: EXPECTED_PAYOUT - 1,
+        expected_payout_unix_seconds: EXPECTED_PAYOUT,
+    };
+    let pledge_after_early = AttestationTimingIssue::PledgeAfterAttestation {
+        pledge_nostr_event_id: NostrEventIdHex(late_pledge.id.to_hex()),
+        attested_at_unix_seconds: EXPECTED_PAYOUT - 1,
+        pledged_at_unix_seconds: DEADLINE + 10,
+    };
+    let after_pledge_deadline = AttestationTimingIssue::AfterPledgeDeadline {
+        pledge_nostr_event_id: NostrEventIdHex(late_pledge.id.to_hex()),
+        attested_at_unix_seconds: DEADLINE + 1,
+        attestation_deadline_unix_seconds: DEADLINE,
+    };
+
+    let report = validate_attestation_timing(&event, &early, pledges, &Default::default()).unwrap();
+    assert_eq!(
+        report.errors,
+        vec![before_expected_payout.clone(), pledge_after_early.clone()]
+    );
+    assert!(report.warnings.is_empty());
+
+    let inverted = AttestationTimingPolicy {
+        tolerance_seconds: 0,
+        before_expected_payout: TimingSeverity::Warning,
+        after_pledge_deadline: TimingSeverity::Error,
+        pledge_after_attestation: TimingSeverity::Warning,
+    };
+    let report = validate_attestation_timing(&event, &early, pledges, &inverted).unwrap();
+    assert!(report.is_ok());
+    assert_eq!(
+        report.warnings,
+        vec![before_expected_payout, pledge_after_early]
+    );
+    let report = validate_attestation_timing(&event, &late, pledges, &inverted).unwrap();
+    assert_eq!(report.errors, vec![after_pledge_deadline]);
+    assert_eq!(report.warnings.len(), 1);
+}
+
+#[test]
+fn attestation_timing_ignored_pledges_1() {
+    let event = v1_event();
+    let other_event = v1_event();
+    let oracle = ::nostr::Keys::generate();
+    let other_oracle = ::nostr::Keys::generate();
+    let policy = AttestationTimingPolicy::default();
+    let late = attestation(&event, vec![10, 0], &oracle, DEADLINE + 1);
+
+    // pledged after attesting and with a passed deadline, but by another oracle or for another event
+    let pledge_without_terms = sign(
+        FutureEventPayoutAttestationPledge::create_nostr_event_builder(
+            &AttestationPledge::new(&event, Option::None).unwrap(),
+        )
+        .unwrap(),
+        &oracle,
+        EXPECTED_PAYOUT - 1000,
+    );
+    let nostr_events = [
+        pledge(&event, &other_oracle, DEADLINE + 2, Option::None),
+        pledge(&other_event, &oracle, DEADLINE + 2, Option::None),
+        attestation(&event, vec![0, 10], &oracle, DEADLINE + 2),
+        pledge_without_terms,
+    ];
+    assert_eq!(
+        validate_attestation_timing(&event, &late, &nostr_events, &policy).unwrap(),
+        Default::default()
+    );
+
+    // the same pledge by the oracle for event is used
+    let own = pledge(&event, &oracle, DEADLINE + 2, Option::None);
+    let report =
+        validate_attestation_timing(&event, &late, std::slice::from_ref(&own), &policy).unwrap();
+    assert_eq!(report.warnings.len(), 1);
+    assert_eq!(report.errors.len(), 1);
+}
@@ -0,0 +1,128 @@
+use crate::{
+    information::Information,
+    nostr_event_types::{
+        bulk::verify_signatures, EventPayoutAttestation, NewEvent, NostrEventUtils,
+    },
+    Error, Event, EventPayout,
+};
+
+fn attestations(event_payout: &EventPayout, count: usize) -> Vec<::nostr::Event> {
+    let builder = EventPayoutAttestation::create_nostr_event_builder(event_payout).unwrap();
+
+    (0..count)
+        .map(|_| {
+            builder
+                .clone()
+                .to_event(&::nostr::Keys::generate())
+                .unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn nostr_interpret_bulk_1() {
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let event_payout = EventPayout::new(&event, vec![10, 0]).unwrap();
+    let mut nostr_events = attestations(&event_payout, 20);
+
+    // invalid signature
+    let mut forged = nostr_events[3].clone();
+    forged.sig = nostr_events[4].sig;
+    nostr_events.push(forged);
+    // repeated nostr event
+    nostr_events.push(nostr_events[7].clone());
+    // other kind
+    nostr_events.push(
+        NewEvent::create_nostr_event_builder(&event)
+            .unwrap()
+            .to_event(&::nostr::Keys::generate())
+            .unwrap(),
+    );
+
+    let bulk = EventPayoutAttestation::interpret_nostr_events_bulk(&nostr_events);
+    assert!(!bulk.is_all_valid());
+    assert_eq!(bulk.duplicates, vec![21]);
+
+    let invalid_indexes: Vec<usize> = bulk.invalid.iter().map(|(i, _)| *i).collect();
+    assert_eq!(invalid_indexes, vec![20, 22]);
+    assert!(matches!(bulk.invalid[0].1, Error::NostrEvent(_)));
+    assert!(matches!(bulk.invalid[1].1, Error::Validation(_)));
+
+    let interpreted_indexes: Vec<usize> = bulk.interpreted.iter().map(|(i, _)| *i).collect();
+    assert_eq!(interpreted_indexes, (0..20).collect::<Vec<_>>());
+    for (index, interpreted) in bulk.interpreted {
+        assert_eq!(
+            interpreted,
+            EventPayoutAttestation::interpret_nostr_event_enveloped(&nostr_events[index]).unwrap()
+        );
+        assert_eq!(interpreted.value.1, event_payout);
+    }
+
+    assert!(EventPayoutAttestation::interpret_nostr_events_bulk(&[]).is_all_valid());
+}
+
+#[test]
+fn nostr_verify_signatures_1() {
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let mut nostr_events = attestations(&EventPayout::new(&event, vec![10, 0]).unwrap(), 100);
+    assert!(verify_signatures(nostr_events.iter()));
+    assert!(verify_signatures(nostr_events[..1].iter()));
+    assert!(verify_signatures([].iter()));
+
+    nostr_events[70].sig = nostr_events[71].sig;
+    assert!(!verify_signatures(nostr_events.iter()));
+    assert!(verify_signatures(nostr_events[..70].iter()));
+    assert!(!verify_signatures(nostr_events[70..71].iter()));
+}
+
+#[test]
+fn nostr_interpret_bulk_batches_1() {
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let event_payout = EventPayout::new(&event, vec![10, 0]).unwrap();
+    let mut nostr_events = attestations(&event_payout, 1100);
+
+    // invalid signatures in both batches
+    nostr_events[100].sig = nostr_events[101].sig;
+    nostr_events[1050].sig = nostr_events[1051].sig;
+    // valid signature over an id that does not match the content
+    let keys = ::nostr::Keys::generate();
+    let mut tampered = nostr_events[0].clone();
+    tampered.pubkey = keys.public_key;
+    tampered.sig = keys.sign_schnorr(&::nostr::secp256k1::Message::from_digest(
+        tampered.id.to_bytes(),
+    ));
+    assert!(tampered.verify_signature());
+    nostr_events[5] = tampered;
+
+    let bulk = EventPayoutAttestation::interpret_nostr_events_bulk(&nostr_events);
+    let invalid_indexes: Vec<usize> = bulk.invalid.iter().map(|(i, _)| *i).collect();
+    assert_eq!(invalid_indexes, vec![5, 100, 1050]);
+    assert!(matches!(
+        bulk.invalid[0].1,
+        Error::NostrEvent(::nostr::event::Error::InvalidId)
+    ));
+    assert!(matches!(
+        bulk.invalid[1].1,
+        Error::NostrEvent(::nostr::event::Error::InvalidSignature)
+    ));
+    assert!(matches!(
+        bulk.invalid[2].1,
+        Error::NostrEvent(::nostr::event::Error::InvalidSignature)
+    ));
+    assert_eq!(bulk.interpreted.len(), 1097);
+}
+
+#[test]
+fn nostr_interpret_bulk_signature_verified_1() {
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let nostr_events = attestations(&EventPayout::new(&event, vec![10, 0]).unwrap(), 200);
+    let mut forged = nostr_events[0].clone();
+    forged.sig = nostr_events[1].sig;
+
+    // batch verification only applies to the nostr events of the batch
+    assert!(EventPayoutAttestation::interpret_nostr_events_bulk(&nostr_events).is_all_valid());
+    assert!(matches!(
+        EventPayoutAttestation::interpret_nostr_event(&forged),
+        Err(Error::NostrEvent(::nostr::event::Error::InvalidSignature))
+    ));
+}
@@ -0,0 +1,274 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures_util::{SinkExt, StreamExt};
+use nostr::{
+    message::{ClientMessage, RelayMessage, SubscriptionId},
+    types::Filter,
+    util::JsonUtil,
+};
+use tokio::{net::TcpListener, sync::broadcast};
+use tokio_tungstenite::{accept_async, tungstenite::Message};
+
+use crate::{
+    client::{Client, Subscription, SubscriptionItem},
+    information::Information,
+    nostr_event_types::{NewEvent, NostrEventUtils},
+    signer::TestSigner,
+    Error, Event,
+};
+
+/// Minimal in-process relay, stores every verified nostr event and serves subscriptions.
+/// Rejects every nostr event if accept is false.
+pub(super) async fn spawn_test_relay(accept: bool) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let relay_url = format!("ws://{}", listener.local_addr().unwrap());
+    let stored: Arc<Mutex<Vec<::nostr::Event>>> = Arc::default();
+    let (live, _) = broadcast::channel::<::nostr::Event>(64);
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let stored = stored.clone();
+            let live = live.clone();
+            tokio::spawn(async move {
+                let mut ws = accept_async(stream).await.unwrap();
+                let mut live_receiver = live.subscribe();
+                let mut subscriptions: HashMap<SubscriptionId, Vec<Filter>> = HashMap::new();
+                loop {
+                    let replies = tokio::select! {
+                        message = ws.next() => {
+                            let Some(Ok(Message::Text(text))) = message else {
+                                return;
+                            };
+                            match ClientMessage::from_json(text.as_str()).unwrap() {
+                                ClientMessage::Event(nostr_event) => {
+                                    let ok = accept && nostr_event.verify().is_ok();
+                                    if ok {
+                                        stored.lock().unwrap().push(*nostr_event.clone());
+                                        let _ = live.send(*nostr_event.clone());
+                                    }
+                                    vec![RelayMessage::ok(nostr_event.id, ok, if ok { "" } else { "blocked: test" })]
+                                }
+                                ClientMessage::Req { subscription_id, filters } => {
+                                    let mut replies: Vec<RelayMessage> = stored
+                                        .lock()
+                                        .unwrap()
+                                        .iter()
+                                        .filter(|e| filters.iter().any(|f| f.match_event(e)))
+                                        .map(|e| RelayMessage::event(subscription_id.clone(), e.clone()))
+                                        .collect();
+                                    replies.push(RelayMessage::eose(subscription_id.clone()));
+                                    subscriptions.insert(subscription_id, filters);
+                                    replies
+                                }
+                                ClientMessage::Close(subscription_id) => {
+                                    subscriptions.remove(&subscription_id);
+                                    vec![]
+                                }
+                                _ => vec![],
+                            }
+                        }
+                        Ok(nostr_event) = live_receiver.recv() => subscriptions
+                            .iter()
+                            .filter(|(_, filters)| filters.iter().any(|f| f.match_event(&nostr_event)))
+                            .map(|(id, _)| RelayMessage::event(id.clone(), nostr_event.clone()))
+                            .collect(),
+                    };
+                    for reply in replies {
+                        ws.send(Message::text(reply.as_json())).await.unwrap();
+                    }
+                }
+            });
+        }
+    });
+
+    relay_url
+}
+
+/// Relay that answers every subscription with nostr_events after delay, without verifying them.
+async fn spawn_static_relay(nostr_events: Vec<::nostr::Event>, delay: Duration) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let relay_url = format!("ws://{}", listener.local_addr().unwrap());
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let nostr_events = nostr_events.clone();
+            tokio::spawn(async move {
+                let mut ws = accept_async(stream).await.unwrap();
+                while let Some(Ok(Message::Text(text))) = ws.next().await {
+                    let ClientMessage::Req {
+                        subscription_id, ..
+                    } = ClientMessage::from_json(text.as_str()).unwrap()
+                    else {
+                        continue;
+                    };
+                    tokio::time::sleep(delay).await;
+                    for nostr_event in nostr_events.iter() {
+                        let reply =
+                            RelayMessage::event(subscription_id.clone(), nostr_event.clone());
+                        ws.send(Message::text(reply.as_json())).await.unwrap();
+                    }
+                    let reply = RelayMessage::eose(subscription_id);
+                    ws.send(Message::text(reply.as_json())).await.unwrap();
+                }
+            });
+        }
+    });
+
+    relay_url
+}
+
+/// Url of a port nothing listens on.
+async fn unreachable_relay_url() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    format!("ws://{}", listener.local_addr().unwrap())
+}
+
+async fn next_item<T: NostrEventUtils>(
+    subscription: &mut Subscription<T>,
+) -> SubscriptionItem<T::InterpretResult> {
+    tokio::time::timeout(Duration::from_secs(5), subscription.next())
+        .await
+        .unwrap()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn client_publish_subscribe_1() {
+    let relay_urls = vec![
+        spawn_test_relay(true).await,
+        spawn_test_relay(true).await,
+        unreachable_relay_url().await,
+    ];
+    let (client, errors) = Client::connect(&relay_urls).await;
+    assert_eq!(client.relay_urls(), vec![&relay_urls[0], &relay_urls[1]]);
+    assert!(matches!(&errors[..], [Error::Relay { relay_url, .. }] if relay_url == &relay_urls[2]));
+
+    let keys = ::nostr::Keys::generate();
+    let stored_event = Event::new_with_random_nonce(2, 10, Information::None);
+    let (nostr_event, results) = client
+        .publish_with_signer::<NewEvent, _>(&stored_event, &keys)
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|(_, r)| r.is_ok()));
+
+    // concurrent publishes of the same nostr event all get its OK
+    let (first, second) = tokio::join!(
+        client.publish_nostr_event(&nostr_event),
+        client.publish_nostr_event(&nostr_event)
+    );
+    assert!(first.iter().chain(&second).all(|(_, r)| r.is_ok()));
+
+    // stored on both relays, interpreted once
+    let mut subscription = client.subscribe::<NewEvent>(vec![]);
+    let mut end_of_stored_events = 0;
+    let mut interpreted = Vec::new();
+    while end_of_stored_events < 2 {
+        match next_item(&mut subscription).await {
+            SubscriptionItem::EndOfStoredEvents { .. } => end_of_stored_events += 1,
+            SubscriptionItem::Interpreted { interpreted: i, .. } => {
+                interpreted.push(i.into_inner())
+            }
+            item => panic!("unexpected item {item:?}"),
+        }
+    }
+    assert_eq!(interpreted, vec![stored_event]);
+
+    let live_event = Event::new_with_random_nonce(3, 10, Information::None);
+    client
+        .publish_with_signer::<NewEvent, _>(&live_event, &keys)
+        .await
+        .unwrap();
+    let malformed = ::nostr::EventBuilder::new(NewEvent::KIND, "not an event", [])
+        .to_event(&keys)
+        .unwrap();
+    client.publish_nostr_event(&malformed).await;
+
+    let SubscriptionItem::Interpreted {
+        nostr_event,
+        interpreted,
+    } = next_item(&mut subscription).await
+    else {
+        panic!("expected interpreted item");
+    };
+    assert_eq!(interpreted.value, live_event);
+    assert_eq!(
+        interpreted.metadata.nostr_event_id.0,
+        nostr_event.id.to_hex()
+    );
+    assert!(relay_urls[..2].contains(interpreted.metadata.relay_url.as_ref().unwrap()));
+    let SubscriptionItem::Invalid { nostr_event, .. } = next_item(&mut subscription).await else {
+        panic!("expected invalid item");
+    };
+    assert_eq!(nostr_event.id, malformed.id);
+    // copies from the second relay are deduplicated
+    assert!(
+        tokio::time::timeout(Duration::from_millis(200), subscription.next())
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn client_publish_rejected_1() {
+    let relay_urls = vec![spawn_test_relay(true).await, spawn_test_relay(false).await];
+    let (client, errors) = Client::connect(&relay_urls).await;
+    assert!(errors.is_empty());
+
+    let signer = TestSigner::new("client_publish_rejected_1");
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let (nostr_event, results) = client
+        .publish_with_signer::<NewEvent, _>(&event, &signer)
+        .await
+        .unwrap();
+    assert_eq!(signer.signed_event_ids(), vec![nostr_event.id]);
+    assert!(matches!(&results[0], (url, Ok(())) if url == &relay_urls[0]));
+    assert!(matches!(
+        &results[1],
+        (url, Err(Error::Relay { message, .. })) if url == &relay_urls[1] && message == "rejected: blocked: test"
+    ));
+}
+
+#[tokio::test]
+async fn client_subscribe_forged_copy_1() {
+    let keys = ::nostr::Keys::generate();
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let genuine = NewEvent::create_nostr_signed_event(&event, &keys).unwrap();
+    // reuses id and signature of the genuine nostr event
+    let mut forged_json: serde_json::Value = serde_json::from_str(&genuine.as_json()).unwrap();
+    forged_json["content"] = "forged".into();
+    let forged = ::nostr::Event::from_json(forged_json.to_string()).unwrap();
+    assert_eq!(forged.id, genuine.id);
+
+    let relay_urls = vec![
+        spawn_static_relay(vec![forged], Duration::ZERO).await,
+        spawn_static_relay(vec![genuine.clone()], Duration::from_millis(200)).await,
+    ];
+    let (client, errors) = Client::connect(&relay_urls).await;
+    assert!(errors.is_empty());
+
+    let mut subscription = client.subscribe::<NewEvent>(vec![]);
+    let mut invalid = Vec::new();
+    let mut interpreted = Vec::new();
+    let mut end_of_stored_events = 0;
+    while end_of_stored_events < 2 {
+        match next_item(&mut subscription).await {
+            SubscriptionItem::EndOfStoredEvents { .. } => end_of_stored_events += 1,
+            SubscriptionItem::Invalid { relay_url, .. } => invalid.push(relay_url),
+            SubscriptionItem::Interpreted {
+                nostr_event,
+                interpreted: i,
+            } => {
+                assert_eq!(*nostr_event, genuine);
+                interpreted.push(i.metadata.relay_url.unwrap());
+            }
+            item => panic!("unexpected item {item:?}"),
+        }
+    }
+    assert_eq!(invalid, vec![relay_urls[0].clone()]);
+    assert_eq!(interpreted, vec![relay_urls[1].clone()]);
+}
@@ -0,0 +1,239 @@
+//! Runs the machine-readable test vectors in `test-vectors/` against this implementation.
+
+use serde::Deserialize;
+
+#[allow(unused_imports)]
+use crate::{information::*, nostr_event_types::*, *};
+
+#[derive(Deserialize)]
+struct VectorFile<T> {
+    vectors: Vec<T>,
+    #[serde(default)]
+    unparsable: Vec<UnparsableEventVector>,
+}
+
+#[derive(Deserialize)]
+struct EventVector {
+    description: String,
+    event_json: String,
+    hash_hex: String,
+    validation: Vec<EventValidationVector>,
+}
+
+#[derive(Deserialize)]
+struct EventValidationVector {
+    accepted_information_variant_ids: Vec<String>,
+    valid: bool,
+}
+
+#[derive(Deserialize)]
+struct UnparsableEventVector {
+    description: String,
+    event_json: String,
+}
+
+#[derive(Deserialize)]
+struct EventPayoutVector {
+    description: String,
+    event_json: String,
+    event_payout_json: String,
+    valid: bool,
+}
+
+#[derive(Deserialize)]
+struct AttestationPledgeVector {
+    description: String,
+    event_json: String,
+    attestation_pledge_json: String,
+    valid: bool,
+}
+
+#[derive(Deserialize)]
+struct NostrEventVector {
+    description: String,
+    kind: u16,
+    event_hash_tag_mode: Option<String>,
+    nostr_event_json: String,
+    valid: bool,
+    expected: Option<NostrEventExpected>,
+}
+
+#[derive(Deserialize)]
+struct NostrEventExpected {
+    event_json: Option<String>,
+    event_hash_hex: Option<String>,
+    nostr_public_key_hex: Option<String>,
+    units_per_outcome: Option<Vec<PayoutUnit>>,
+    pledge_terms: Option<AttestationPledgeTerms>,
+}
+
+#[derive(Deserialize)]
+struct EventPointerVector {
+    description: String,
+    bech32: String,
+    valid: bool,
+    #[serde(default)]
+    canonical: bool,
+    expected: Option<EventPointer>,
+}
+
+#[test]
+fn conformance_v1_events() {
+    let file: VectorFile<EventVector> =
+        serde_json::from_str(include_str!("../../test-vectors/v1/events.json")).unwrap();
+
+    for v in file.vectors {
+        let event = Event::try_from_json_str(&v.event_json)
+            .unwrap_or_else(|e| panic!("{}: failed to parse event: {e}", v.description));
+        assert_eq!(
+            event.try_to_json_string().unwrap(),
+            v.event_json,
+            "{}: event json is not canonical",
+            v.description
+        );
+        assert_eq!(event.hash_hex().unwrap().0, v.hash_hex, "{}", v.description);
+
+        for validation in v.validation {
+            let accepted: Vec<&str> = validation
+                .accepted_information_variant_ids
+                .iter()
+                .map(|s| s.as_str())
+                .collect();
+            let res = event.validate(&accepted);
+            assert_eq!(
+                res.is_ok(),
+                validation.valid,
+                "{}: accepting {accepted:?}: {res:?}",
+                v.description
+            );
+        }
+    }
+
+    for v in file.unparsable {
+        let res = Event::try_from_json_str(&v.event_json);
+        assert!(res.is_err(), "{}", v.description);
+    }
+}
+
+#[test]
+fn conformance_v1_event_payouts() {
+    let file: VectorFile<EventPayoutVector> =
+        serde_json::from_str(include_str!("../../test-vectors/v1/event_payouts.json")).unwrap();
+
+    for v in file.vectors {
+        let event = Event::try_from_json_str(&v.event_json).unwrap();
+        let event_payout = EventPayout::try_from_json_str(&v.event_payout_json).unwrap();
+
+        let res = event_payout.validate(&event);
+        assert_eq!(res.is_ok(), v.valid, "{}: {res:?}", v.description);
+    }
+}
+
+#[test]
+fn conformance_v1_nostr_events() {
+    run_nostr_event_vectors(include_str!("../../test-vectors/v1/nostr_events.json"));
+}
+
+#[test]
+fn conformance_v2_nostr_events() {
+    run_nostr_event_vectors(include_str!("../../test-vectors/v2/nostr_events.json"));
+}
+
+fn run_nostr_event_vectors(json: &str) {
+    let file: VectorFile<NostrEventVector> = serde_json::from_str(json).unwrap();
+
+    for v in file.vectors {
+        let mut options = InterpretOptions::default();
+        match v.event_hash_tag_mode.as_deref() {
+            Option::None | Some("transition") => options.event_hash_tag_mode = EventHashTagMode::Transition,
+            Some("dedicated") => options.event_hash_tag_mode = EventHashTagMode::Dedicated,
+            Some(mode) => panic!("{}: unknown event hash tag mode {mode}", v.description),
+        }
+
+        match v.kind {
+            NewEvent::KIND_U16 => {
+                let res = NewEvent::interpret_nostr_event_json_with_options(
+                    &v.nostr_event_json,
+                    &options,
+                );
+                assert_eq!(res.is_ok(), v.valid, "{}: {res:?}", v.description);
+                if let (Ok(event), Some(expected)) = (res, v.expected) {
+                    assert_eq!(Some(event.try_to_json_string().unwrap()), expected.event_json);
+                    assert_eq!(Some(event.hash_hex().unwrap().0), expected.event_hash_hex);
+                }
+            }
+            FutureEventPayoutAttestationPledge::KIND_U16 => {
+                let res =
+                    FutureEventPayoutAttestationPledge::interpret_nostr_event_json_with_options(
+                        &v.nostr_event_json,
+                        &options,
+                    );
+                assert_eq!(res.is_ok(), v.valid, "{}: {res:?}", v.description);
+                if let (Ok((pk, pledge)), Some(expected)) = (res, v.expected) {
+                    assert_eq!(Some(pk.0), expected.nostr_public_key_hex);
+                    assert_eq!(Some(pledge.event_hash_hex.0), expected.event_hash_hex);
+                    assert_eq!(pledge.terms, expected.pledge_terms);
+                }
+            }
+            EventPayoutAttestation::KIND_U16 => {
+                let res = EventPayoutAttestation::interpret_nostr_event_json_with_options(
+                    &v.nostr_event_json,
+                    &options,
+                );
+                assert_eq!(res.is_ok(), v.valid, "{}: {res:?}", v.description);
+                if let (Ok((pk, event_payout)), Some(expected)) = (res, v.expected) {
+                    assert_eq!(Some(pk.0), expected.nostr_public_key_hex);
+                    assert_eq!(
+                        Some(event_payout.event_hash_hex.0),
+                        expected.event_hash_hex
+                    );
+                    assert_eq!(
+                        Some(event_payout.units_per_outcome),
+                        expected.units_per_outcome
+                    );
+                }
+            }
+            kind => panic!("{}: unknown kind {kind}", v.description),
+        }
+    }
+}
+
+#[test]
+fn conformance_v3_attestation_pledges() {
+    let file: VectorFile<AttestationPledgeVector> =
+        serde_json::from_str(include_str!("../../test-vectors/v3/attestation_pledges.json"))
+            .unwrap();
+
+    for v in file.vectors {
+        let event = Event::try_from_json_str(&v.event_json).unwrap();
+        let pledge: AttestationPledge = serde_json::from_str(&v.attestation_pledge_json).unwrap();
+
+        let res = pledge.validate(&event);
+        assert_eq!(res.is_ok(), v.valid, "{}: {res:?}", v.description);
+    }
+}
+
+#[test]
+fn conformance_v3_nostr_events() {
+    run_nostr_event_vectors(include_str!("../../test-vectors/v3/nostr_events.json"));
+}
+
+#[test]
+fn conformance_v4_event_pointers() {
+    let file: VectorFile<EventPointerVector> =
+        serde_json::from_str(include_str!("../../test-vectors/v4/event_pointers.json")).unwrap();
+
+    for v in file.vectors {
+        let res: Result<EventPointer, _> = v.bech32.parse();
+        assert_eq!(res.is_ok(), v.valid, "{}: {res:?}", v.description);
+        if let (Ok(pointer), Some(expected)) = (res, v.expected) {
+            assert_eq!(pointer, expected, "{}", v.description);
+            assert_eq!(
+                pointer.to_bech32().unwrap() == v.bech32,
+                v.canonical,
+                "{}",
+                v.description
+            );
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use ::nostr::util::JsonUtil;
+
+use super::util::sign;
+use crate::{
+    information::Information,
+    nostr_event_types::{
+        resolve_attestation_history, AttestationCorrection, EventPayoutAttestation,
+        EventPayoutAttestationCorrection, NostrEventIdHex, NostrEventUtils, NostrPublicKeyHex,
+    },
+    Error, Event, EventPayout, PayoutUnit,
+};
+
+#[test]
+fn nostr_event_payout_attestation_correction_1() {
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let keys = ::nostr::Keys::generate();
+    let correction = AttestationCorrection {
+        corrected_nostr_event_id: NostrEventIdHex(::nostr::EventId::all_zeros().to_hex()),
+        event_hash_hex: event.hash_hex().unwrap(),
+        units_per_outcome: Some(vec![10, 0]),
+        reason: "outcomes were swapped".into(),
+    };
+
+    let nostr_event_json = EventPayoutAttestationCorrection::create_nostr_signed_event_json(
+        &correction,
+        &keys.secret_key().to_secret_hex(),
+    )
+    .unwrap();
+    let (pk, c) =
+        EventPayoutAttestationCorrection::interpret_nostr_event_json(&nostr_event_json).unwrap();
+    assert_eq!(keys.public_key.to_hex(), pk.0);
+    assert_eq!(correction, c);
+    assert_eq!(
+        c.replacement_event_payout().unwrap(),
+        EventPayout::new(&event, vec![10, 0]).unwrap()
+    );
+
+    // retraction
+    let retraction = AttestationCorrection {
+        units_per_outcome: None,
+        ..correction
+    };
+    let nostr_event_json = EventPayoutAttestationCorrection::create_nostr_signed_event_json(
+        &retraction,
+        &keys.secret_key().to_secret_hex(),
+    )
+    .unwrap();
+    let (_, c) =
+        EventPayoutAttestationCorrection::interpret_nostr_event_json(&nostr_event_json).unwrap();
+    assert_eq!(retraction, c);
+    assert!(c.replacement_event_payout().is_none());
+
+    println!("nostr event json: {nostr_event_json}");
+}
+
+#[test]
+fn nostr_event_payout_attestation_correction_2() {
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let keys = ::nostr::Keys::generate();
+
+    // a correction without an `e` tag is rejected
+    let builder = EventPayoutAttestation::create_nostr_event_builder(
+        &EventPayout::new(&event, vec![0, 10]).unwrap(),
+    )
+    .unwrap();
+    let nostr_event = ::nostr::EventBuilder::new(
+        EventPayoutAttestationCorrection::KIND,
+        r#"{"units_per_outcome":null,"reason":""}"#,
+        sign(builder, &keys, 1).tags,
+    )
+    .to_event(&keys)
+    .unwrap();
+    let res = EventPayoutAttestationCorrection::interpret_nostr_event(&nostr_event);
+    assert!(matches!(res, Err(Error::Validation(_))));
+    println!("{res:?}");
+
+    // the corrected id must be a nostr event id
+    let res = EventPayoutAttestationCorrection::create_nostr_event_builder(
+        &AttestationCorrection {
+            corrected_nostr_event_id: NostrEventIdHex("not an id".into()),
+            event_hash_hex: event.hash_hex().unwrap(),
+            units_per_outcome: None,
+            reason: "".into(),
+        },
+    );
+    assert!(matches!(res, Err(Error::NostrEventId(_))));
+}
+
+#[test]
+fn nostr_resolve_attestation_history_1() {
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let other_event = Event::new_with_random_nonce(2, 10, Information::None);
+    let keys = ::nostr::Keys::generate();
+    let other_keys = ::nostr::Keys::generate();
+    let oracle = NostrPublicKeyHex(keys.public_key.to_hex());
+
+    let attest = |event: &Event, units: Vec<PayoutUnit>, keys: &::nostr::Keys, created_at: u64| {
+        let event_payout = EventPayout::new(event, units).unwrap();
+        let builder = EventPayoutAttestation::create_nostr_event_builder(&event_payout).unwrap();
+        sign(builder, keys, created_at)
+    };
+    let correct = |corrected: &::nostr::Event, units: Option<Vec<PayoutUnit>>, created_at: u64| {
+        let correction = AttestationCorrection {
+            corrected_nostr_event_id: NostrEventIdHex(corrected.id.to_hex()),
+            event_hash_hex: event.hash_hex().unwrap(),
+            units_per_outcome: units,
+            reason: "mistake".into(),
+        };
+        let builder =
+            EventPayoutAttestationCorrection::create_nostr_event_builder(&correction).unwrap();
+        let deletion =
+            EventPayoutAttestationCorrection::create_nostr_deletion_event_builder(&correction)
+                .unwrap();
+        (
+            sign(builder, &keys, created_at),
+            sign(deletion, &keys, created_at),
+        )
+    };
+
+    // nothing attested
+    let history = resolve_attestation_history(&[], &oracle, &event.hash_hex().unwrap());
+    assert!(history.effective_event_payout.is_none());
+    assert!(history.entries.is_empty());
+
+    let attestation = attest(&event, vec![0, 10], &keys, 100);
+    let (correction, deletion) = correct(&attestation, Some(vec![10, 0]), 200);
+    let unrelated = vec![
+        attest(&event, vec![5, 5], &other_keys, 300),
+        attest(&other_event, vec![5, 5], &keys, 300),
+    ];
+
+    // correction supersedes the attestation, regardless of input order
+    let mut nostr_events = vec![correction.clone(), attestation.clone()];
+    nostr_events.extend(unrelated.clone());
+    let history = resolve_attestation_history(&nostr_events, &oracle, &event.hash_hex().unwrap());
+    assert_eq!(
+        history.effective_event_payout,
+        Some(EventPayout::new(&event, vec![10, 0]).unwrap())
+    );
+    assert_eq!(history.entries.len(), 2);
+    assert_eq!(history.entries[0].nostr_event_id.0, attestation.id.to_hex());
+    assert!(history.entries[0].superseded);
+    assert!(!history.entries[0].deleted);
+    assert!(history.entries[1].correction.is_some());
+    assert!(!history.entries[1].superseded);
+
+    // deletion is recorded
+    nostr_events.push(deletion);
+    let history = resolve_attestation_history(&nostr_events, &oracle, &event.hash_hex().unwrap());
+    assert!(history.entries[0].deleted);
+    assert_eq!(
+        history.effective_event_payout,
+        Some(EventPayout::new(&event, vec![10, 0]).unwrap())
+    );
+
+    // retracting the correction leaves no effective payout
+    let (retraction, _) = correct(&correction, None, 300);
+    nostr_events.push(retraction);
+    let history = resolve_attestation_history(&nostr_events, &oracle, &event.hash_hex().unwrap());
+    assert!(history.effective_event_payout.is_none());
+    assert_eq!(history.entries.len(), 3);
+    assert!(history.entries[1].superseded);
+
+    // deleting the only correction falls back to the attestation
+    let correction_deletion = sign(
+        ::nostr::EventBuilder::delete_with_reason([correction.id], "wrong correction"),
+        &keys,
+        250,
+    );
+    let history = resolve_attestation_history(
+        &[attestation.clone(), correction.clone(), correction_deletion],
+        &oracle,
+        &event.hash_hex().unwrap(),
+    );
+    assert_eq!(
+        history.effective_event_payout,
+        Some(EventPayout::new(&event, vec![0, 10]).unwrap())
+    );
+    assert!(!history.entries[0].superseded);
+    assert!(history.entries[1].deleted);
+
+    // two uncorrected attestations: the newer one is effective
+    let newer = attest(&event, vec![3, 7], &keys, 150);
+    let history = resolve_attestation_history(
+        &[attestation.clone(), newer, attestation.clone()],
+        &oracle,
+        &event.hash_hex().unwrap(),
+    );
+    assert_eq!(history.entries.len(), 2);
+    assert_eq!(
+        history.effective_event_payout,
+        Some(EventPayout::new(&event, vec![3, 7]).unwrap())
+    );
+
+    println!("{}", attestation.as_json());
+}
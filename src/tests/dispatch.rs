@@ -0,0 +1,131 @@
+use crate::{
+    information::Information,
+    nostr_event_types::{
+        interpret_any, EventPayoutAttestation, FutureEventPayoutAttestationPledge, KindRegistry,
+        NewEvent, NostrEventUtils, NostrPublicKeyHex, PredictionMarketMessage,
+    },
+    AttestationPledge, Error, Event, EventPayout,
+};
+
+/// Custom kind used to test [KindRegistry].
+struct MarketComment;
+
+impl NostrEventUtils for MarketComment {
+    const KIND_U16: u16 = 6299;
+
+    type CreateParameter = String;
+
+    fn create_nostr_event_builder(
+        comment: &Self::CreateParameter,
+    ) -> Result<::nostr::EventBuilder, Error> {
+        Ok(::nostr::EventBuilder::new(Self::KIND, comment, []))
+    }
+
+    type InterpretResult = (NostrPublicKeyHex, String);
+
+    fn interpret_nostr_event(nostr_event: &::nostr::Event) -> Result<Self::InterpretResult, Error> {
+        nostr_event.verify()?;
+
+        Ok((
+            NostrPublicKeyHex(nostr_event.pubkey.to_hex()),
+            nostr_event.content.clone(),
+        ))
+    }
+}
+
+#[test]
+fn nostr_interpret_any_1() {
+    let keys = ::nostr::Keys::generate();
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+
+    let new_event = NewEvent::create_nostr_event_builder(&event)
+        .unwrap()
+        .custom_created_at(::nostr::Timestamp::from(1000))
+        .to_event(&keys)
+        .unwrap();
+    let message = interpret_any(&new_event).unwrap();
+    assert_eq!(message.kind(), NewEvent::KIND_U16);
+    assert_eq!(message.metadata().nostr_event_id.0, new_event.id.to_hex());
+    assert_eq!(message.metadata().author.0, keys.public_key.to_hex());
+    assert_eq!(message.metadata().created_at_unix_seconds, 1000);
+    assert!(matches!(message, PredictionMarketMessage::NewEvent { event: e, .. } if e == event));
+
+    let pledge = AttestationPledge::new(&event, Option::None).unwrap();
+    let nostr_event = FutureEventPayoutAttestationPledge::create_nostr_event_builder(&pledge)
+        .unwrap()
+        .to_event(&keys)
+        .unwrap();
+    assert!(matches!(
+        interpret_any(&nostr_event).unwrap(),
+        PredictionMarketMessage::Pledge { pledge: p, .. } if p == pledge
+    ));
+
+    let event_payout = EventPayout::new(&event, vec![10, 0]).unwrap();
+    let nostr_event = EventPayoutAttestation::create_nostr_event_builder(&event_payout)
+        .unwrap()
+        .to_event(&keys)
+        .unwrap();
+    assert!(matches!(
+        interpret_any(&nostr_event).unwrap(),
+        PredictionMarketMessage::Attestation { referenced_event_payout, .. }
+            if referenced_event_payout.event_payout == event_payout
+    ));
+
+    let malformed = ::nostr::EventBuilder::new(NewEvent::KIND, "not an event", [])
+        .to_event(&keys)
+        .unwrap();
+    assert!(interpret_any(&malformed).is_err());
+
+    let text_note = ::nostr::EventBuilder::text_note("hello", [])
+        .to_event(&keys)
+        .unwrap();
+    assert!(matches!(
+        interpret_any(&text_note).unwrap(),
+        PredictionMarketMessage::Unknown { kind: 1, .. }
+    ));
+}
+
+#[test]
+fn nostr_kind_registry_1() {
+    let keys = ::nostr::Keys::generate();
+    let comment = MarketComment::create_nostr_event_builder(&"looks like rain".to_string())
+        .unwrap()
+        .to_event(&keys)
+        .unwrap();
+
+    // unregistered kinds are unknown
+    let message = interpret_any(&comment).unwrap();
+    assert!(matches!(
+        message,
+        PredictionMarketMessage::Unknown { kind: 6299, .. }
+    ));
+    assert_eq!(message.custom_value::<MarketComment>(), Option::None);
+
+    let mut registry = KindRegistry::new();
+    registry.register::<MarketComment>().unwrap();
+    assert!(matches!(
+        registry.register::<MarketComment>(),
+        Err(Error::Validation(_))
+    ));
+    assert!(matches!(
+        registry.register::<NewEvent>(),
+        Err(Error::Validation(_))
+    ));
+
+    let message = registry.interpret(&comment).unwrap();
+    assert_eq!(message.kind(), MarketComment::KIND_U16);
+    let (author, content) = message.custom_value::<MarketComment>().unwrap();
+    assert_eq!(author.0, keys.public_key.to_hex());
+    assert_eq!(content, "looks like rain");
+
+    // built in kinds are still interpreted
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let new_event = NewEvent::create_nostr_event_builder(&event)
+        .unwrap()
+        .to_event(&keys)
+        .unwrap();
+    assert!(matches!(
+        registry.interpret(&new_event).unwrap(),
+        PredictionMarketMessage::NewEvent { .. }
+    ));
+}
@@ -0,0 +1,155 @@
+use super::util::sign;
+use crate::{
+    information::Information,
+    nostr_event_types::{
+        payout_finality, AttestationCorrection, AttestationDispute, EventPayoutAttestation,
+        EventPayoutAttestationCorrection, EventPayoutAttestationDispute, NostrEventIdHex,
+        NostrEventUtils, NostrPublicKeyHex, PayoutFinality,
+    },
+    Error, Event, EventPayout, PayoutUnit,
+};
+
+fn dispute(disputed: &::nostr::Event, event: &Event, units: Vec<PayoutUnit>) -> AttestationDispute {
+    AttestationDispute {
+        disputed_nostr_event_id: NostrEventIdHex(disputed.id.to_hex()),
+        event_hash_hex: event.hash_hex().unwrap(),
+        proposed_units_per_outcome: units,
+        evidence: vec!["https://example.com/results".into()],
+    }
+}
+
+#[test]
+fn nostr_event_payout_attestation_dispute_1() {
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let keys = ::nostr::Keys::generate();
+    let d = AttestationDispute {
+        disputed_nostr_event_id: NostrEventIdHex(::nostr::EventId::all_zeros().to_hex()),
+        event_hash_hex: event.hash_hex().unwrap(),
+        proposed_units_per_outcome: vec![0, 10],
+        evidence: vec!["https://example.com/results".into()],
+    };
+
+    let nostr_event_json = EventPayoutAttestationDispute::create_nostr_signed_event_json(
+        &d,
+        &keys.secret_key().to_secret_hex(),
+    )
+    .unwrap();
+    let (pk, res) =
+        EventPayoutAttestationDispute::interpret_nostr_event_json(&nostr_event_json).unwrap();
+    assert_eq!(keys.public_key.to_hex(), pk.0);
+    assert_eq!(d, res);
+    assert!(matches!(res.validate(&event), Ok(())));
+
+    let mut invalid = d.clone();
+    invalid.proposed_units_per_outcome = vec![0, 9];
+    assert!(matches!(invalid.validate(&event), Err(Error::Validation(_))));
+
+    let mut invalid = d;
+    invalid.evidence = vec!["".into(); 17];
+    assert!(matches!(invalid.validate(&event), Err(Error::Validation(_))));
+
+    println!("nostr event json: {nostr_event_json}");
+}
+
+#[test]
+fn nostr_payout_finality_1() {
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let oracle_keys = ::nostr::Keys::generate();
+    let user_keys = ::nostr::Keys::generate();
+    let oracle = NostrPublicKeyHex(oracle_keys.public_key.to_hex());
+    let window = 100;
+
+    let res = payout_finality(&event, &oracle, &[], window, 0).unwrap();
+    assert_eq!(res, PayoutFinality::NoAttestation);
+
+    let event_payout = EventPayout::new(&event, vec![10, 0]).unwrap();
+    let attestation = sign(
+        EventPayoutAttestation::create_nostr_event_builder(&event_payout).unwrap(),
+        &oracle_keys,
+        1000,
+    );
+    let mut nostr_events = vec![attestation.clone()];
+
+    let res = payout_finality(&event, &oracle, &nostr_events, window, 1100).unwrap();
+    assert_eq!(
+        res,
+        PayoutFinality::PendingDisputeWindow {
+            event_payout: event_payout.clone(),
+            window_end_unix_seconds: 1100
+        }
+    );
+    let res = payout_finality(&event, &oracle, &nostr_events, window, 1101).unwrap();
+    assert_eq!(
+        res,
+        PayoutFinality::Final {
+            event_payout: event_payout.clone()
+        }
+    );
+
+    // ignored: after the window, backdated before the attestation, identical payout, invalid payout
+    let ignored = [
+        (vec![0, 10], 1101),
+        (vec![0, 10], 999),
+        (vec![10, 0], 1050),
+        (vec![0, 9], 1050),
+    ];
+    for (units, created_at) in ignored {
+        let builder = EventPayoutAttestationDispute::create_nostr_event_builder(&dispute(
+            &attestation,
+            &event,
+            units,
+        ))
+        .unwrap();
+        nostr_events.push(sign(builder, &user_keys, created_at));
+    }
+    let res = payout_finality(&event, &oracle, &nostr_events, window, 2000).unwrap();
+    assert!(matches!(res, PayoutFinality::Final { .. }));
+
+    let d = dispute(&attestation, &event, vec![0, 10]);
+    let builder = EventPayoutAttestationDispute::create_nostr_event_builder(&d).unwrap();
+    nostr_events.push(sign(builder, &user_keys, 1050));
+    let res = payout_finality(&event, &oracle, &nostr_events, window, 2000).unwrap();
+    assert_eq!(
+        res,
+        PayoutFinality::Contested {
+            event_payout,
+            disputes: vec![(NostrPublicKeyHex(user_keys.public_key.to_hex()), d)]
+        }
+    );
+
+    // a correction restarts the window and leaves disputes of the corrected attestation behind
+    let correction = AttestationCorrection {
+        corrected_nostr_event_id: NostrEventIdHex(attestation.id.to_hex()),
+        event_hash_hex: event.hash_hex().unwrap(),
+        units_per_outcome: Some(vec![0, 10]),
+        reason: "disputed correctly".into(),
+    };
+    let builder = EventPayoutAttestationCorrection::create_nostr_event_builder(&correction).unwrap();
+    nostr_events.push(sign(builder, &oracle_keys, 1500));
+    let res = payout_finality(&event, &oracle, &nostr_events, window, 1550).unwrap();
+    assert_eq!(
+        res,
+        PayoutFinality::PendingDisputeWindow {
+            event_payout: EventPayout::new(&event, vec![0, 10]).unwrap(),
+            window_end_unix_seconds: 1600
+        }
+    );
+}
+
+#[test]
+fn nostr_payout_finality_invalid_attestation_1() {
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let oracle_keys = ::nostr::Keys::generate();
+    let oracle = NostrPublicKeyHex(oracle_keys.public_key.to_hex());
+    let attestation = sign(
+        EventPayoutAttestation::create_nostr_event_builder(
+            &EventPayout::new(&event, vec![1, 0]).unwrap(),
+        )
+        .unwrap(),
+        &oracle_keys,
+        1000,
+    );
+
+    let res = payout_finality(&event, &oracle, &[attestation], 100, 2000);
+    assert!(matches!(res, Err(Error::Validation(_))));
+}
@@ -190,3 +190,15 @@ fn event_payout_too_many_units() {
     assert!(matches!(res, Err(Error::Validation(_))));
     println!("{res:?}");
 }
+
+#[test]
+fn event_nonce_not_hex() {
+    let event = Event::new_with_random_nonce(3, 1, Information::None);
+    let json = event.try_to_json_string().unwrap();
+    let nonce_hex = json.split('"').nth(3).unwrap().to_owned();
+
+    let plus_nonce_hex = format!("+1{}", &nonce_hex[2..]);
+    let res = Event::try_from_json_str(&json.replace(&nonce_hex, &plus_nonce_hex));
+    assert!(matches!(res, Err(Error::SerdeJson(_))));
+    println!("{res:?}");
+}
@@ -0,0 +1,256 @@
+use super::util::{attestation, pledge, sign, v1_event, DEADLINE, EXPECTED_PAYOUT};
+use crate::{
+    merkle::PayoutMerkleTree,
+    nostr_event_types::{
+        detect_fraud, detect_unfulfilled_pledges, AttestationCorrection, BatchCommitment,
+        EventPayoutAttestationCorrection, EventPayoutBatchAttestation, FraudDetectionOptions,
+        FraudProof, NostrEventIdHex, NostrEventUtils, NostrPublicKeyHex, UnfulfilledPledge,
+    },
+    Error, Event, EventPayout,
+};
+
+fn correction(
+    event: &Event,
+    corrected: &::nostr::Event,
+    keys: &::nostr::Keys,
+    created_at: u64,
+) -> ::nostr::Event {
+    sign(
+        EventPayoutAttestationCorrection::create_nostr_event_builder(&AttestationCorrection {
+            corrected_nostr_event_id: NostrEventIdHex(corrected.id.to_hex()),
+            event_hash_hex: event.hash_hex().unwrap(),
+            units_per_outcome: Some(vec![0, 10]),
+            reason: "mistake".into(),
+        })
+        .unwrap(),
+        keys,
+        created_at,
+    )
+}
+
+#[test]
+fn fraud_proof_conflicting_attestations_1() {
+    let oracle = ::nostr::Keys::generate();
+    let other_oracle = ::nostr::Keys::generate();
+    let event = v1_event();
+    let options = FraudDetectionOptions {
+        correction_window_seconds: Option::None,
+    };
+
+    let first = attestation(&event, vec![10, 0], &oracle, EXPECTED_PAYOUT);
+    let same = attestation(&event, vec![10, 0], &oracle, EXPECTED_PAYOUT + 1);
+    let other_oracle_attestation = attestation(&event, vec![0, 10], &other_oracle, EXPECTED_PAYOUT);
+    // repeating the payout or attesting differently to another oracle is fine
+    assert!(detect_fraud(
+        &[
+            first.clone(),
+            same.clone(),
+            other_oracle_attestation.clone()
+        ],
+        &options
+    )
+    .is_empty());
+
+    let second = attestation(&event, vec![0, 10], &oracle, EXPECTED_PAYOUT + 2);
+    let third = attestation(&event, vec![5, 5], &oracle, EXPECTED_PAYOUT + 3);
+    let repeated_second = attestation(&event, vec![0, 10], &oracle, EXPECTED_PAYOUT + 4);
+    let fraud_proofs = detect_fraud(
+        &[
+            repeated_second,
+            third.clone(),
+            second.clone(),
+            same,
+            first.clone(),
+            first.clone(),
+            other_oracle_attestation.clone(),
+        ],
+        &options,
+    );
+    assert_eq!(
+        fraud_proofs,
+        vec![
+            FraudProof::ConflictingAttestations {
+                first: first.clone(),
+                second: second.clone(),
+            },
+            FraudProof::ConflictingAttestations {
+                first: first.clone(),
+                second: third,
+            },
+        ]
+    );
+
+    // portable and verifiable on its own
+    let fraud_proof =
+        FraudProof::try_from_json_str(&fraud_proofs[0].try_to_json_string().unwrap()).unwrap();
+    assert_eq!(fraud_proof, fraud_proofs[0]);
+    assert!(fraud_proof.verify(Option::None).is_ok());
+    assert_eq!(
+        fraud_proof.oracle(),
+        NostrPublicKeyHex(oracle.public_key.to_hex())
+    );
+
+    // different keys, events or equal payouts prove nothing
+    let different_keys = FraudProof::ConflictingAttestations {
+        first: first.clone(),
+        second: other_oracle_attestation,
+    };
+    assert!(matches!(
+        different_keys.verify(Option::None),
+        Err(Error::Validation(_))
+    ));
+    let different_events = FraudProof::ConflictingAttestations {
+        first: first.clone(),
+        second: attestation(&v1_event(), vec![0, 10], &oracle, EXPECTED_PAYOUT),
+    };
+    assert!(different_events.verify(Option::None).is_err());
+    let no_conflict = FraudProof::ConflictingAttestations {
+        first: first.clone(),
+        second: first.clone(),
+    };
+    assert!(no_conflict.verify(Option::None).is_err());
+
+    // payouts of batch attestations are not known without inclusion proofs
+    let tree = PayoutMerkleTree::new(vec![EventPayout::new(&event, vec![0, 10]).unwrap()]).unwrap();
+    let batch = sign(
+        EventPayoutBatchAttestation::create_nostr_event_builder(&BatchCommitment::from(&tree))
+            .unwrap(),
+        &oracle,
+        EXPECTED_PAYOUT + 5,
+    );
+    assert!(detect_fraud(&[first.clone(), batch], &options).is_empty());
+
+    // tampered nostr event
+    let mut json: serde_json::Value =
+        serde_json::from_str(&fraud_proof.try_to_json_string().unwrap()).unwrap();
+    json["ConflictingAttestations"]["second"]["created_at"] = (EXPECTED_PAYOUT + 100).into();
+    let tampered = FraudProof::try_from_json_str(&json.to_string()).unwrap();
+    assert!(tampered.verify(Option::None).is_err());
+}
+
+#[test]
+fn fraud_proof_unfulfilled_pledge_1() {
+    let oracle = ::nostr::Keys::generate();
+    let event = v1_event();
+    let pledge_nostr_event = pledge(&event, &oracle, EXPECTED_PAYOUT - 3600, Option::None);
+    let now_unix_seconds = DEADLINE + 1;
+
+    // fulfilled
+    let on_time = attestation(&event, vec![10, 0], &oracle, DEADLINE);
+    assert!(
+        detect_unfulfilled_pledges(&[pledge_nostr_event.clone(), on_time], now_unix_seconds)
+            .is_empty()
+    );
+
+    // deadline has not passed yet
+    assert!(
+        detect_unfulfilled_pledges(std::slice::from_ref(&pledge_nostr_event), DEADLINE).is_empty()
+    );
+
+    // never attested, not a fraud proof
+    let never_attested = UnfulfilledPledge {
+        pledge: pledge_nostr_event.clone(),
+        late_attestation: Option::None,
+    };
+    assert_eq!(
+        detect_unfulfilled_pledges(std::slice::from_ref(&pledge_nostr_event), now_unix_seconds),
+        vec![never_attested.clone()]
+    );
+    assert!(detect_fraud(
+        std::slice::from_ref(&pledge_nostr_event),
+        &FraudDetectionOptions::default()
+    )
+    .is_empty());
+
+    // attested late, found before the deadline passes for the caller
+    let late = attestation(&event, vec![10, 0], &oracle, DEADLINE + 10);
+    let later = attestation(&event, vec![10, 0], &oracle, DEADLINE + 20);
+    assert_eq!(
+        detect_unfulfilled_pledges(&[later, late.clone(), pledge_nostr_event.clone()], 0),
+        vec![UnfulfilledPledge {
+            pledge: pledge_nostr_event.clone(),
+            late_attestation: Some(late),
+        }]
+    );
+
+    // attestations of other oracles do not fulfill the pledge
+    let other_oracle = ::nostr::Keys::generate();
+    let other = attestation(&event, vec![10, 0], &other_oracle, DEADLINE);
+    assert_eq!(
+        detect_unfulfilled_pledges(&[pledge_nostr_event, other], now_unix_seconds),
+        vec![never_attested]
+    );
+
+    // expiring pledges are withdrawn
+    let expiring = pledge(
+        &event,
+        &oracle,
+        EXPECTED_PAYOUT - 3600,
+        Some(EXPECTED_PAYOUT),
+    );
+    assert!(detect_unfulfilled_pledges(&[expiring], now_unix_seconds).is_empty());
+}
+
+#[test]
+fn fraud_proof_late_correction_1() {
+    let oracle = ::nostr::Keys::generate();
+    let event = v1_event();
+    let mistake = attestation(&event, vec![10, 0], &oracle, EXPECTED_PAYOUT);
+    let in_window = correction(&event, &mistake, &oracle, EXPECTED_PAYOUT + 3600);
+    let late = correction(&event, &mistake, &oracle, EXPECTED_PAYOUT + 3601);
+    let options = FraudDetectionOptions {
+        correction_window_seconds: Some(3600),
+    };
+
+    // corrections are not equivocation
+    assert!(detect_fraud(&[mistake.clone(), in_window.clone()], &options).is_empty());
+    assert!(detect_fraud(
+        &[mistake.clone(), late.clone()],
+        &FraudDetectionOptions::default()
+    )
+    .is_empty());
+
+    let fraud_proofs = detect_fraud(&[late.clone(), mistake.clone()], &options);
+    let late_correction = FraudProof::LateCorrection {
+        corrected: mistake.clone(),
+        correction: late.clone(),
+    };
+    assert_eq!(fraud_proofs, vec![late_correction.clone()]);
+    assert!(late_correction.verify(Some(3600)).is_ok());
+    // the window is the policy of the verifier
+    assert!(late_correction.verify(Some(3601)).is_err());
+    assert!(late_correction.verify(Option::None).is_err());
+
+    // corrected nostr event is needed
+    assert!(detect_fraud(std::slice::from_ref(&late), &options).is_empty());
+
+    // correction of a correction
+    let late_again = correction(&event, &in_window, &oracle, EXPECTED_PAYOUT + 7201);
+    assert_eq!(
+        detect_fraud(
+            &[mistake.clone(), in_window.clone(), late_again.clone()],
+            &options
+        ),
+        vec![FraudProof::LateCorrection {
+            corrected: in_window.clone(),
+            correction: late_again,
+        }]
+    );
+
+    let in_window = FraudProof::LateCorrection {
+        corrected: mistake.clone(),
+        correction: in_window,
+    };
+    assert!(in_window.verify(Some(3600)).is_err());
+    let other_oracle = ::nostr::Keys::generate();
+    let not_corrected = FraudProof::LateCorrection {
+        corrected: attestation(&event, vec![10, 0], &oracle, EXPECTED_PAYOUT - 1),
+        correction: late.clone(),
+    };
+    assert!(not_corrected.verify(Some(3600)).is_err());
+    let other_key = FraudProof::LateCorrection {
+        corrected: mistake.clone(),
+        correction: correction(&event, &mistake, &other_oracle, EXPECTED_PAYOUT + 3601),
+    };
+    assert!(other_key.verify(Some(3600)).is_err());
+}
@@ -10,6 +10,7 @@ fn information_none_1() {
 }
 
 #[test]
+#[allow(clippy::manual_repeat_n, clippy::manual_str_repeat)]
 fn information_v1_1() {
     let event = Event::new_with_random_nonce(
         3,
@@ -31,6 +32,7 @@ fn information_v1_1() {
 }
 
 #[test]
+#[allow(clippy::manual_repeat_n, clippy::manual_str_repeat)]
 fn information_v1_too_many_outcome_titles() {
     let event = Event::new_with_random_nonce(
         2,
@@ -53,6 +55,7 @@ fn information_v1_too_many_outcome_titles() {
 }
 
 #[test]
+#[allow(clippy::manual_repeat_n, clippy::manual_str_repeat)]
 fn information_v1_not_enough_outcome_titles() {
     let event = Event::new_with_random_nonce(
         4,
@@ -75,6 +78,7 @@ fn information_v1_not_enough_outcome_titles() {
 }
 
 #[test]
+#[allow(clippy::manual_repeat_n, clippy::manual_str_repeat)]
 fn information_v1_title_too_long() {
     let event = Event::new_with_random_nonce(
         3,
@@ -97,6 +101,7 @@ fn information_v1_title_too_long() {
 }
 
 #[test]
+#[allow(clippy::manual_repeat_n, clippy::manual_str_repeat)]
 fn information_v1_description_too_long() {
     let event = Event::new_with_random_nonce(
         3,
@@ -121,6 +126,7 @@ fn information_v1_description_too_long() {
 }
 
 #[test]
+#[allow(clippy::manual_repeat_n, clippy::manual_str_repeat)]
 fn information_v1_outcome_title_too_long() {
     let event = Event::new_with_random_nonce(
         3,
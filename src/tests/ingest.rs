@@ -0,0 +1,78 @@
+use crate::{
+    information::Information,
+    ingest::IngestValidator,
+    nostr_event_types::{EventPayoutAttestation, NewEvent, NostrEventUtils},
+    Event, EventPayout,
+};
+
+#[test]
+fn ingest_validator_new_event_1() {
+    let validator = IngestValidator::new();
+    let keys = ::nostr::Keys::generate();
+
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let nostr_event = NewEvent::create_nostr_event_builder(&event)
+        .unwrap()
+        .to_event(&keys)
+        .unwrap();
+    assert_eq!(validator.validate(&nostr_event), Ok(()));
+
+    let invalid_event = Event::new_with_random_nonce(1, 10, Information::None);
+    let nostr_event = NewEvent::create_nostr_event_builder(&invalid_event)
+        .unwrap()
+        .to_event(&keys)
+        .unwrap();
+    let message = validator.validate(&nostr_event).unwrap_err();
+    assert!(
+        message.starts_with("invalid: validation failed"),
+        "{message}"
+    );
+
+    let malformed = ::nostr::EventBuilder::new(NewEvent::KIND, "not an event", [])
+        .to_event(&keys)
+        .unwrap();
+    let message = validator.validate(&malformed).unwrap_err();
+    assert!(message.starts_with("invalid: "), "{message}");
+
+    // other kinds only need a valid signature
+    let text_note = ::nostr::EventBuilder::text_note("hello", [])
+        .to_event(&keys)
+        .unwrap();
+    assert_eq!(validator.validate(&text_note), Ok(()));
+    let mut tampered: serde_json::Value =
+        serde_json::from_str(&::nostr::JsonUtil::as_json(&text_note)).unwrap();
+    tampered["content"] = "goodbye".into();
+    let tampered: ::nostr::Event = serde_json::from_value(tampered).unwrap();
+    assert!(validator.validate(&tampered).is_err());
+}
+
+#[test]
+fn ingest_validator_attestation_1() {
+    let mut validator = IngestValidator::new();
+    let keys = ::nostr::Keys::generate();
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+
+    let invalid_payout = EventPayout::new(&event, vec![1, 0]).unwrap();
+    let attestation = EventPayoutAttestation::create_nostr_event_builder(&invalid_payout)
+        .unwrap()
+        .to_event(&keys)
+        .unwrap();
+    // the event is unknown, the attestation can only be interpreted
+    assert_eq!(validator.validate(&attestation), Ok(()));
+
+    let new_event = NewEvent::create_nostr_event_builder(&event)
+        .unwrap()
+        .to_event(&keys)
+        .unwrap();
+    validator.accept(&new_event);
+    assert_eq!(validator.event(&event.hash_hex().unwrap()), Some(&event));
+    let message = validator.validate(&attestation).unwrap_err();
+    assert!(message.starts_with("invalid: "), "{message}");
+
+    let valid_payout = EventPayout::new(&event, vec![4, 6]).unwrap();
+    let attestation = EventPayoutAttestation::create_nostr_event_builder(&valid_payout)
+        .unwrap()
+        .to_event(&keys)
+        .unwrap();
+    assert_eq!(validator.validate(&attestation), Ok(()));
+}
@@ -0,0 +1,179 @@
+use crate::{
+    information::Information,
+    merkle::{PayoutInclusionProof, PayoutMerkleTree},
+    nostr_event_types::{
+        interpret_any, BatchCommitment, EventPayoutBatchAttestation, NostrEventUtils,
+        NostrPublicKeyHex, PredictionMarketMessage,
+    },
+    Error, Event, EventHashHex, EventPayout,
+};
+
+fn event_payouts(count: usize) -> Vec<EventPayout> {
+    (0..count)
+        .map(|i| {
+            let event = Event::new_with_random_nonce(2, 10, Information::None);
+            EventPayout::new(&event, vec![(i % 11) as u32, 10 - (i % 11) as u32]).unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn merkle_proof_1() {
+    for count in 1..=9 {
+        let tree = PayoutMerkleTree::new(event_payouts(count)).unwrap();
+        assert_eq!(tree.leaf_count(), count as u64);
+        for event_payout in tree.event_payouts() {
+            let proof = tree.proof(&event_payout.event_hash_hex).unwrap();
+            assert_eq!(&proof.event_payout, event_payout);
+            assert!(proof.verify(&tree.root_hex(), tree.leaf_count()).is_ok());
+
+            let proof =
+                PayoutInclusionProof::try_from_json_str(&proof.try_to_json_string().unwrap())
+                    .unwrap();
+            assert_eq!(proof.root_hex().unwrap(), tree.root_hex());
+        }
+    }
+
+    // order matters
+    let payouts = event_payouts(3);
+    let tree = PayoutMerkleTree::new(payouts.clone()).unwrap();
+    let reversed = PayoutMerkleTree::new(payouts.into_iter().rev().collect()).unwrap();
+    assert_ne!(tree.root_hex(), reversed.root_hex());
+
+    // single leaf proof has no siblings
+    let tree = PayoutMerkleTree::new(event_payouts(1)).unwrap();
+    let proof = tree.proof(&tree.event_payouts()[0].event_hash_hex).unwrap();
+    assert!(proof.sibling_hashes_hex.is_empty());
+
+    // compact for large batches
+    let tree = PayoutMerkleTree::new(event_payouts(5000)).unwrap();
+    let proof = tree.proof(&tree.event_payouts()[0].event_hash_hex).unwrap();
+    assert_eq!(proof.sibling_hashes_hex.len(), 13);
+    assert!(proof.verify(&tree.root_hex(), 5000).is_ok());
+    // the last leaves are promoted past levels without a sibling
+    let proof = tree
+        .proof(&tree.event_payouts()[4999].event_hash_hex)
+        .unwrap();
+    assert!(proof.sibling_hashes_hex.len() < 13);
+    assert!(proof.verify(&tree.root_hex(), 5000).is_ok());
+    assert!(tree.proof(&EventHashHex("00".repeat(32))).is_none());
+}
+
+#[test]
+fn merkle_proof_invalid_1() {
+    let tree = PayoutMerkleTree::new(event_payouts(5)).unwrap();
+    let root_hex = tree.root_hex();
+    let proof = tree.proof(&tree.event_payouts()[2].event_hash_hex).unwrap();
+
+    let mut other_payout = proof.clone();
+    other_payout.event_payout.units_per_outcome = vec![0, 10];
+    assert!(matches!(
+        other_payout.verify(&root_hex, 5),
+        Err(Error::Validation(_))
+    ));
+
+    let mut other_index = proof.clone();
+    other_index.leaf_index = 3;
+    assert!(other_index.verify(&root_hex, 5).is_err());
+
+    let mut out_of_range = proof.clone();
+    out_of_range.leaf_index = 5;
+    assert!(out_of_range.verify(&root_hex, 5).is_err());
+
+    let mut missing_sibling = proof.clone();
+    missing_sibling.sibling_hashes_hex.pop();
+    assert!(missing_sibling.verify(&root_hex, 5).is_err());
+
+    let mut extra_sibling = proof.clone();
+    extra_sibling.sibling_hashes_hex.push("00".repeat(32));
+    assert!(extra_sibling.verify(&root_hex, 5).is_err());
+
+    let mut invalid_hex = proof.clone();
+    invalid_hex.sibling_hashes_hex[0] = "zz".repeat(32);
+    assert!(invalid_hex.verify(&root_hex, 5).is_err());
+
+    // a batch of another size
+    assert!(proof.verify(&root_hex, 6).is_err());
+
+    // empty, duplicate or invalid leaves
+    assert!(PayoutMerkleTree::new(vec![]).is_err());
+    let duplicate = tree.event_payouts()[0].clone();
+    assert!(PayoutMerkleTree::new(vec![duplicate.clone(), duplicate.clone()]).is_err());
+    assert!(PayoutMerkleTree::new(vec![EventPayout {
+        event_hash_hex: EventHashHex("ab".to_string()),
+        units_per_outcome: vec![10, 0],
+    }])
+    .is_err());
+    // the same event hash in upper case is not a second event
+    let mut uppercase = duplicate.clone();
+    uppercase.event_hash_hex = EventHashHex(uppercase.event_hash_hex.0.to_ascii_uppercase());
+    assert!(matches!(
+        PayoutMerkleTree::new(vec![duplicate, uppercase.clone()]),
+        Err(Error::Validation(message)) if message == "event hash hex is not 64 lowercase hex characters"
+    ));
+    assert!(PayoutMerkleTree::new(vec![uppercase]).is_err());
+}
+
+#[test]
+fn nostr_event_payout_batch_attestation_1() {
+    let oracle = ::nostr::Keys::generate();
+    let tree = PayoutMerkleTree::new(event_payouts(100)).unwrap();
+    let commitment = BatchCommitment::from(&tree);
+
+    let nostr_event =
+        EventPayoutBatchAttestation::create_nostr_signed_event(&commitment, &oracle).unwrap();
+    let (pk, interpreted) =
+        EventPayoutBatchAttestation::interpret_nostr_event(&nostr_event).unwrap();
+    assert_eq!(pk, NostrPublicKeyHex(oracle.public_key.to_hex()));
+    assert_eq!(interpreted, commitment);
+
+    let event_payout = tree.event_payouts()[42].clone();
+    let proof = tree.proof(&event_payout.event_hash_hex).unwrap();
+    assert_eq!(
+        EventPayoutBatchAttestation::verify_event_payout(&nostr_event, &proof).unwrap(),
+        (pk, event_payout)
+    );
+
+    // proof of another batch
+    let other_tree = PayoutMerkleTree::new(event_payouts(100)).unwrap();
+    let other_proof = other_tree
+        .proof(&other_tree.event_payouts()[42].event_hash_hex)
+        .unwrap();
+    assert!(EventPayoutBatchAttestation::verify_event_payout(&nostr_event, &other_proof).is_err());
+
+    assert!(matches!(
+        interpret_any(&nostr_event).unwrap(),
+        PredictionMarketMessage::BatchAttestation { commitment: c, .. } if c == commitment
+    ));
+
+    // empty batch
+    let empty = EventPayoutBatchAttestation::create_nostr_signed_event(
+        &BatchCommitment {
+            merkle_root_hex: tree.root_hex(),
+            leaf_count: 0,
+        },
+        &oracle,
+    )
+    .unwrap();
+    assert!(EventPayoutBatchAttestation::interpret_nostr_event(&empty).is_err());
+
+    // merkle root must be 64 lowercase hex characters
+    for merkle_root_hex in [
+        tree.root_hex().to_ascii_uppercase(),
+        tree.root_hex()[..62].to_string(),
+        "+".repeat(64),
+    ] {
+        let invalid = EventPayoutBatchAttestation::create_nostr_signed_event(
+            &BatchCommitment {
+                merkle_root_hex,
+                leaf_count: 100,
+            },
+            &oracle,
+        )
+        .unwrap();
+        assert!(matches!(
+            EventPayoutBatchAttestation::interpret_nostr_event(&invalid),
+            Err(Error::Validation(message)) if message == "merkle root hex is not 64 lowercase hex characters"
+        ));
+    }
+}
@@ -1,3 +1,25 @@
 mod event;
 mod information;
 mod nostr;
+mod conformance;
+mod pledge;
+mod correction;
+mod oracle_profile;
+mod aggregation;
+mod dispute;
+#[cfg(feature = "client")]
+mod client;
+mod ingest;
+#[cfg(feature = "relay")]
+mod relay;
+#[cfg(feature = "remote-signer")]
+mod remote_signer;
+mod write_policy;
+mod dispatch;
+mod bulk;
+mod signer;
+mod private_event;
+mod attestation_timing;
+mod merkle;
+mod fraud_proof;
+mod util;
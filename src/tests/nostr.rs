@@ -50,7 +50,7 @@ fn nostr_future_event_payout_attestation_pledge_1() {
 
     let nostr_unsigned_event_json =
         nostr_event_types::FutureEventPayoutAttestationPledge::create_nostr_unsigned_event_json(
-            &event.hash_hex().unwrap(),
+            &AttestationPledge::from(event.hash_hex().unwrap()),
             &keys.public_key.to_hex(),
         )
         .unwrap();
@@ -58,14 +58,60 @@ fn nostr_future_event_payout_attestation_pledge_1() {
         ::nostr::UnsignedEvent::from_json(nostr_unsigned_event_json).unwrap();
     let nostr_event = nostr_unsigned_event.sign(&keys).unwrap();
     let nostr_event_json = nostr_event.try_as_json().unwrap();
-    let (pk, h) =
+    let (pk, p) =
         nostr_event_types::FutureEventPayoutAttestationPledge::interpret_nostr_event_json(&nostr_event_json)
             .unwrap();
 
     assert_eq!(keys.public_key.to_hex(), pk.0);
-    assert_eq!(event.hash_hex().unwrap(), h);
+    assert_eq!(event.hash_hex().unwrap(), p.event_hash_hex);
+    assert!(p.terms.is_none());
 
-    println!("nostr public key hex: {pk}\nevent hash hex: {h}");
+    println!("nostr public key hex: {pk}\nevent hash hex: {}", p.event_hash_hex);
+}
+
+#[test]
+fn nostr_future_event_payout_attestation_pledge_2() {
+    let event = Event::new_with_random_nonce(
+        3,
+        1,
+        Information::V1(V1 {
+            title: "my event".into(),
+            description: "a description of my event".into(),
+            outcome_titles: vec!["outcome 1".into(), "outcome 2".into(), "outcome 3".into()],
+            expected_payout_unix_seconds: 1725388253,
+        }),
+    );
+    let pledge = AttestationPledge::new(
+        &event,
+        Some(AttestationPledgeTerms {
+            attestation_deadline_unix_seconds: 1725388253 + 60 * 60,
+            fee: Some(AttestationFee {
+                amount: 1000,
+                denomination: "sat".into(),
+            }),
+            resolution_methodology: Some("https://example.com/methodology".into()),
+            expiration_unix_seconds: Some(1725388253),
+        }),
+    )
+    .unwrap();
+
+    let keys = ::nostr::Keys::generate();
+
+    let nostr_event_json =
+        nostr_event_types::FutureEventPayoutAttestationPledge::create_nostr_signed_event_json(
+            &pledge,
+            &keys.secret_key().to_secret_hex(),
+        )
+        .unwrap();
+    let (pk, p) =
+        nostr_event_types::FutureEventPayoutAttestationPledge::interpret_nostr_event_json(&nostr_event_json)
+            .unwrap();
+
+    assert_eq!(keys.public_key.to_hex(), pk.0);
+    assert_eq!(pledge, p);
+    assert!(matches!(p.validate(&event), Ok(())));
+
+    println!("nostr event json: {nostr_event_json}");
 }
 
 #[test]
@@ -98,7 +144,468 @@ fn nostr_event_payout_attestation_1() {
         nostr_event_types::EventPayoutAttestation::interpret_nostr_event_json(&nostr_event_json).unwrap();
 
     assert_eq!(keys.public_key.to_hex(), pk.0);
-    assert_eq!(event_payout.event_hash_hex, e.event_hash_hex);
+    assert_eq!(event_payout, e);
 
     println!("nostr public key hex: {pk}\n\nevent payout: {e:?}");
 }
+
+#[test]
+fn nostr_filters_1() {
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let other_event = Event::new_with_random_nonce(2, 10, Information::None);
+    let event_payout = EventPayout::new(&event, vec![4, 6]).unwrap();
+
+    let oracle_keys = ::nostr::Keys::generate();
+    let other_keys = ::nostr::Keys::generate();
+    let oracles = vec![nostr_event_types::NostrPublicKeyHex(
+        oracle_keys.public_key.to_hex(),
+    )];
+
+    let sign = |json: String| ::nostr::Event::from_json(json).unwrap();
+    let attestation = sign(
+        nostr_event_types::EventPayoutAttestation::create_nostr_signed_event_json(
+            &event_payout,
+            &oracle_keys.secret_key().to_secret_hex(),
+        )
+        .unwrap(),
+    );
+    let other_attestation = sign(
+        nostr_event_types::EventPayoutAttestation::create_nostr_signed_event_json(
+            &event_payout,
+            &other_keys.secret_key().to_secret_hex(),
+        )
+        .unwrap(),
+    );
+    let pledge = sign(
+        nostr_event_types::FutureEventPayoutAttestationPledge::create_nostr_signed_event_json(
+            &AttestationPledge::from(event.hash_hex().unwrap()),
+            &oracle_keys.secret_key().to_secret_hex(),
+        )
+        .unwrap(),
+    );
+    let new_event = sign(
+        nostr_event_types::NewEvent::create_nostr_signed_event_json(
+            &event,
+            &other_keys.secret_key().to_secret_hex(),
+        )
+        .unwrap(),
+    );
+    let other_new_event = sign(
+        nostr_event_types::NewEvent::create_nostr_signed_event_json(
+            &other_event,
+            &other_keys.secret_key().to_secret_hex(),
+        )
+        .unwrap(),
+    );
+
+    let filter = nostr_event_types::EventPayoutAttestation::filter_event_hash_authors(
+        &event.hash_hex().unwrap(),
+        &oracles,
+    )
+    .unwrap();
+    assert!(filter.match_event(&attestation));
+    assert!(!filter.match_event(&other_attestation));
+    assert!(!filter.match_event(&pledge));
+
+    let filter = nostr_event_types::FutureEventPayoutAttestationPledge::filter_since(
+        pledge.created_at.as_u64(),
+    );
+    assert!(filter.match_event(&pledge));
+    let filter = nostr_event_types::FutureEventPayoutAttestationPledge::filter_since(
+        pledge.created_at.as_u64() + 1,
+    );
+    assert!(!filter.match_event(&pledge));
+
+    let filter = nostr_event_types::NewEvent::filter_authors(&oracles).unwrap();
+    assert!(!filter.match_event(&new_event));
+
+    let filter = nostr_event_types::filter_all_kinds_event_hash(&event.hash_hex().unwrap());
+    assert!(filter.match_event(&attestation));
+    assert!(filter.match_event(&other_attestation));
+    assert!(filter.match_event(&pledge));
+    assert!(filter.match_event(&new_event));
+    assert!(!filter.match_event(&other_new_event));
+
+    let res = nostr_event_types::NewEvent::filter_authors(&[nostr_event_types::NostrPublicKeyHex(
+        "not a key".into(),
+    )]);
+    assert!(matches!(res, Err(Error::NostrKey(_))));
+}
+
+#[test]
+fn nostr_event_hash_tag_1() {
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let other_event = Event::new_with_random_nonce(2, 10, Information::None);
+    let keys = ::nostr::Keys::generate();
+    let dedicated = nostr_event_types::InterpretOptions {
+        event_hash_tag_mode: nostr_event_types::EventHashTagMode::Dedicated,
+        ..Default::default()
+    };
+
+    let sign = |tags: Vec<::nostr::Tag>| {
+        ::nostr::EventBuilder::new(
+            nostr_event_types::NewEvent::KIND,
+            event.try_to_json_string().unwrap(),
+            tags,
+        )
+        .to_event(&keys)
+        .unwrap()
+    };
+
+    // created notes carry the label and the legacy hashtag
+    let nostr_event_json = nostr_event_types::NewEvent::create_nostr_signed_event_json(
+        &event,
+        &keys.secret_key().to_secret_hex(),
+    )
+    .unwrap();
+    let res = nostr_event_types::NewEvent::interpret_nostr_event_json_with_options(
+        &nostr_event_json,
+        &dedicated,
+    );
+    assert_eq!(res.unwrap(), event);
+
+    // a topic hashtag in front of the hash no longer breaks interpretation
+    let nostr_event = sign(vec![
+        ::nostr::Tag::hashtag("weather"),
+        ::nostr::Tag::hashtag(event.hash_hex().unwrap().0),
+    ]);
+    let res = nostr_event_types::NewEvent::interpret_nostr_event(&nostr_event);
+    assert_eq!(res.unwrap(), event);
+
+    // legacy hashtags are not read in dedicated mode
+    let res = nostr_event_types::NewEvent::interpret_nostr_event_with_options(
+        &nostr_event,
+        &dedicated,
+    );
+    assert!(matches!(res, Err(Error::Validation(_))));
+    println!("{res:?}");
+
+    // conflicting references are rejected
+    let mut tags = nostr_event_types::event_hash_tags(&event.hash_hex().unwrap());
+    tags.push(::nostr::Tag::hashtag(other_event.hash_hex().unwrap().0));
+    let nostr_event = sign(tags);
+    let res = nostr_event_types::NewEvent::interpret_nostr_event(&nostr_event);
+    assert!(matches!(res, Err(Error::Validation(_))));
+    println!("{res:?}");
+}
+
+#[test]
+fn nostr_new_event_reference_1() {
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let other_event = Event::new_with_random_nonce(2, 10, Information::None);
+    let creator_keys = ::nostr::Keys::generate();
+    let oracle_keys = ::nostr::Keys::generate();
+
+    let sign_new_event = |event: &Event| {
+        nostr_event_types::NewEvent::create_nostr_event_builder(event)
+            .unwrap()
+            .to_event(&creator_keys)
+            .unwrap()
+    };
+    let new_event_nostr_event = sign_new_event(&event);
+    let other_new_event_nostr_event = sign_new_event(&other_event);
+    let reference = nostr_event_types::NewEventReference::from_nostr_event(
+        &new_event_nostr_event,
+        Some("wss://relay.example.com".into()),
+    );
+
+    // attestation
+    let param = nostr_event_types::ReferencedEventPayout {
+        event_payout: EventPayout::new(&event, vec![4, 6]).unwrap(),
+        new_event_reference: Some(reference.clone()),
+    };
+    let nostr_event_json =
+        nostr_event_types::ReferencedEventPayoutAttestation::create_nostr_signed_event_json(
+            &param,
+            &oracle_keys.secret_key().to_secret_hex(),
+        )
+        .unwrap();
+    let (_, p) = nostr_event_types::ReferencedEventPayoutAttestation::interpret_nostr_event_json(
+        &nostr_event_json,
+    )
+    .unwrap();
+    assert_eq!(param, p);
+    // same kind, the reference is dropped
+    let (_, event_payout) =
+        nostr_event_types::EventPayoutAttestation::interpret_nostr_event_json(&nostr_event_json)
+            .unwrap();
+    assert_eq!(param.event_payout, event_payout);
+    let nostr_event = ::nostr::Event::from_json(&nostr_event_json).unwrap();
+    assert!(nostr_event.public_keys().any(|pk| pk == &creator_keys.public_key));
+    println!("nostr event json: {nostr_event_json}");
+
+    let r = p.new_event_reference.unwrap();
+    let event_hash_hex = event.hash_hex().unwrap();
+    assert!(matches!(r.verify(&new_event_nostr_event, &event_hash_hex), Ok(())));
+    let res = r.verify(&other_new_event_nostr_event, &event_hash_hex);
+    assert!(matches!(res, Err(Error::Validation(_))));
+    // a referenced note containing another event fails
+    let mut unknown_creator = r.clone();
+    unknown_creator.creator = Option::None;
+    unknown_creator.nostr_event_id =
+        nostr_event_types::NostrEventIdHex(other_new_event_nostr_event.id.to_hex());
+    let res = unknown_creator.verify(&other_new_event_nostr_event, &event_hash_hex);
+    assert!(matches!(res, Err(Error::Validation(_))));
+    // wrong creator
+    let mut wrong_creator = r;
+    wrong_creator.creator = Some(nostr_event_types::NostrPublicKeyHex(
+        oracle_keys.public_key.to_hex(),
+    ));
+    let res = wrong_creator.verify(&new_event_nostr_event, &event_hash_hex);
+    assert!(matches!(res, Err(Error::Validation(_))));
+
+    // pledge without creator or relay hint
+    let mut pledge = AttestationPledge::from(event.hash_hex().unwrap());
+    pledge.new_event_reference = Some(nostr_event_types::NewEventReference {
+        nostr_event_id: nostr_event_types::NostrEventIdHex(new_event_nostr_event.id.to_hex()),
+        creator: Option::None,
+        relay_hint: Option::None,
+    });
+    let nostr_event_json =
+        nostr_event_types::FutureEventPayoutAttestationPledge::create_nostr_signed_event_json(
+            &pledge,
+            &oracle_keys.secret_key().to_secret_hex(),
+        )
+        .unwrap();
+    let (_, p) = nostr_event_types::FutureEventPayoutAttestationPledge::interpret_nostr_event_json(
+        &nostr_event_json,
+    )
+    .unwrap();
+    assert_eq!(pledge, p);
+    let nostr_event = ::nostr::Event::from_json(&nostr_event_json).unwrap();
+    assert_eq!(nostr_event.public_keys().count(), 0);
+
+    // conflicting references are rejected
+    let mut tags = nostr_event.tags.to_vec();
+    tags.push(::nostr::Tag::parse(&["e", &other_new_event_nostr_event.id.to_hex(), "", nostr_event_types::NEW_EVENT_MARKER]).unwrap());
+    let nostr_event = ::nostr::EventBuilder::new(nostr_event.kind, "", tags)
+        .to_event(&oracle_keys)
+        .unwrap();
+    let res = nostr_event_types::FutureEventPayoutAttestationPledge::interpret_nostr_event(&nostr_event);
+    assert!(matches!(res, Err(Error::Validation(_))));
+
+    // a p tag naming another creator is rejected
+    let nostr_event =
+        nostr_event_types::ReferencedEventPayoutAttestation::create_nostr_event_builder(&param)
+            .unwrap()
+            .add_tags([::nostr::Tag::public_key(oracle_keys.public_key)])
+            .to_event(&oracle_keys)
+            .unwrap();
+    let res =
+        nostr_event_types::ReferencedEventPayoutAttestation::interpret_nostr_event(&nostr_event);
+    assert!(matches!(res, Err(Error::Validation(_))));
+
+    // without creator in the e tag the p tag names the creator
+    let nostr_event =
+        nostr_event_types::FutureEventPayoutAttestationPledge::create_nostr_event_builder(&pledge)
+            .unwrap()
+            .add_tags([::nostr::Tag::public_key(creator_keys.public_key)])
+            .to_event(&oracle_keys)
+            .unwrap();
+    let (_, p) =
+        nostr_event_types::FutureEventPayoutAttestationPledge::interpret_nostr_event(&nostr_event)
+            .unwrap();
+    assert_eq!(
+        p.new_event_reference.unwrap().creator,
+        Some(nostr_event_types::NostrPublicKeyHex(
+            creator_keys.public_key.to_hex()
+        ))
+    );
+}
+
+#[test]
+fn nostr_event_pointer_1() {
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let keys = ::nostr::Keys::generate();
+    let pointer = nostr_event_types::EventPointer {
+        event_hash_hex: event.hash_hex().unwrap(),
+        relays: vec!["wss://relay.example.com".into()],
+        creator: Some(nostr_event_types::NostrPublicKeyHex(keys.public_key.to_hex())),
+        kind: Some(nostr_event_types::NewEvent::KIND_U16),
+    };
+
+    let s = pointer.to_bech32().unwrap();
+    assert!(s.starts_with("pmevent1"));
+    assert_eq!(s.parse::<nostr_event_types::EventPointer>().unwrap(), pointer);
+    println!("{s}");
+
+    let minimal = nostr_event_types::EventPointer::from(event.hash_hex().unwrap());
+    let s = minimal.to_bech32().unwrap();
+    assert_eq!(s.parse::<nostr_event_types::EventPointer>().unwrap(), minimal);
+
+    let mut long_relay = pointer.clone();
+    long_relay.relays = vec!["a".repeat(256)];
+    assert!(matches!(long_relay.to_bech32(), Err(Error::Validation(_))));
+
+    let mut bad_creator = pointer;
+    bad_creator.creator = Some(nostr_event_types::NostrPublicKeyHex("ab".into()));
+    assert!(matches!(bad_creator.to_bech32(), Err(Error::Validation(_))));
+
+    let res = "not bech32".parse::<nostr_event_types::EventPointer>();
+    assert!(matches!(res, Err(Error::Bech32Decode(_))));
+
+    // hashes and nostr event ids also parse from bech32
+    assert_eq!(
+        s.parse::<crate::EventHashHex>().unwrap(),
+        event.hash_hex().unwrap()
+    );
+    let nostr_event = nostr_event_types::NewEvent::create_nostr_event_builder(&event)
+        .unwrap()
+        .to_event(&keys)
+        .unwrap();
+    let note = ::nostr::ToBech32::to_bech32(&nostr_event.id).unwrap();
+    let nevent = ::nostr::ToBech32::to_bech32(&::nostr::nips::nip19::Nip19Event::new(
+        nostr_event.id,
+        ["wss://relay.example.com"],
+    ))
+    .unwrap();
+    for s in [nostr_event.id.to_hex(), note, nevent] {
+        assert_eq!(
+            s.parse::<nostr_event_types::NostrEventIdHex>().unwrap().0,
+            nostr_event.id.to_hex()
+        );
+    }
+    assert!("pmevent1".parse::<crate::EventHashHex>().is_err());
+    assert!(s.parse::<nostr_event_types::NostrEventIdHex>().is_err());
+}
+
+#[test]
+fn nostr_interpreted_1() {
+    use nostr_event_types::{EventPayoutAttestation, NostrPublicKeyHex};
+
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let event_payout = EventPayout::new(&event, vec![3, 7]).unwrap();
+    let keys = ::nostr::Keys::generate();
+    let nostr_event = EventPayoutAttestation::create_nostr_event_builder(&event_payout)
+        .unwrap()
+        .custom_created_at(::nostr::Timestamp::from(1725388253))
+        .to_event(&keys)
+        .unwrap();
+
+    let interpreted = EventPayoutAttestation::interpret_nostr_event_enveloped(&nostr_event)
+        .unwrap()
+        .with_relay_url("wss://relay.example.com");
+    assert_eq!(interpreted.metadata.nostr_event_id.0, nostr_event.id.to_hex());
+    assert_eq!(interpreted.metadata.author.0, keys.public_key.to_hex());
+    assert_eq!(interpreted.metadata.created_at_unix_seconds, 1725388253);
+    assert!(interpreted
+        .metadata
+        .tags
+        .contains(&vec!["t".to_string(), event.hash_hex().unwrap().0]));
+    assert_eq!(
+        interpreted.metadata.relay_url.as_deref(),
+        Some("wss://relay.example.com")
+    );
+
+    // the tuple shape of interpret_nostr_event is kept
+    let (pk, interpreted_event_payout) = interpreted.clone().into_inner();
+    assert_eq!(
+        (pk.clone(), interpreted_event_payout),
+        EventPayoutAttestation::interpret_nostr_event(&nostr_event).unwrap()
+    );
+    let mapped = interpreted.map(|(_, event_payout)| event_payout);
+    assert_eq!(mapped.value, event_payout);
+    assert_eq!(mapped.metadata.author, NostrPublicKeyHex(pk.0));
+}
+
+#[test]
+fn nostr_pow_expiration_1() {
+    use nostr_event_types::{
+        CreateOptions, FutureEventPayoutAttestationPledge, InterpretOptions, NewEvent,
+    };
+
+    let keys = ::nostr::Keys::generate();
+    let event = Event::new_with_random_nonce(
+        2,
+        10,
+        Information::V1(V1 {
+            title: "title".to_string(),
+            description: "description".to_string(),
+            outcome_titles: vec!["yes".to_string(), "no".to_string()],
+            expected_payout_unix_seconds: 1725388253,
+        }),
+    );
+
+    // new event mined to a difficulty, expiring once trading closes before the expected payout
+    let options = CreateOptions {
+        pow_difficulty: 8,
+        ..CreateOptions::new_event_expiration(1725300000)
+    };
+    let nostr_event =
+        NewEvent::create_nostr_signed_event_with_options(&event, &options, &keys).unwrap();
+    assert!(nostr_event.check_pow(8));
+    assert_eq!(
+        nostr_event.expiration(),
+        Some(&::nostr::Timestamp::from(1725300000))
+    );
+    assert_eq!(
+        NewEvent::interpret_nostr_event(&nostr_event).unwrap(),
+        event
+    );
+
+    let min_pow = |min_pow_difficulty| InterpretOptions {
+        min_pow_difficulty,
+        ..Default::default()
+    };
+    assert!(NewEvent::interpret_nostr_event_with_options(&nostr_event, &min_pow(8)).is_ok());
+    let unmined = NewEvent::create_nostr_signed_event(&event, &keys).unwrap();
+    assert!(unmined.expiration().is_none());
+    // an unmined id has 8 leading zero bits with probability 1/256, retry once
+    let unmined = if unmined.check_pow(8) {
+        NewEvent::create_nostr_signed_event(&event, &keys).unwrap()
+    } else {
+        unmined
+    };
+    assert!(matches!(
+        NewEvent::interpret_nostr_event_with_options(&unmined, &min_pow(8)),
+        Err(Error::Validation(message)) if message == "nostr event proof of work is below min difficulty"
+    ));
+
+    let reject_expired_at = |now| InterpretOptions {
+        reject_expired_at_unix_seconds: Some(now),
+        ..Default::default()
+    };
+    assert!(NewEvent::interpret_nostr_event_with_options(
+        &nostr_event,
+        &reject_expired_at(1725300000)
+    )
+    .is_ok());
+    assert!(matches!(
+        NewEvent::interpret_nostr_event_with_options(&nostr_event, &reject_expired_at(1725300001)),
+        Err(Error::Validation(message)) if message == "nostr event is expired"
+    ));
+    // nostr events without expiration never expire
+    assert!(
+        NewEvent::interpret_nostr_event_with_options(&unmined, &reject_expired_at(u64::MAX))
+            .is_ok()
+    );
+
+    // pledges expire together with their terms
+    let pledge = AttestationPledge::new(
+        &event,
+        Some(AttestationPledgeTerms {
+            attestation_deadline_unix_seconds: 1725400000,
+            fee: Option::None,
+            resolution_methodology: Option::None,
+            expiration_unix_seconds: Some(1725390000),
+        }),
+    )
+    .unwrap();
+    let nostr_event = FutureEventPayoutAttestationPledge::create_nostr_signed_event_with_options(
+        &pledge,
+        &CreateOptions::pledge_expiration(&pledge),
+        &keys,
+    )
+    .unwrap();
+    assert!(matches!(
+        FutureEventPayoutAttestationPledge::interpret_nostr_event_with_options(
+            &nostr_event,
+            &reject_expired_at(1725390001)
+        ),
+        Err(Error::Validation(_))
+    ));
+    assert_eq!(
+        CreateOptions::pledge_expiration(&AttestationPledge::new(&event, Option::None).unwrap()),
+        CreateOptions::default()
+    );
+}
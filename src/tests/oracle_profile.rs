@@ -0,0 +1,188 @@
+use crate::{
+    information::{Information, V1},
+    nostr_event_types::{
+        latest_oracle_profiles, NostrEventUtils, NostrPublicKeyHex, OracleProfileAnnouncement,
+        INFORMATION_VARIANT_LABEL_NAMESPACE,
+    },
+    AttestationFee, Error, OracleFeeScheduleEntry, OracleProfile,
+};
+
+fn profile() -> OracleProfile {
+    OracleProfile {
+        name: "weather oracle".into(),
+        information_variant_ids: vec![
+            Information::None.information_variant_id().into(),
+            V1::ID.into(),
+        ],
+        categories: vec!["weather".into(), "sports".into()],
+        fee_schedule: vec![
+            OracleFeeScheduleEntry {
+                category: None,
+                fee: AttestationFee {
+                    amount: 1000,
+                    denomination: "sat".into(),
+                },
+            },
+            OracleFeeScheduleEntry {
+                category: Some("sports".into()),
+                fee: AttestationFee {
+                    amount: 2000,
+                    denomination: "sat".into(),
+                },
+            },
+        ],
+        typical_resolution_latency_seconds: Some(60 * 60),
+        policy: Some("outcomes are resolved from the national weather service".into()),
+        contact: Some("mailto:oracle@example.com".into()),
+        relays: vec!["wss://relay.example.com".into()],
+    }
+}
+
+#[test]
+fn oracle_profile_validate_1() {
+    let profile = profile();
+    assert!(matches!(profile.validate(), Ok(())));
+    assert!(profile.supports_information_variant(V1::ID));
+    assert!(!profile.supports_information_variant("v99"));
+
+    let mut p = profile.clone();
+    p.name = "".into();
+    assert!(matches!(p.validate(), Err(Error::Validation(_))));
+
+    let mut p = profile.clone();
+    p.information_variant_ids = vec![];
+    assert!(matches!(p.validate(), Err(Error::Validation(_))));
+
+    // unknown variants are accepted
+    let mut p = profile.clone();
+    p.information_variant_ids.push("v99".into());
+    assert!(matches!(p.validate(), Ok(())));
+
+    let mut p = profile.clone();
+    p.categories.push("Weather".into());
+    assert!(matches!(p.validate(), Err(Error::Validation(_))));
+
+    let mut p = profile.clone();
+    p.fee_schedule[1].category = Some("politics".into());
+    assert!(matches!(p.validate(), Err(Error::Validation(_))));
+
+    let mut p = profile.clone();
+    p.fee_schedule[0].fee.denomination = "".into();
+    assert!(matches!(p.validate(), Err(Error::Validation(_))));
+
+    let mut p = profile;
+    p.policy = Some("a".repeat(1024 * 10 + 1));
+    assert!(matches!(p.validate(), Err(Error::Validation(_))));
+}
+
+#[test]
+fn nostr_oracle_profile_announcement_1() {
+    let keys = ::nostr::Keys::generate();
+    let profile = profile();
+
+    assert!(OracleProfileAnnouncement::KIND.is_replaceable());
+
+    let nostr_event_json = OracleProfileAnnouncement::create_nostr_signed_event_json(
+        &profile,
+        &keys.secret_key().to_secret_hex(),
+    )
+    .unwrap();
+    let (pk, p) = OracleProfileAnnouncement::interpret_nostr_event_json(&nostr_event_json).unwrap();
+    assert_eq!(keys.public_key.to_hex(), pk.0);
+    assert_eq!(profile, p);
+
+    let nostr_event = <::nostr::Event as ::nostr::JsonUtil>::from_json(&nostr_event_json).unwrap();
+    assert!(OracleProfileAnnouncement::filter_category("weather").match_event(&nostr_event));
+    assert!(!OracleProfileAnnouncement::filter_category("politics").match_event(&nostr_event));
+    assert!(OracleProfileAnnouncement::filter_information_variant(
+        Information::None.information_variant_id()
+    )
+    .match_event(&nostr_event));
+    assert!(
+        OracleProfileAnnouncement::filter_information_variant(V1::ID).match_event(&nostr_event)
+    );
+    assert!(
+        !OracleProfileAnnouncement::filter_information_variant("v99").match_event(&nostr_event)
+    );
+    // one label per information variant
+    let labels = nostr_event
+        .tags
+        .iter()
+        .filter(|t| matches!(t.as_standardized(), Some(::nostr::TagStandard::Label(_))))
+        .count();
+    assert_eq!(labels, 2);
+
+    // labels must list exactly the information variants of the content
+    let label = |id: &str| {
+        ::nostr::Tag::from(::nostr::TagStandard::Label(vec![
+            id.into(),
+            INFORMATION_VARIANT_LABEL_NAMESPACE.into(),
+        ]))
+    };
+    let missing_label = ::nostr::EventBuilder::new(
+        OracleProfileAnnouncement::KIND,
+        profile.try_to_json_string().unwrap(),
+        [label(V1::ID)],
+    )
+    .to_event(&keys)
+    .unwrap();
+    assert!(matches!(
+        OracleProfileAnnouncement::interpret_nostr_event(&missing_label),
+        Err(Error::Validation(_))
+    ));
+    let combined_label = ::nostr::EventBuilder::new(
+        OracleProfileAnnouncement::KIND,
+        profile.try_to_json_string().unwrap(),
+        [::nostr::Tag::from(::nostr::TagStandard::Label(vec![
+            Information::None.information_variant_id().into(),
+            V1::ID.into(),
+            INFORMATION_VARIANT_LABEL_NAMESPACE.into(),
+        ]))],
+    )
+    .to_event(&keys)
+    .unwrap();
+    assert!(OracleProfileAnnouncement::interpret_nostr_event(&combined_label).is_err());
+
+    println!("nostr event json: {nostr_event_json}");
+}
+
+#[test]
+fn nostr_latest_oracle_profiles_1() {
+    let keys = ::nostr::Keys::generate();
+    let other_keys = ::nostr::Keys::generate();
+    let sign = |profile: &OracleProfile, keys: &::nostr::Keys, created_at: u64| {
+        OracleProfileAnnouncement::create_nostr_event_builder(profile)
+            .unwrap()
+            .custom_created_at(::nostr::Timestamp::from(created_at))
+            .to_event(keys)
+            .unwrap()
+    };
+
+    let old = profile();
+    let mut new = profile();
+    new.typical_resolution_latency_seconds = Some(60);
+    let mut other = profile();
+    other.name = "other oracle".into();
+
+    let nostr_events = vec![
+        sign(&new, &keys, 200),
+        sign(&other, &other_keys, 100),
+        sign(&old, &keys, 100),
+    ];
+    let latest = latest_oracle_profiles(&nostr_events);
+    assert_eq!(latest.len(), 2);
+    // discoverable by any of the information variants
+    let v1_filter = OracleProfileAnnouncement::filter_information_variant(V1::ID);
+    assert!(nostr_events.iter().all(|e| v1_filter.match_event(e)));
+    assert_eq!(latest[&NostrPublicKeyHex(keys.public_key.to_hex())], new);
+    assert_eq!(latest[&NostrPublicKeyHex(other_keys.public_key.to_hex())], other);
+
+    // same created_at: lowest id wins
+    let a = sign(&old, &keys, 300);
+    let b = sign(&new, &keys, 300);
+    let expected = if a.id < b.id { &old } else { &new };
+    let latest = latest_oracle_profiles(&[a.clone(), b.clone()]);
+    assert_eq!(&latest[&NostrPublicKeyHex(keys.public_key.to_hex())], expected);
+    let latest = latest_oracle_profiles(&[b, a]);
+    assert_eq!(&latest[&NostrPublicKeyHex(keys.public_key.to_hex())], expected);
+}
@@ -0,0 +1,122 @@
+use crate::{
+    information::{Information, V1},
+    *,
+};
+
+fn event() -> Event {
+    Event::new_with_random_nonce(
+        2,
+        10,
+        Information::V1(V1 {
+            title: "my event".into(),
+            description: "a description of my event".into(),
+            outcome_titles: vec!["outcome 1".into(), "outcome 2".into()],
+            expected_payout_unix_seconds: 1725388253,
+        }),
+    )
+}
+
+fn terms() -> AttestationPledgeTerms {
+    AttestationPledgeTerms {
+        attestation_deadline_unix_seconds: 1725388253,
+        fee: Some(AttestationFee {
+            amount: 1,
+            denomination: "sat".into(),
+        }),
+        resolution_methodology: Some("official results".into()),
+        expiration_unix_seconds: None,
+    }
+}
+
+#[test]
+fn pledge_1() {
+    let event = event();
+
+    let pledge = AttestationPledge::new(&event, None).unwrap();
+    let res = pledge.validate(&event);
+    assert!(matches!(res, Ok(())));
+
+    let pledge = AttestationPledge::new(&event, Some(terms())).unwrap();
+    let res = pledge.validate(&event);
+    assert!(matches!(res, Ok(())));
+
+    let json = pledge.terms.as_ref().unwrap().try_to_json_string().unwrap();
+    let terms_from_json = AttestationPledgeTerms::try_from_json_str(&json).unwrap();
+    assert_eq!(pledge.terms, Some(terms_from_json));
+
+    println!("pledge terms json: {json}");
+}
+
+#[test]
+fn pledge_information_none() {
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+
+    let mut terms = terms();
+    terms.attestation_deadline_unix_seconds = 0;
+    let pledge = AttestationPledge::new(&event, Some(terms)).unwrap();
+    let res = pledge.validate(&event);
+    assert!(matches!(res, Ok(())));
+}
+
+#[test]
+fn pledge_wrong_event() {
+    let pledge = AttestationPledge::new(&event(), Some(terms())).unwrap();
+
+    let res = pledge.validate(&event());
+    assert!(matches!(res, Err(Error::Validation(_))));
+    println!("{res:?}");
+}
+
+#[test]
+fn pledge_deadline_before_expected_payout() {
+    let event = event();
+
+    let mut terms = terms();
+    terms.attestation_deadline_unix_seconds -= 1;
+    let pledge = AttestationPledge::new(&event, Some(terms)).unwrap();
+    let res = pledge.validate(&event);
+    assert!(matches!(res, Err(Error::Validation(_))));
+    println!("{res:?}");
+}
+
+#[test]
+fn pledge_expiration_after_deadline() {
+    let event = event();
+
+    let mut terms = terms();
+    terms.expiration_unix_seconds = Some(terms.attestation_deadline_unix_seconds + 1);
+    let pledge = AttestationPledge::new(&event, Some(terms)).unwrap();
+    let res = pledge.validate(&event);
+    assert!(matches!(res, Err(Error::Validation(_))));
+    println!("{res:?}");
+}
+
+#[test]
+fn pledge_fee_denomination_invalid() {
+    let event = event();
+
+    let mut terms = terms();
+    terms.fee.as_mut().unwrap().denomination = "".into();
+    let pledge = AttestationPledge::new(&event, Some(terms.clone())).unwrap();
+    let res = pledge.validate(&event);
+    assert!(matches!(res, Err(Error::Validation(_))));
+    println!("{res:?}");
+
+    terms.fee.as_mut().unwrap().denomination = "x".repeat(33);
+    let pledge = AttestationPledge::new(&event, Some(terms)).unwrap();
+    let res = pledge.validate(&event);
+    assert!(matches!(res, Err(Error::Validation(_))));
+    println!("{res:?}");
+}
+
+#[test]
+fn pledge_resolution_methodology_too_long() {
+    let event = event();
+
+    let mut terms = terms();
+    terms.resolution_methodology = Some("x".repeat(1024 + 1));
+    let pledge = AttestationPledge::new(&event, Some(terms)).unwrap();
+    let res = pledge.validate(&event);
+    assert!(matches!(res, Err(Error::Validation(_))));
+    println!("{res:?}");
+}
@@ -0,0 +1,171 @@
+use crate::{
+    information::Information,
+    nostr_event_types::{
+        interpret_any, NewEvent, NewEventReference, NostrEventUtils, NostrPublicKeyHex,
+        PredictionMarketMessage, Private, PrivateEventPayoutAttestation, PrivateNewEvent,
+        PrivateNostrEventUtils, ReferencedEventPayout, ReferencedEventPayoutAttestation,
+    },
+    signer::ZeroizingKeys,
+    Error, Event, EventPayout,
+};
+
+fn hex(keys: &::nostr::Keys) -> NostrPublicKeyHex {
+    NostrPublicKeyHex(keys.public_key.to_hex())
+}
+
+#[test]
+fn private_new_event_1() {
+    let creator = ::nostr::Keys::generate();
+    let counterparty = ::nostr::Keys::generate();
+    let outsider = ::nostr::Keys::generate();
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+
+    let nostr_event = PrivateNewEvent::create_nostr_signed_event(
+        &Private {
+            value: event.clone(),
+            recipients: vec![hex(&creator), hex(&counterparty), hex(&counterparty)],
+        },
+        &creator,
+    )
+    .unwrap();
+    assert!(!nostr_event
+        .content
+        .contains(&event.try_to_json_string().unwrap()));
+
+    let envelope = PrivateNewEvent::interpret_nostr_event(&nostr_event).unwrap();
+    assert_eq!(envelope.author, hex(&creator));
+    assert_eq!(envelope.event_hash_hex, event.hash_hex().unwrap());
+    let mut recipients = vec![hex(&creator), hex(&counterparty)];
+    recipients.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(envelope.recipients, recipients);
+    // discoverable by event hash like public kinds
+    assert!(PrivateNewEvent::filter_event_hash(&envelope.event_hash_hex).match_event(&nostr_event));
+
+    assert_eq!(
+        PrivateNewEvent::decrypt_nostr_event(&nostr_event, &creator).unwrap(),
+        event
+    );
+    let counterparty_keys =
+        ZeroizingKeys::parse(&counterparty.secret_key().to_secret_hex()).unwrap();
+    assert_eq!(
+        PrivateNewEvent::decrypt_nostr_event(&nostr_event, &counterparty_keys).unwrap(),
+        event
+    );
+    assert!(matches!(
+        PrivateNewEvent::decrypt_nostr_event(&nostr_event, &outsider),
+        Err(Error::Validation(_))
+    ));
+
+    assert!(matches!(
+        interpret_any(&nostr_event).unwrap(),
+        PredictionMarketMessage::PrivateNewEvent { envelope: e, .. } if e == envelope
+    ));
+
+    assert!(PrivateNewEvent::create_nostr_event_builder(&Private {
+        value: event,
+        recipients: vec![],
+    })
+    .is_err());
+}
+
+#[test]
+fn private_attestation_1() {
+    let creator = ::nostr::Keys::generate();
+    let oracle = ::nostr::Keys::generate();
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let new_event = NewEvent::create_nostr_event_builder(&event)
+        .unwrap()
+        .to_event(&creator)
+        .unwrap();
+    let referenced_event_payout = ReferencedEventPayout {
+        event_payout: EventPayout::new(&event, vec![10, 0]).unwrap(),
+        new_event_reference: Some(NewEventReference::from_nostr_event(
+            &new_event,
+            Option::None,
+        )),
+    };
+    let private = Private {
+        value: referenced_event_payout.clone(),
+        recipients: vec![hex(&creator)],
+    };
+
+    let nostr_event =
+        PrivateEventPayoutAttestation::create_nostr_signed_event(&private, &oracle).unwrap();
+    // the same payout encrypts to a different commitment every time
+    let other =
+        PrivateEventPayoutAttestation::create_nostr_signed_event(&private, &oracle).unwrap();
+    assert_ne!(
+        PrivateEventPayoutAttestation::interpret_nostr_event(&nostr_event)
+            .unwrap()
+            .commitment,
+        PrivateEventPayoutAttestation::interpret_nostr_event(&other)
+            .unwrap()
+            .commitment
+    );
+
+    // decrypts to the result of the public kind
+    let decrypted =
+        PrivateEventPayoutAttestation::decrypt_nostr_event(&nostr_event, &creator).unwrap();
+    let public = ReferencedEventPayoutAttestation::create_nostr_signed_event(
+        &referenced_event_payout,
+        &oracle,
+    )
+    .unwrap();
+    assert_eq!(
+        decrypted,
+        ReferencedEventPayoutAttestation::interpret_nostr_event(&public).unwrap()
+    );
+    assert_eq!(decrypted.0, hex(&oracle));
+
+    // oracle is not a recipient
+    assert!(PrivateEventPayoutAttestation::decrypt_nostr_event(&nostr_event, &oracle).is_err());
+
+    // a commitment of other content fails after decrypting
+    let forged_tags: Vec<::nostr::Tag> = nostr_event
+        .tags
+        .iter()
+        .map(|tag| match tag.as_slice() {
+            [kind, _] if kind == "commitment" => ::nostr::Tag::custom(
+                ::nostr::TagKind::Custom("commitment".into()),
+                ["00".repeat(32)],
+            ),
+            _ => tag.clone(),
+        })
+        .collect();
+    let forged = ::nostr::EventBuilder::new(
+        PrivateEventPayoutAttestation::KIND,
+        nostr_event.content.clone(),
+        forged_tags,
+    )
+    .to_event(&oracle)
+    .unwrap();
+    assert!(matches!(
+        PrivateEventPayoutAttestation::decrypt_nostr_event(&forged, &creator),
+        Err(Error::Validation(message)) if message == "decrypted content does not match the commitment"
+    ));
+
+    // p tags must match the recipients of the ciphertexts
+    let retagged = |tags: Vec<::nostr::Tag>| {
+        ::nostr::EventBuilder::new(
+            PrivateEventPayoutAttestation::KIND,
+            nostr_event.content.clone(),
+            tags,
+        )
+        .to_event(&oracle)
+        .unwrap()
+    };
+    let untagged: Vec<::nostr::Tag> = nostr_event
+        .tags
+        .iter()
+        .filter(|tag| !matches!(tag.as_slice(), [kind, ..] if kind == "p"))
+        .cloned()
+        .collect();
+    let mut extra_tagged = nostr_event.tags.to_vec();
+    extra_tagged.push(::nostr::Tag::public_key(oracle.public_key));
+    for tags in [untagged, extra_tagged] {
+        assert!(matches!(
+            PrivateEventPayoutAttestation::interpret_nostr_event(&retagged(tags)),
+            Err(Error::Validation(message)) if message == "p tags do not match the recipients of the private nostr event"
+        ));
+    }
+}
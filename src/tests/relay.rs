@@ -0,0 +1,219 @@
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use nostr::{
+    message::{ClientMessage, RelayMessage, SubscriptionId},
+    util::JsonUtil,
+};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+use super::util::sign;
+use crate::{
+    information::{Information, V1},
+    nostr_event_types::{
+        AttestationCorrection, EventPayoutAttestation, EventPayoutAttestationCorrection, NewEvent,
+        NostrEventIdHex, NostrEventUtils, OracleProfileAnnouncement,
+    },
+    relay::Relay,
+    Event, EventPayout, OracleProfile,
+};
+
+type Ws = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+async fn send(ws: &mut Ws, message: ClientMessage) {
+    ws.send(Message::text(message.as_json())).await.unwrap();
+}
+
+async fn receive(ws: &mut Ws) -> RelayMessage {
+    loop {
+        let message = tokio::time::timeout(Duration::from_secs(5), ws.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        if let Message::Text(text) = message {
+            return RelayMessage::from_json(text.as_str()).unwrap();
+        }
+    }
+}
+
+async fn publish(ws: &mut Ws, nostr_event: &::nostr::Event) -> (bool, String) {
+    send(ws, ClientMessage::event(nostr_event.clone())).await;
+    match receive(ws).await {
+        RelayMessage::Ok {
+            event_id,
+            status,
+            message,
+        } if event_id == nostr_event.id => (status, message),
+        message => panic!("expected OK, got {message:?}"),
+    }
+}
+
+#[tokio::test]
+async fn relay_ingest_1() {
+    let handle = Relay::in_memory().spawn("127.0.0.1:0").await.unwrap();
+    let (mut ws, _) = connect_async(handle.url()).await.unwrap();
+    let keys = ::nostr::Keys::generate();
+
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let new_event = sign(
+        NewEvent::create_nostr_event_builder(&event).unwrap(),
+        &keys,
+        1000,
+    );
+    assert_eq!(publish(&mut ws, &new_event).await, (true, String::new()));
+    // duplicates are accepted
+    assert_eq!(publish(&mut ws, &new_event).await, (true, String::new()));
+
+    let malformed = sign(
+        ::nostr::EventBuilder::new(NewEvent::KIND, "not an event", []),
+        &keys,
+        1000,
+    );
+    let (status, message) = publish(&mut ws, &malformed).await;
+    assert!(!status);
+    assert!(message.starts_with("invalid: "), "{message}");
+
+    let invalid_payout = EventPayout::new(&event, vec![1, 0]).unwrap();
+    let attestation = sign(
+        EventPayoutAttestation::create_nostr_event_builder(&invalid_payout).unwrap(),
+        &keys,
+        1001,
+    );
+    let (status, message) = publish(&mut ws, &attestation).await;
+    assert!(!status);
+    assert!(
+        message.starts_with("invalid: validation failed"),
+        "{message}"
+    );
+
+    let subscription_id = SubscriptionId::new("attestations");
+    send(
+        &mut ws,
+        ClientMessage::req(subscription_id.clone(), vec![NewEvent::filter()]),
+    )
+    .await;
+    assert_eq!(
+        receive(&mut ws).await,
+        RelayMessage::event(subscription_id.clone(), new_event)
+    );
+    assert_eq!(
+        receive(&mut ws).await,
+        RelayMessage::eose(subscription_id.clone())
+    );
+
+    // nostr events published on another connection are forwarded
+    let (mut other_ws, _) = connect_async(handle.url()).await.unwrap();
+    let live_event = Event::new_with_random_nonce(3, 10, Information::None);
+    let live = sign(
+        NewEvent::create_nostr_event_builder(&live_event).unwrap(),
+        &keys,
+        1002,
+    );
+    assert!(publish(&mut other_ws, &live).await.0);
+    assert_eq!(
+        receive(&mut ws).await,
+        RelayMessage::event(subscription_id, live)
+    );
+}
+
+#[tokio::test]
+async fn relay_disk_store_1() {
+    let path = std::env::temp_dir().join(format!(
+        "prediction-market-relay-{}.jsonl",
+        rand::random::<u64>()
+    ));
+    let keys = ::nostr::Keys::generate();
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let new_event = sign(
+        NewEvent::create_nostr_event_builder(&event).unwrap(),
+        &keys,
+        1000,
+    );
+    let attestation = sign(
+        EventPayoutAttestation::create_nostr_event_builder(
+            &EventPayout::new(&event, vec![10, 0]).unwrap(),
+        )
+        .unwrap(),
+        &keys,
+        1001,
+    );
+    let deletion = sign(
+        EventPayoutAttestationCorrection::create_nostr_deletion_event_builder(
+            &AttestationCorrection {
+                corrected_nostr_event_id: NostrEventIdHex(attestation.id.to_hex()),
+                event_hash_hex: event.hash_hex().unwrap(),
+                units_per_outcome: Option::None,
+                reason: "wrong event".into(),
+            },
+        )
+        .unwrap(),
+        &keys,
+        1002,
+    );
+    let profile = |name: &str| OracleProfile {
+        name: name.into(),
+        information_variant_ids: vec![V1::ID.into()],
+        categories: vec![],
+        fee_schedule: vec![],
+        typical_resolution_latency_seconds: Option::None,
+        policy: Option::None,
+        contact: Option::None,
+        relays: vec![],
+    };
+    let old_profile = sign(
+        OracleProfileAnnouncement::create_nostr_event_builder(&profile("old")).unwrap(),
+        &keys,
+        1000,
+    );
+    let new_profile = sign(
+        OracleProfileAnnouncement::create_nostr_event_builder(&profile("new")).unwrap(),
+        &keys,
+        1001,
+    );
+
+    {
+        let relay = Relay::open(&path).unwrap();
+        for nostr_event in [
+            &new_event,
+            &attestation,
+            &deletion,
+            &old_profile,
+            &new_profile,
+        ] {
+            assert_eq!(relay.ingest(nostr_event.clone()).await, Ok(()));
+        }
+        assert!(relay.ingest(old_profile.clone()).await.is_err());
+        assert!(relay.ingest(attestation.clone()).await.is_err());
+    }
+
+    let relay = Relay::open(&path).unwrap();
+    let stored = relay.query(&[::nostr::Filter::new()]);
+    assert_eq!(stored, vec![deletion, new_profile, new_event]);
+    let limited = relay.query(&[::nostr::Filter::new().limit(1)]);
+    assert_eq!(limited.len(), 1);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "client")]
+#[tokio::test]
+async fn relay_client_1() {
+    use crate::{client::Client, Error};
+
+    let handle = Relay::in_memory().spawn("127.0.0.1:0").await.unwrap();
+    let (client, errors) = Client::connect(&[handle.url()]).await;
+    assert!(errors.is_empty());
+
+    let keys = ::nostr::Keys::generate();
+    let event = Event::new_with_random_nonce(1, 10, Information::None);
+    let (_, results) = client
+        .publish_with_signer::<NewEvent, _>(&event, &keys)
+        .await
+        .unwrap();
+    assert!(matches!(
+        &results[..],
+        [(_, Err(Error::Relay { message, .. }))] if message.starts_with("rejected: invalid: ")
+    ));
+}
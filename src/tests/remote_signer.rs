@@ -0,0 +1,156 @@
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use nostr::{
+    nips::{
+        nip44,
+        nip46::{Message as NostrConnectMessage, Request, ResponseResult},
+    },
+    types::Filter,
+    util::JsonUtil,
+    Kind, Tag,
+};
+
+use super::client::spawn_test_relay;
+use crate::{
+    client::{Client, SubscriptionItem},
+    information::Information,
+    nostr_event_types::{EventPayoutAttestation, NostrEventUtils},
+    remote_signer::RemoteSigner,
+    Error, Event, EventPayout,
+};
+
+/// Bunker stand-in answering NIP-46 requests for user_keys on relay_url, returns its `bunker://` uri.
+/// Signs a different content than requested if tamper is true.
+async fn spawn_test_bunker(relay_url: &str, user_keys: ::nostr::Keys, tamper: bool) -> String {
+    let (client, errors) = Client::connect(&[relay_url.to_string()]).await;
+    assert!(errors.is_empty());
+    let mut requests = client.subscribe_nostr_events(vec![Filter::new()
+        .kind(Kind::NostrConnect)
+        .pubkey(user_keys.public_key)]);
+    // the subscription is open once the relay sent its stored nostr events
+    assert!(matches!(
+        requests.next().await,
+        Some(SubscriptionItem::EndOfStoredEvents { .. })
+    ));
+
+    let bunker_uri = format!(
+        "bunker://{}?relay={relay_url}&secret=test-secret",
+        user_keys.public_key.to_hex()
+    );
+    tokio::spawn(async move {
+        while let Some(item) = requests.next().await {
+            let SubscriptionItem::Interpreted { nostr_event, .. } = item else {
+                continue;
+            };
+            let json = nip44::decrypt(
+                user_keys.secret_key(),
+                &nostr_event.pubkey,
+                &nostr_event.content,
+            )
+            .unwrap();
+            let NostrConnectMessage::Request { id, req } =
+                NostrConnectMessage::from_json(json).unwrap()
+            else {
+                continue;
+            };
+            let (result, error) = match req {
+                Request::Connect { secret, .. } if secret.as_deref() == Some("test-secret") => {
+                    (Some(ResponseResult::Connect), Option::None)
+                }
+                Request::Connect { .. } => (Option::None, Some("invalid secret".to_string())),
+                Request::GetPublicKey => (
+                    Some(ResponseResult::GetPublicKey(user_keys.public_key)),
+                    Option::None,
+                ),
+                Request::SignEvent(mut unsigned_event) => {
+                    if tamper {
+                        unsigned_event.content.push_str(" tampered");
+                        unsigned_event.id = Option::None;
+                    }
+                    let signed = unsigned_event.sign(&user_keys).unwrap();
+                    (
+                        Some(ResponseResult::SignEvent(Box::new(signed))),
+                        Option::None,
+                    )
+                }
+                Request::Ping => (Some(ResponseResult::Pong), Option::None),
+                _ => (Option::None, Some("unsupported".to_string())),
+            };
+            let response = NostrConnectMessage::response(id, result, error);
+            let content = nip44::encrypt(
+                user_keys.secret_key(),
+                &nostr_event.pubkey,
+                response.as_json(),
+                nip44::Version::V2,
+            )
+            .unwrap();
+            let response_event = ::nostr::EventBuilder::new(
+                Kind::NostrConnect,
+                content,
+                [Tag::public_key(nostr_event.pubkey)],
+            )
+            .to_event(&user_keys)
+            .unwrap();
+            client.publish_nostr_event(&response_event).await;
+        }
+    });
+
+    bunker_uri
+}
+
+#[tokio::test]
+async fn remote_signer_1() {
+    let relay_url = spawn_test_relay(true).await;
+    let oracle_keys = ::nostr::Keys::generate();
+    let bunker_uri = spawn_test_bunker(&relay_url, oracle_keys.clone(), false).await;
+
+    let remote_signer = RemoteSigner::connect(&bunker_uri).await.unwrap();
+    assert_eq!(remote_signer.user_public_key(), oracle_keys.public_key);
+    remote_signer.ping().await.unwrap();
+
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let event_payout = EventPayout::new(&event, vec![10, 0]).unwrap();
+    let nostr_event =
+        EventPayoutAttestation::create_nostr_signed_event_async(&event_payout, &remote_signer)
+            .await
+            .unwrap();
+    assert_eq!(nostr_event.pubkey, oracle_keys.public_key);
+    let (_, interpreted_event_payout) =
+        EventPayoutAttestation::interpret_nostr_event(&nostr_event).unwrap();
+    assert_eq!(interpreted_event_payout, event_payout);
+
+    // wrong secret
+    let wrong_secret_uri = bunker_uri.replace("test-secret", "wrong");
+    assert!(matches!(
+        RemoteSigner::connect(&wrong_secret_uri).await,
+        Err(Error::Signer(message)) if message == "rejected: invalid secret"
+    ));
+}
+
+#[tokio::test]
+async fn remote_signer_tampered_1() {
+    let relay_url = spawn_test_relay(true).await;
+    let oracle_keys = ::nostr::Keys::generate();
+    let bunker_uri = spawn_test_bunker(&relay_url, oracle_keys, true).await;
+    let remote_signer = RemoteSigner::connect_with_timeout(&bunker_uri, Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let result = EventPayoutAttestation::create_nostr_signed_event_async(
+        &EventPayout::new(&event, vec![10, 0]).unwrap(),
+        &remote_signer,
+    )
+    .await;
+    assert!(matches!(
+        result,
+        Err(Error::Signer(message)) if message == "remote signer returned a different nostr event"
+    ));
+
+    // not a bunker uri
+    assert!(matches!(
+        RemoteSigner::connect(&relay_url).await,
+        Err(Error::Signer(_))
+    ));
+}
@@ -0,0 +1,128 @@
+use nostr::util::JsonUtil;
+use zeroize::Zeroizing;
+
+use crate::{
+    information::Information,
+    nostr_event_types::{NewEvent, NostrEventUtils},
+    signer::{AsyncSigner, Signer, TestSigner, ZeroizingKeys},
+    Error, Event,
+};
+
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let mut context = std::task::Context::from_waker(std::task::Waker::noop());
+    match future.as_mut().poll(&mut context) {
+        std::task::Poll::Ready(output) => output,
+        std::task::Poll::Pending => panic!("signer future is pending"),
+    }
+}
+
+fn assert_signs<S: Signer>(signer: &S) {
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let nostr_event = NewEvent::create_nostr_signed_event(&event, signer).unwrap();
+    assert_eq!(nostr_event.pubkey, signer.public_key().unwrap());
+    assert_eq!(
+        NewEvent::interpret_nostr_event(&nostr_event).unwrap(),
+        event
+    );
+
+    let json = NewEvent::create_nostr_signed_event_json_with_signer(&event, signer).unwrap();
+    assert_eq!(NewEvent::interpret_nostr_event_json(&json).unwrap(), event);
+}
+
+#[test]
+fn signer_1() {
+    let keys = ::nostr::Keys::generate();
+    assert_signs(&keys);
+
+    let zeroizing_keys = ZeroizingKeys::parse(&keys.secret_key().to_secret_hex()).unwrap();
+    assert_eq!(zeroizing_keys.public_key().unwrap(), keys.public_key);
+    assert_signs(&zeroizing_keys);
+    let zeroizing_keys =
+        ZeroizingKeys::from_bytes(Zeroizing::new(keys.secret_key().secret_bytes())).unwrap();
+    assert_eq!(zeroizing_keys.public_key().unwrap(), keys.public_key);
+    assert!(matches!(
+        ZeroizingKeys::from_bytes(Zeroizing::new([0; 32])),
+        Err(Error::NostrKey(_))
+    ));
+    assert!(!format!("{zeroizing_keys:?}").contains(&keys.secret_key().to_secret_hex()));
+
+    let signer = TestSigner::new("signer_1");
+    assert_eq!(
+        TestSigner::new("signer_1").public_key().unwrap(),
+        signer.public_key().unwrap()
+    );
+    assert_signs(&signer);
+    assert_eq!(signer.signed_event_ids().len(), 2);
+
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    assert!(matches!(
+        NewEvent::create_nostr_signed_event(&event, &TestSigner::failing("signer_1")),
+        Err(Error::Signer(_))
+    ));
+
+    // the secret key string path signs the same as the Keys signer
+    let json = NewEvent::create_nostr_signed_event_json(&event, &keys.secret_key().to_secret_hex())
+        .unwrap();
+    assert_eq!(
+        ::nostr::Event::from_json(json).unwrap().pubkey,
+        keys.public_key
+    );
+}
+
+#[test]
+fn signer_async_1() {
+    let signer = TestSigner::new("signer_async_1");
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+
+    let nostr_event = block_on(NewEvent::create_nostr_signed_event_async(&event, &signer)).unwrap();
+    assert_eq!(
+        nostr_event.pubkey,
+        block_on(signer.public_key_async()).unwrap()
+    );
+    assert_eq!(signer.signed_event_ids(), vec![nostr_event.id]);
+    assert_eq!(
+        NewEvent::interpret_nostr_event(&nostr_event).unwrap(),
+        event
+    );
+}
+
+#[cfg(feature = "key-storage")]
+#[test]
+fn signer_key_storage_1() {
+    // NIP-06 test vector
+    let keys = ZeroizingKeys::from_mnemonic(
+        "leader monkey parrot ring guide accident before fence cannon height naive bean",
+        Option::None,
+        0,
+    )
+    .unwrap();
+    assert_eq!(
+        keys.public_key().unwrap().to_hex(),
+        "17162c921dc4d2518f9a101db33695df1afb56ab82f5ff3e5da6eec3ca5cd917"
+    );
+    let other_account = ZeroizingKeys::from_mnemonic(
+        "leader monkey parrot ring guide accident before fence cannon height naive bean",
+        Option::None,
+        1,
+    )
+    .unwrap();
+    assert_ne!(
+        other_account.public_key().unwrap(),
+        keys.public_key().unwrap()
+    );
+    assert!(matches!(
+        ZeroizingKeys::from_mnemonic("not a mnemonic", Option::None, 0),
+        Err(Error::KeyStorage(_))
+    ));
+
+    let ncryptsec = keys.to_ncryptsec("correct horse", 8).unwrap();
+    assert!(ncryptsec.starts_with("ncryptsec1"));
+    let decrypted = ZeroizingKeys::from_ncryptsec(&ncryptsec, "correct horse").unwrap();
+    assert_eq!(decrypted.public_key().unwrap(), keys.public_key().unwrap());
+    assert_signs(&decrypted);
+    assert!(matches!(
+        ZeroizingKeys::from_ncryptsec(&ncryptsec, "wrong horse"),
+        Err(Error::KeyStorage(_))
+    ));
+}
@@ -0,0 +1,74 @@
+use crate::{
+    information::{Information, V1},
+    nostr_event_types::{
+        EventPayoutAttestation, FutureEventPayoutAttestationPledge, NostrEventUtils,
+    },
+    AttestationPledge, AttestationPledgeTerms, Event, EventPayout, PayoutUnit,
+};
+
+/// Expected payout time of [v1_event].
+pub(super) const EXPECTED_PAYOUT: u64 = 1725388253;
+
+/// Attestation deadline of [pledge].
+pub(super) const DEADLINE: u64 = EXPECTED_PAYOUT + 3600;
+
+pub(super) fn sign(
+    builder: ::nostr::EventBuilder,
+    keys: &::nostr::Keys,
+    created_at: u64,
+) -> ::nostr::Event {
+    builder
+        .custom_created_at(::nostr::Timestamp::from(created_at))
+        .to_event(keys)
+        .unwrap()
+}
+
+pub(super) fn v1_event() -> Event {
+    Event::new_with_random_nonce(
+        2,
+        10,
+        Information::V1(V1 {
+            title: "title".to_string(),
+            description: "description".to_string(),
+            outcome_titles: vec!["yes".to_string(), "no".to_string()],
+            expected_payout_unix_seconds: EXPECTED_PAYOUT,
+        }),
+    )
+}
+
+pub(super) fn attestation(
+    event: &Event,
+    units_per_outcome: Vec<PayoutUnit>,
+    keys: &::nostr::Keys,
+    created_at: u64,
+) -> ::nostr::Event {
+    let event_payout = EventPayout::new(event, units_per_outcome).unwrap();
+    sign(
+        EventPayoutAttestation::create_nostr_event_builder(&event_payout).unwrap(),
+        keys,
+        created_at,
+    )
+}
+
+pub(super) fn pledge(
+    event: &Event,
+    keys: &::nostr::Keys,
+    created_at: u64,
+    expiration_unix_seconds: Option<u64>,
+) -> ::nostr::Event {
+    let pledge = AttestationPledge::new(
+        event,
+        Some(AttestationPledgeTerms {
+            attestation_deadline_unix_seconds: DEADLINE,
+            fee: Option::None,
+            resolution_methodology: Option::None,
+            expiration_unix_seconds,
+        }),
+    )
+    .unwrap();
+    sign(
+        FutureEventPayoutAttestationPledge::create_nostr_event_builder(&pledge).unwrap(),
+        keys,
+        created_at,
+    )
+}
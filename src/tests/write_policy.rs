@@ -0,0 +1,290 @@
+use nostr::util::JsonUtil;
+
+use crate::{
+    information::{Information, V1},
+    merkle::PayoutMerkleTree,
+    nostr_event_types::{
+        AttestationCorrection, BatchCommitment, EventPayoutAttestation,
+        EventPayoutAttestationCorrection, EventPayoutBatchAttestation,
+        FutureEventPayoutAttestationPledge, NewEvent, NostrEventIdHex, NostrEventUtils,
+        NostrPublicKeyHex, Private, PrivateEventPayoutAttestation, PrivateNewEvent,
+        ReferencedEventPayout,
+    },
+    write_policy::{WritePolicy, WritePolicyAction, WritePolicyConfig, WritePolicyOutput},
+    AttestationPledge, Event, EventPayout,
+};
+
+fn input_line(nostr_event: &::nostr::Event) -> String {
+    format!(
+        r#"{{"type":"new","event":{},"receivedAt":1700000000,"sourceType":"IP4","sourceInfo":"127.0.0.1"}}"#,
+        nostr_event.as_json()
+    )
+}
+
+fn decide(policy: &mut WritePolicy, nostr_event: &::nostr::Event) -> WritePolicyOutput {
+    let output = policy.handle_line(&input_line(nostr_event));
+    let output: WritePolicyOutput = serde_json::from_str(&output).unwrap();
+    assert_eq!(output.id, nostr_event.id.to_hex());
+    output
+}
+
+#[test]
+fn write_policy_1() {
+    let oracle_keys = ::nostr::Keys::generate();
+    let other_keys = ::nostr::Keys::generate();
+    let mut policy = WritePolicy::new(WritePolicyConfig {
+        allowed_oracles: vec![NostrPublicKeyHex(oracle_keys.public_key.to_hex())],
+        max_description_bytes: Some(16),
+        require_pledge: true,
+    });
+
+    let event = Event::new_with_random_nonce(
+        2,
+        10,
+        Information::V1(V1 {
+            title: "rain".into(),
+            description: "rain tomorrow".into(),
+            outcome_titles: vec!["yes".into(), "no".into()],
+            expected_payout_unix_seconds: 1700000000,
+        }),
+    );
+    let new_event = NewEvent::create_nostr_event_builder(&event)
+        .unwrap()
+        .to_event(&other_keys)
+        .unwrap();
+    assert_eq!(
+        decide(&mut policy, &new_event).action,
+        WritePolicyAction::Accept
+    );
+
+    let mut long_event = event.clone();
+    let Information::V1(information) = &mut long_event.information else {
+        unreachable!()
+    };
+    information.description = "rain tomorrow, measured in the city center".into();
+    let nostr_event = NewEvent::create_nostr_event_builder(&long_event)
+        .unwrap()
+        .to_event(&other_keys)
+        .unwrap();
+    let output = decide(&mut policy, &nostr_event);
+    assert_eq!(output.action, WritePolicyAction::Reject);
+    assert_eq!(output.msg, "invalid: description length is over max");
+
+    let attestation = |keys: &::nostr::Keys| {
+        EventPayoutAttestation::create_nostr_event_builder(
+            &EventPayout::new(&event, vec![10, 0]).unwrap(),
+        )
+        .unwrap()
+        .to_event(keys)
+        .unwrap()
+    };
+    let output = decide(&mut policy, &attestation(&other_keys));
+    assert_eq!(output.msg, "blocked: oracle is not allowed");
+    let output = decide(&mut policy, &attestation(&oracle_keys));
+    assert_eq!(
+        output.msg,
+        "blocked: oracle did not pledge to attest to the event"
+    );
+
+    let pledge = FutureEventPayoutAttestationPledge::create_nostr_event_builder(
+        &AttestationPledge::new(&event, Option::None).unwrap(),
+    )
+    .unwrap()
+    .to_event(&oracle_keys)
+    .unwrap();
+    assert_eq!(
+        decide(&mut policy, &pledge).action,
+        WritePolicyAction::Accept
+    );
+    assert_eq!(
+        decide(&mut policy, &attestation(&oracle_keys)).action,
+        WritePolicyAction::Accept
+    );
+
+    // the event is known, so the payout is validated against it
+    let invalid = EventPayoutAttestation::create_nostr_event_builder(
+        &EventPayout::new(&event, vec![1, 0]).unwrap(),
+    )
+    .unwrap()
+    .to_event(&oracle_keys)
+    .unwrap();
+    let output = decide(&mut policy, &invalid);
+    assert_eq!(output.action, WritePolicyAction::Reject);
+    assert!(output.msg.starts_with("invalid: "), "{}", output.msg);
+
+    // non market kinds pass through
+    let text_note = ::nostr::EventBuilder::text_note("hello", [])
+        .to_event(&other_keys)
+        .unwrap();
+    assert_eq!(
+        decide(&mut policy, &text_note).action,
+        WritePolicyAction::Accept
+    );
+}
+
+#[test]
+fn write_policy_require_pledge_1() {
+    let oracle_keys = ::nostr::Keys::generate();
+    let other_keys = ::nostr::Keys::generate();
+    let config = WritePolicyConfig {
+        allowed_oracles: vec![NostrPublicKeyHex(oracle_keys.public_key.to_hex())],
+        max_description_bytes: Option::None,
+        require_pledge: true,
+    };
+    let mut policy = WritePolicy::new(config.clone());
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+
+    let correction = |keys: &::nostr::Keys| {
+        EventPayoutAttestationCorrection::create_nostr_event_builder(&AttestationCorrection {
+            corrected_nostr_event_id: NostrEventIdHex(::nostr::EventId::all_zeros().to_hex()),
+            event_hash_hex: event.hash_hex().unwrap(),
+            units_per_outcome: Some(vec![0, 10]),
+            reason: "mistake".into(),
+        })
+        .unwrap()
+        .to_event(keys)
+        .unwrap()
+    };
+    let output = decide(&mut policy, &correction(&other_keys));
+    assert_eq!(output.msg, "blocked: oracle is not allowed");
+    let output = decide(&mut policy, &correction(&oracle_keys));
+    assert_eq!(
+        output.msg,
+        "blocked: oracle did not pledge to attest to the event"
+    );
+
+    let pledge = FutureEventPayoutAttestationPledge::create_nostr_event_builder(
+        &AttestationPledge::new(&event, Option::None).unwrap(),
+    )
+    .unwrap()
+    .to_event(&oracle_keys)
+    .unwrap();
+    assert_eq!(
+        decide(&mut policy, &pledge).action,
+        WritePolicyAction::Accept
+    );
+    assert_eq!(
+        decide(&mut policy, &correction(&oracle_keys)).action,
+        WritePolicyAction::Accept
+    );
+
+    // the events of a batch are not known to the relay
+    let tree = PayoutMerkleTree::new(vec![EventPayout::new(&event, vec![10, 0]).unwrap()]).unwrap();
+    let batch = |keys: &::nostr::Keys| {
+        EventPayoutBatchAttestation::create_nostr_event_builder(&BatchCommitment::from(&tree))
+            .unwrap()
+            .to_event(keys)
+            .unwrap()
+    };
+    let output = decide(&mut policy, &batch(&oracle_keys));
+    assert_eq!(output.action, WritePolicyAction::Reject);
+    assert_eq!(
+        output.msg,
+        "blocked: batch attestations can not be checked against pledges"
+    );
+
+    let mut policy = WritePolicy::new(WritePolicyConfig {
+        require_pledge: false,
+        ..config
+    });
+    let output = decide(&mut policy, &batch(&other_keys));
+    assert_eq!(output.msg, "blocked: oracle is not allowed");
+    assert_eq!(
+        decide(&mut policy, &batch(&oracle_keys)).action,
+        WritePolicyAction::Accept
+    );
+}
+
+#[test]
+fn write_policy_private_1() {
+    let oracle_keys = ::nostr::Keys::generate();
+    let other_keys = ::nostr::Keys::generate();
+    let mut policy = WritePolicy::new(WritePolicyConfig {
+        allowed_oracles: vec![NostrPublicKeyHex(oracle_keys.public_key.to_hex())],
+        max_description_bytes: Option::None,
+        require_pledge: true,
+    });
+    let event = Event::new_with_random_nonce(2, 10, Information::None);
+    let recipients = vec![NostrPublicKeyHex(other_keys.public_key.to_hex())];
+
+    // private new events are not oracle kinds
+    let private_new_event = PrivateNewEvent::create_nostr_signed_event(
+        &Private {
+            value: event.clone(),
+            recipients: recipients.clone(),
+        },
+        &other_keys,
+    )
+    .unwrap();
+    assert_eq!(
+        decide(&mut policy, &private_new_event).action,
+        WritePolicyAction::Accept
+    );
+
+    let private_attestation = |keys: &::nostr::Keys| {
+        PrivateEventPayoutAttestation::create_nostr_signed_event(
+            &Private {
+                value: ReferencedEventPayout::from(EventPayout::new(&event, vec![10, 0]).unwrap()),
+                recipients: recipients.clone(),
+            },
+            keys,
+        )
+        .unwrap()
+    };
+    let output = decide(&mut policy, &private_attestation(&other_keys));
+    assert_eq!(output.msg, "blocked: oracle is not allowed");
+    let output = decide(&mut policy, &private_attestation(&oracle_keys));
+    assert_eq!(
+        output.msg,
+        "blocked: oracle did not pledge to attest to the event"
+    );
+
+    let pledge = FutureEventPayoutAttestationPledge::create_nostr_event_builder(
+        &AttestationPledge::new(&event, Option::None).unwrap(),
+    )
+    .unwrap()
+    .to_event(&oracle_keys)
+    .unwrap();
+    assert_eq!(
+        decide(&mut policy, &pledge).action,
+        WritePolicyAction::Accept
+    );
+    assert_eq!(
+        decide(&mut policy, &private_attestation(&oracle_keys)).action,
+        WritePolicyAction::Accept
+    );
+
+    // the envelope is interpreted
+    let mut tags = private_new_event.tags.to_vec();
+    tags.push(::nostr::Tag::public_key(oracle_keys.public_key));
+    let retagged = ::nostr::EventBuilder::new(
+        PrivateNewEvent::KIND,
+        private_new_event.content.clone(),
+        tags,
+    )
+    .to_event(&other_keys)
+    .unwrap();
+    let output = decide(&mut policy, &retagged);
+    assert_eq!(output.action, WritePolicyAction::Reject);
+    assert!(output.msg.starts_with("invalid: "), "{}", output.msg);
+}
+
+#[test]
+fn write_policy_malformed_input_1() {
+    let mut policy = WritePolicy::new(WritePolicyConfig::default());
+
+    let output: WritePolicyOutput = serde_json::from_str(&policy.handle_line("not json")).unwrap();
+    assert_eq!(output.id, "");
+    assert_eq!(output.action, WritePolicyAction::Reject);
+
+    let output: WritePolicyOutput =
+        serde_json::from_str(&policy.handle_line(r#"{"type":"new","event":{"id":"abcd"}}"#))
+            .unwrap();
+    assert_eq!(output.id, "abcd");
+    assert_eq!(output.action, WritePolicyAction::Reject);
+    assert!(output.msg.starts_with("error: "));
+
+    let config: WritePolicyConfig = serde_json::from_str(r#"{"require_pledge":true}"#).unwrap();
+    assert!(config.require_pledge);
+    assert!(config.allowed_oracles.is_empty());
+}
@@ -0,0 +1,217 @@
+//! Write policy for relays that consult an external plugin over json lines on stdin and stdout, like strfry.
+//!
+//! Used by the `prediction-market-write-policy` binary. Every input line is answered by exactly one output line,
+//! see [WritePolicy::handle_line].
+
+use std::collections::HashSet;
+
+use nostr::event::Event as NostrEvent;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    information::Information,
+    ingest::IngestValidator,
+    nostr_event_types::{
+        EventPayoutAttestation, EventPayoutAttestationCorrection, EventPayoutAttestationDispute,
+        EventPayoutBatchAttestation, FutureEventPayoutAttestationPledge, NewEvent, NostrEventUtils,
+        NostrPublicKeyHex, OracleProfileAnnouncement, PrivateEventPayoutAttestation,
+        PrivateNewEvent,
+    },
+    EventHashHex,
+};
+
+/// Configuration of [WritePolicy], read from a json file by the binary.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct WritePolicyConfig {
+    /// Oracles allowed to publish pledges, attestations, private attestations, batch attestations, corrections and oracle profiles. Empty allows every oracle.
+    pub allowed_oracles: Vec<NostrPublicKeyHex>,
+
+    /// Max length in bytes of the description of [crate::information::V1].
+    pub max_description_bytes: Option<usize>,
+
+    /// Only accept an attestation, private attestation or correction if its oracle pledged to the same [crate::Event] before.
+    /// Batch attestations are rejected, the relay can not see which [crate::Event]s they attest to.
+    pub require_pledge: bool,
+}
+
+/// One input line, a nostr event the relay received.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WritePolicyInput {
+    /// `new` for nostr events received from clients, `lookback` for stored nostr events replayed on startup.
+    #[serde(rename = "type")]
+    pub input_type: String,
+    pub event: NostrEvent,
+    #[serde(default)]
+    pub received_at: u64,
+    #[serde(default)]
+    pub source_type: String,
+    #[serde(default)]
+    pub source_info: String,
+}
+
+/// One output line, the decision about a [WritePolicyInput].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WritePolicyOutput {
+    /// Id of the nostr event as hex.
+    pub id: String,
+    pub action: WritePolicyAction,
+    /// Reason sent to the client in the `OK` message, empty if accepted.
+    pub msg: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum WritePolicyAction {
+    Accept,
+    Reject,
+    ShadowReject,
+}
+
+/// Decides which nostr events a relay accepts.
+///
+/// Nostr events of kinds not in [crate::nostr_event_types] are accepted without checks. Market kinds are validated with
+/// [IngestValidator] and [WritePolicyConfig]. Accepted [NewEvent]s and pledges are remembered for later decisions,
+/// so the relay should replay its stored nostr events with `lookback` lines when the plugin starts.
+#[derive(Debug, Clone)]
+pub struct WritePolicy {
+    config: WritePolicyConfig,
+    validator: IngestValidator,
+    pledges: HashSet<(NostrPublicKeyHex, EventHashHex)>,
+}
+
+impl WritePolicy {
+    pub fn new(config: WritePolicyConfig) -> Self {
+        Self {
+            config,
+            validator: IngestValidator::new(),
+            pledges: HashSet::new(),
+        }
+    }
+
+    /// Decide about nostr_event and remember it if it is accepted.
+    ///
+    /// Returns the NIP-01 `OK` message with machine readable prefix if nostr_event should be rejected.
+    pub fn decide(&mut self, nostr_event: &NostrEvent) -> Result<(), String> {
+        let kind = nostr_event.kind;
+        if kind == NewEvent::KIND {
+            self.validator.validate(nostr_event)?;
+            self.check_description(nostr_event)?;
+        } else if kind == FutureEventPayoutAttestationPledge::KIND {
+            self.validator.validate(nostr_event)?;
+            self.check_oracle(nostr_event)?;
+            if let Ok((oracle, pledge)) =
+                FutureEventPayoutAttestationPledge::interpret_nostr_event(nostr_event)
+            {
+                self.pledges.insert((oracle, pledge.event_hash_hex));
+            }
+        } else if kind == EventPayoutAttestation::KIND
+            || kind == EventPayoutAttestationCorrection::KIND
+            || kind == EventPayoutBatchAttestation::KIND
+            || kind == PrivateEventPayoutAttestation::KIND
+        {
+            self.validator.validate(nostr_event)?;
+            self.check_oracle(nostr_event)?;
+            self.check_pledge(nostr_event)?;
+        } else if kind == OracleProfileAnnouncement::KIND {
+            self.validator.validate(nostr_event)?;
+            self.check_oracle(nostr_event)?;
+        } else if kind == EventPayoutAttestationDispute::KIND || kind == PrivateNewEvent::KIND {
+            self.validator.validate(nostr_event)?;
+        } else {
+            return Ok(());
+        }
+        self.validator.accept(nostr_event);
+
+        Ok(())
+    }
+
+    /// Decide about one input line and return the output line, without trailing newline.
+    ///
+    /// Lines that can not be parsed are rejected, with an empty id if it can not be read either.
+    pub fn handle_line(&mut self, line: &str) -> String {
+        let output = match serde_json::from_str::<WritePolicyInput>(line) {
+            Ok(input) => {
+                let id = input.event.id.to_hex();
+                match self.decide(&input.event) {
+                    Ok(()) => WritePolicyOutput {
+                        id,
+                        action: WritePolicyAction::Accept,
+                        msg: String::new(),
+                    },
+                    Err(msg) => WritePolicyOutput {
+                        id,
+                        action: WritePolicyAction::Reject,
+                        msg,
+                    },
+                }
+            }
+            Err(e) => {
+                let id = serde_json::from_str::<serde_json::Value>(line)
+                    .ok()
+                    .and_then(|v| v["event"]["id"].as_str().map(str::to_owned))
+                    .unwrap_or_default();
+                WritePolicyOutput {
+                    id,
+                    action: WritePolicyAction::Reject,
+                    msg: format!("error: could not parse input: {e}"),
+                }
+            }
+        };
+
+        serde_json::to_string(&output).expect("output serializes")
+    }
+
+    fn check_oracle(&self, nostr_event: &NostrEvent) -> Result<(), String> {
+        let oracle = NostrPublicKeyHex(nostr_event.pubkey.to_hex());
+        if !self.config.allowed_oracles.is_empty() && !self.config.allowed_oracles.contains(&oracle)
+        {
+            return Err("blocked: oracle is not allowed".to_string());
+        }
+
+        Ok(())
+    }
+
+    fn check_description(&self, nostr_event: &NostrEvent) -> Result<(), String> {
+        let Some(max) = self.config.max_description_bytes else {
+            return Ok(());
+        };
+        let event =
+            NewEvent::interpret_nostr_event(nostr_event).map_err(|e| format!("invalid: {e}"))?;
+        if let Information::V1(information) = &event.information {
+            if information.description.len() > max {
+                return Err("invalid: description length is over max".to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check that the oracle of a nostr event carrying a payout pledged to its [crate::Event].
+    fn check_pledge(&self, nostr_event: &NostrEvent) -> Result<(), String> {
+        if !self.config.require_pledge {
+            return Ok(());
+        }
+        let pledged = if nostr_event.kind == EventPayoutAttestation::KIND {
+            EventPayoutAttestation::interpret_nostr_event(nostr_event)
+                .map(|(oracle, event_payout)| (oracle, event_payout.event_hash_hex))
+        } else if nostr_event.kind == EventPayoutAttestationCorrection::KIND {
+            EventPayoutAttestationCorrection::interpret_nostr_event(nostr_event)
+                .map(|(oracle, correction)| (oracle, correction.event_hash_hex))
+        } else if nostr_event.kind == PrivateEventPayoutAttestation::KIND {
+            PrivateEventPayoutAttestation::interpret_nostr_event(nostr_event)
+                .map(|envelope| (envelope.author, envelope.event_hash_hex))
+        } else {
+            return Err(
+                "blocked: batch attestations can not be checked against pledges".to_string(),
+            );
+        };
+        let pledged = pledged.map_err(|e| format!("invalid: {e}"))?;
+        if !self.pledges.contains(&pledged) {
+            return Err("blocked: oracle did not pledge to attest to the event".to_string());
+        }
+
+        Ok(())
+    }
+}